@@ -1,6 +1,8 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rfgrep::app::{FileFilter, FileFilterOptions};
 use rfgrep::search_algorithms::SearchAlgorithm;
 use rfgrep::streaming_search::{StreamingConfig, StreamingSearchPipeline};
+use rfgrep::walker::{walk_dir_with_options, walk_files, WalkerOptions};
 
 use std::fs;
 use std::path::Path;
@@ -24,6 +26,12 @@ fn criterion_benchmark(c: &mut Criterion) {
 
     // Benchmark memory usage
     benchmark_memory_usage(c, &test_dir);
+
+    // Benchmark mapped vs streamed reads of a large file
+    benchmark_mmap(c, &test_dir);
+
+    // Benchmark walking, filtering and sorting a tree
+    benchmark_walk(c);
 }
 
 fn generate_test_data(test_dir: &Path) {
@@ -71,13 +79,15 @@ fn benchmark_algorithms(c: &mut Criterion, test_dir: &Path) {
     for (name, algorithm) in algorithms {
         let config = StreamingConfig {
             algorithm,
-            context_lines: 0,
+            context_before: 0,
+            context_after: 0,
             case_sensitive: true,
             invert_match: false,
             max_matches: None,
             timeout_per_file: None,
             chunk_size: 8192,
             buffer_size: 65536,
+            ..Default::default()
         };
 
         let pipeline = StreamingSearchPipeline::new(config);
@@ -117,13 +127,15 @@ fn benchmark_file_sizes(c: &mut Criterion, test_dir: &Path) {
 
         let config = StreamingConfig {
             algorithm: SearchAlgorithm::BoyerMoore,
-            context_lines: 0,
+            context_before: 0,
+            context_after: 0,
             case_sensitive: true,
             invert_match: false,
             max_matches: None,
             timeout_per_file: None,
             chunk_size: 8192,
             buffer_size: 65536,
+            ..Default::default()
         };
 
         let pipeline = StreamingSearchPipeline::new(config);
@@ -159,13 +171,15 @@ fn benchmark_patterns(c: &mut Criterion, test_dir: &Path) {
 
         let config = StreamingConfig {
             algorithm,
-            context_lines: 0,
+            context_before: 0,
+            context_after: 0,
             case_sensitive: true,
             invert_match: false,
             max_matches: None,
             timeout_per_file: None,
             chunk_size: 8192,
             buffer_size: 65536,
+            ..Default::default()
         };
 
         let pipeline = StreamingSearchPipeline::new(config);
@@ -196,13 +210,15 @@ fn benchmark_patterns(c: &mut Criterion, test_dir: &Path) {
 fn benchmark_memory_usage(c: &mut Criterion, test_dir: &Path) {
     let config = StreamingConfig {
         algorithm: SearchAlgorithm::BoyerMoore,
-        context_lines: 0,
+        context_before: 0,
+        context_after: 0,
         case_sensitive: true,
         invert_match: false,
         max_matches: None,
         timeout_per_file: None,
         chunk_size: 8192,
         buffer_size: 65536,
+        ..Default::default()
     };
 
     let pipeline = StreamingSearchPipeline::new(config);
@@ -226,5 +242,83 @@ fn benchmark_memory_usage(c: &mut Criterion, test_dir: &Path) {
     });
 }
 
+fn benchmark_mmap(c: &mut Criterion, test_dir: &Path) {
+    let path = test_dir.join("mmap_large.log");
+    let content: String = (0..500_000)
+        .map(|i| {
+            if i % 1000 == 0 {
+                format!("{i} ERROR something failed\n")
+            } else {
+                format!("{i} INFO nothing to see here\n")
+            }
+        })
+        .collect();
+    fs::write(&path, content).unwrap();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("large_file_read");
+    for (name, mmap_threshold) in [("mmap", 0), ("streaming", u64::MAX)] {
+        let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+            context_before: 0,
+            context_after: 0,
+            mmap_threshold,
+            ..Default::default()
+        });
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let matches = rt.block_on(pipeline.search_file(&path, "ERROR")).unwrap();
+                assert_eq!(matches.len(), 500);
+            });
+        });
+    }
+    group.finish();
+}
+
+fn benchmark_walk(c: &mut Criterion) {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    for dir in 0..20 {
+        let dir_path = temp_dir.path().join(format!("dir_{dir}"));
+        fs::create_dir(&dir_path).unwrap();
+        for file in 0..100 {
+            let name = if file % 4 == 0 { "bin" } else { "txt" };
+            fs::write(
+                dir_path.join(format!("file_{file}.{name}")),
+                "x".repeat(file * 10),
+            )
+            .unwrap();
+        }
+    }
+    let root = temp_dir.path();
+    let filter = FileFilter::new(FileFilterOptions {
+        max_size: Some(1),
+        exclude_extensions: Some(vec!["bin".to_string()]),
+        ..Default::default()
+    });
+
+    // Each stage stats the file again, as the walker used to
+    let stat_per_stage = || {
+        let files: Vec<_> = walk_dir_with_options(root, WalkerOptions::default())
+            .filter(|entry| entry.path().is_file())
+            .map(|entry| entry.path().to_path_buf())
+            .collect();
+        let mut files = filter.filter_files(files);
+        files.sort_by_key(|path| (path.metadata().map(|m| m.len()).unwrap_or(0), path.clone()));
+        files
+    };
+    // One stat while walking, reused by the filter and the sort
+    let single_stat = || {
+        let mut files = filter.filter_entries(walk_files(root, WalkerOptions::default()).collect());
+        files.sort_by(|a, b| (a.metadata.len(), &a.path).cmp(&(b.metadata.len(), &b.path)));
+        files.into_iter().map(|file| file.path).collect::<Vec<_>>()
+    };
+    assert_eq!(stat_per_stage(), single_stat());
+    assert_eq!(single_stat().len(), 20 * 75);
+
+    let mut group = c.benchmark_group("walk_filter_sort");
+    group.bench_function("stat_per_stage", |b| b.iter(stat_per_stage));
+    group.bench_function("single_stat", |b| b.iter(single_stat));
+    group.finish();
+}
+
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
@@ -46,6 +46,29 @@ fn benchmark_simd_vs_boyermoore(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_boyer_moore_pathological(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Boyer-Moore Pathological");
+
+    // Long runs of a single byte force repeated near-complete suffix matches,
+    // which is where the good-suffix shifts pay off
+    let text = "a".repeat(1_048_576);
+    for pattern in ["aaaaab", "baaaaa"] {
+        group.bench_with_input(
+            BenchmarkId::new("Boyer-Moore", pattern),
+            &(&text, pattern),
+            |b, (text, pattern)| {
+                let searcher = BoyerMoore::new(pattern);
+                b.iter(|| {
+                    let results = searcher.search(text, pattern);
+                    black_box(results);
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
 fn benchmark_pattern_lengths(c: &mut Criterion) {
     let mut group = c.benchmark_group("Pattern Length Impact");
 
@@ -191,6 +214,7 @@ fn benchmark_file_operations(c: &mut Criterion) {
 criterion_group!(
     benches,
     benchmark_simd_vs_boyermoore,
+    benchmark_boyer_moore_pathological,
     benchmark_pattern_lengths,
     benchmark_real_world_scenarios,
     benchmark_match_frequency,
@@ -2,38 +2,76 @@
 ///
 /// This module provides comprehensive file filtering capabilities including:
 /// - Extension-based filtering (include/exclude)
+/// - Glob-based path filtering (include/exclude)
+/// - Regex-based path exclusion
 /// - Size-based filtering
 /// - Safety policy enforcement
 /// - File type strategy application
 /// - Binary file detection
-use crate::cli::{FileTypeStrategy, SafetyPolicy};
-use crate::file_types::{FileTypeClassifier, SearchDecision};
-use std::path::Path;
+use crate::cli::{BinaryMode, FileTypeStrategy, SafetyPolicy};
+use crate::file_types::{has_extension, FileTypeClassifier, SearchDecision};
+use crate::walker::FileEntry;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use regex::RegexSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 /// Configuration options for file filtering
 #[derive(Debug, Clone)]
 pub struct FileFilterOptions {
+    /// Deprecated megabyte limit, used only without `max_filesize`
     pub max_size: Option<usize>,
+    /// Skip files larger than this many bytes
+    pub max_filesize: Option<u64>,
+    /// Skip files smaller than this many bytes
+    pub min_filesize: Option<u64>,
     pub skip_binary: bool,
+    /// Anything but `Skip` asks for binary files, so they aren't dropped by
+    /// their type before the search gets to see them
+    pub binary_mode: BinaryMode,
     pub safety_policy: SafetyPolicy,
     pub include_extensions: Option<Vec<String>>,
     pub exclude_extensions: Option<Vec<String>>,
+    /// Match extensions exactly instead of ignoring ASCII case
+    pub case_sensitive_extensions: bool,
+    /// Globs a file's path must match to be searched
+    pub include_globs: Vec<String>,
+    /// Globs that exclude a file even if it matches `include_globs`
+    pub exclude_globs: Vec<String>,
+    /// Directory that glob patterns are matched relative to
+    pub glob_root: Option<PathBuf>,
+    /// Regexes that exclude any file whose path they match
+    pub exclude_patterns: Vec<String>,
     pub search_all_files: bool,
     pub text_only: bool,
     pub file_types: FileTypeStrategy,
+    /// Only files modified after this time
+    pub newer_than: Option<SystemTime>,
+    /// Only files modified before this time
+    pub older_than: Option<SystemTime>,
 }
 
 impl Default for FileFilterOptions {
     fn default() -> Self {
         Self {
             max_size: None,
+            max_filesize: None,
+            min_filesize: None,
             skip_binary: false,
+            binary_mode: BinaryMode::Skip,
             safety_policy: SafetyPolicy::Default,
             include_extensions: None,
             exclude_extensions: None,
+            case_sensitive_extensions: false,
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            glob_root: None,
+            exclude_patterns: Vec::new(),
             search_all_files: false,
             text_only: false,
             file_types: FileTypeStrategy::Default,
+            newer_than: None,
+            older_than: None,
         }
     }
 }
@@ -41,12 +79,23 @@ impl Default for FileFilterOptions {
 /// Handler for filtering files based on various criteria
 pub struct FileFilter {
     options: FileFilterOptions,
+    include_globs: Option<GlobSet>,
+    exclude_globs: Option<GlobSet>,
+    exclude_patterns: Option<RegexSet>,
 }
 
 impl FileFilter {
     /// Create a new file filter with the given options
     pub fn new(options: FileFilterOptions) -> Self {
-        Self { options }
+        let include_globs = build_glob_set(&options.include_globs);
+        let exclude_globs = build_glob_set(&options.exclude_globs);
+        let exclude_patterns = build_regex_set(&options.exclude_patterns);
+        Self {
+            options,
+            include_globs,
+            exclude_globs,
+            exclude_patterns,
+        }
     }
 
     /// Filter a list of files based on configured criteria
@@ -65,6 +114,14 @@ impl FileFilter {
             .collect()
     }
 
+    /// Filter walked files, reusing the metadata each was stat-ed for
+    pub fn filter_entries(&self, files: Vec<FileEntry>) -> Vec<FileEntry> {
+        files
+            .into_iter()
+            .filter(|file| self.should_search_entry(file))
+            .collect()
+    }
+
     /// Determine if a specific file should be searched
     ///
     /// # Arguments
@@ -75,10 +132,17 @@ impl FileFilter {
     ///
     /// `true` if the file passes all filter criteria, `false` otherwise
     pub fn should_search_file(&self, path: &Path) -> bool {
-        let metadata = match path.metadata() {
-            Ok(m) => m,
-            Err(_) => return false,
-        };
+        match FileEntry::from_path(path) {
+            Ok(file) => self.should_search_entry(&file),
+            Err(_) => false,
+        }
+    }
+
+    /// Like [`should_search_file`](Self::should_search_file), without
+    /// stat-ing the file again
+    pub fn should_search_entry(&self, file: &FileEntry) -> bool {
+        let path = file.path.as_path();
+        let metadata = &file.metadata;
 
         let ext = path
             .extension()
@@ -87,31 +151,43 @@ impl FileFilter {
             .unwrap_or_default();
 
         // Check binary files
-        if self.options.skip_binary && crate::processor::is_binary(path) {
+        if self.options.skip_binary && file.is_binary() {
             return false;
         }
 
         // Apply safety policy
-        if !self.apply_safety_policy(&metadata, &ext) {
+        if !self.apply_safety_policy(metadata, &ext) {
             return false;
         }
 
         // Check extension filters
-        if !self.apply_extension_filters(&ext) {
+        if !self.apply_extension_filters(path) {
+            return false;
+        }
+
+        // Check path globs
+        if !self.apply_glob_filters(path) {
             return false;
         }
 
+        // Check path exclusion patterns
+        if let Some(ref exclude) = self.exclude_patterns {
+            if exclude.is_match(&path.to_string_lossy()) {
+                return false;
+            }
+        }
+
         // Check file type strategy
-        if !self.should_search_by_file_type(path, &metadata, &ext) {
+        if !self.should_search_by_file_type(path, metadata, &ext) {
             return false;
         }
 
         // Check size limits
-        if !self.apply_size_limits(&metadata) {
+        if !self.apply_size_limits(metadata) {
             return false;
         }
 
-        true
+        modified_within(metadata, self.options.newer_than, self.options.older_than)
     }
 
     /// Apply safety policy constraints
@@ -124,7 +200,7 @@ impl FileFilter {
                 }
 
                 let classifier = FileTypeClassifier::new();
-                classifier.is_always_search(ext)
+                self.options.binary_mode != BinaryMode::Skip || classifier.is_always_search(ext)
             }
             SafetyPolicy::Performance => {
                 let file_size = metadata.len();
@@ -135,17 +211,46 @@ impl FileFilter {
     }
 
     /// Apply extension filters (include/exclude)
-    fn apply_extension_filters(&self, ext: &str) -> bool {
+    fn apply_extension_filters(&self, path: &Path) -> bool {
+        let case_sensitive = self.options.case_sensitive_extensions;
+
         // Handle include extensions
         if let Some(ref include_exts) = self.options.include_extensions {
-            if !include_exts.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+            if !has_extension(path, include_exts, case_sensitive) {
                 return false;
             }
         }
 
         // Handle exclude extensions
         if let Some(ref exclude_exts) = self.options.exclude_extensions {
-            if exclude_exts.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+            if has_extension(path, exclude_exts, case_sensitive) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Apply glob filters (include/exclude) to the path relative to the glob root
+    fn apply_glob_filters(&self, path: &Path) -> bool {
+        let relative = self
+            .options
+            .glob_root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            // The root may be the file itself
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(path);
+
+        // Excludes win over includes
+        if let Some(ref exclude) = self.exclude_globs {
+            if exclude.is_match(relative) {
+                return false;
+            }
+        }
+
+        if let Some(ref include) = self.include_globs {
+            if !include.is_match(relative) {
                 return false;
             }
         }
@@ -160,7 +265,7 @@ impl FileFilter {
         metadata: &std::fs::Metadata,
         ext: &str,
     ) -> bool {
-        if self.options.search_all_files {
+        if self.options.search_all_files || self.options.binary_mode != BinaryMode::Skip {
             return true;
         }
 
@@ -187,14 +292,80 @@ impl FileFilter {
 
     /// Apply size limits
     fn apply_size_limits(&self, metadata: &std::fs::Metadata) -> bool {
-        if let Some(max_size) = self.options.max_size {
-            let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-            if size_mb > max_size as f64 {
-                return false;
+        let max = self
+            .options
+            .max_filesize
+            .or(self.options.max_size.map(|mb| mb as u64 * 1024 * 1024));
+        let size = metadata.len();
+        max.is_none_or(|max| size <= max) && self.options.min_filesize.is_none_or(|min| size >= min)
+    }
+}
+
+/// Compile glob patterns into a set, or `None` if there are none
+///
+/// Like `.gitignore`, a pattern without a `/` matches at any depth, and `*`
+/// never crosses a path separator. Invalid patterns are reported and skipped.
+fn build_glob_set(patterns: &[String]) -> Option<GlobSet> {
+    if patterns.is_empty() {
+        return None;
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = if pattern.contains('/') {
+            pattern.clone()
+        } else {
+            format!("**/{pattern}")
+        };
+        match GlobBuilder::new(&pattern).literal_separator(true).build() {
+            Ok(glob) => {
+                builder.add(glob);
             }
+            Err(e) => log::warn!("Ignoring invalid glob '{pattern}': {e}"),
         }
-        true
     }
+
+    builder.build().ok()
+}
+
+/// Compile path regexes into a set, or `None` if there are none
+///
+/// Invalid patterns are reported and skipped.
+fn build_regex_set(patterns: &[String]) -> Option<RegexSet> {
+    let valid: Vec<&String> = patterns
+        .iter()
+        .filter(|pattern| match regex::Regex::new(pattern) {
+            Ok(_) => true,
+            Err(e) => {
+                log::warn!("Ignoring invalid exclude pattern '{pattern}': {e}");
+                false
+            }
+        })
+        .collect();
+    if valid.is_empty() {
+        return None;
+    }
+
+    RegexSet::new(valid).ok()
+}
+
+/// Whether a file was modified after `newer_than` and before `older_than`
+///
+/// With either bound set, a file whose modification time can't be read is
+/// left out rather than guessed at.
+pub fn modified_within(
+    metadata: &std::fs::Metadata,
+    newer_than: Option<SystemTime>,
+    older_than: Option<SystemTime>,
+) -> bool {
+    if newer_than.is_none() && older_than.is_none() {
+        return true;
+    }
+    let Ok(modified) = metadata.modified() else {
+        return false;
+    };
+    newer_than.is_none_or(|cutoff| modified > cutoff)
+        && older_than.is_none_or(|cutoff| modified < cutoff)
 }
 
 #[cfg(test)]
@@ -261,4 +432,169 @@ mod tests {
         assert!(filter.should_search_file(&small_file));
         assert!(!filter.should_search_file(&large_file));
     }
+
+    #[test]
+    fn test_glob_filtering() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::create_dir_all(root.join("src/tests")).unwrap();
+        let files = [
+            root.join("src/lib.rs"),
+            root.join("src/nested/deep.rs"),
+            root.join("src/tests/it.rs"),
+            root.join("src/notes.txt"),
+        ];
+        for file in &files {
+            File::create(file).unwrap().write_all(b"test").unwrap();
+        }
+
+        let options = FileFilterOptions {
+            include_globs: vec!["src/**/*.rs".to_string()],
+            exclude_globs: vec!["**/tests/**".to_string()],
+            glob_root: Some(root.to_path_buf()),
+            ..Default::default()
+        };
+        let filter = FileFilter::new(options);
+
+        // `**` spans any number of directories
+        assert!(filter.should_search_file(&files[0]));
+        assert!(filter.should_search_file(&files[1]));
+        // Matches both the include and the exclude glob: exclude wins
+        assert!(!filter.should_search_file(&files[2]));
+        assert!(!filter.should_search_file(&files[3]));
+    }
+
+    #[test]
+    fn test_exclude_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src/generated")).unwrap();
+        let kept = root.join("src/lib.rs");
+        let generated = root.join("src/generated/schema.rs");
+        let minified = root.join("src/app.min.rs");
+        for file in [&kept, &generated, &minified] {
+            File::create(file).unwrap().write_all(b"test").unwrap();
+        }
+
+        let options = FileFilterOptions {
+            include_extensions: Some(vec!["rs".to_string()]),
+            exclude_patterns: vec![
+                "/generated/".to_string(),
+                r"\.min\.rs$".to_string(),
+                // Invalid patterns are skipped rather than excluding everything
+                "(".to_string(),
+            ],
+            ..Default::default()
+        };
+        let filter = FileFilter::new(options);
+
+        // All three have an included extension; the patterns still win
+        assert!(filter.should_search_file(&kept));
+        assert!(!filter.should_search_file(&generated));
+        assert!(!filter.should_search_file(&minified));
+    }
+
+    #[test]
+    fn test_filter_entries_matches_filter_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for (name, size) in [("a.rs", 10), ("b.txt", 10), ("big.rs", 2_000_000)] {
+            File::create(root.join(name))
+                .unwrap()
+                .write_all(&vec![b'x'; size])
+                .unwrap();
+        }
+
+        let filter = FileFilter::new(FileFilterOptions {
+            max_size: Some(1),
+            include_extensions: Some(vec!["rs".to_string()]),
+            ..Default::default()
+        });
+        let mut entries: Vec<PathBuf> = filter
+            .filter_entries(crate::walker::walk_files(root, Default::default()).collect())
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        entries.sort();
+        let mut paths = filter.filter_files(
+            ["a.rs", "b.txt", "big.rs"]
+                .iter()
+                .map(|name| root.join(name))
+                .collect(),
+        );
+        paths.sort();
+
+        assert_eq!(entries, vec![root.join("a.rs")]);
+        assert_eq!(entries, paths);
+    }
+
+    #[test]
+    fn test_byte_size_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let threshold = crate::cli::parse_size("512K").unwrap();
+        let sized = |name: &str, size: u64| {
+            let path = temp_dir.path().join(name);
+            File::create(&path).unwrap().set_len(size).unwrap();
+            path
+        };
+        let under = sized("under.txt", threshold - 1);
+        let exact = sized("exact.txt", threshold);
+        let over = sized("over.txt", threshold + 1);
+
+        let filter = |max_filesize: Option<u64>, min_filesize: Option<u64>| {
+            FileFilter::new(FileFilterOptions {
+                max_filesize,
+                min_filesize,
+                ..Default::default()
+            })
+            .filter_files(vec![under.clone(), exact.clone(), over.clone()])
+        };
+        assert_eq!(
+            filter(Some(threshold), None),
+            vec![under.clone(), exact.clone()]
+        );
+        assert_eq!(
+            filter(None, Some(threshold)),
+            vec![exact.clone(), over.clone()]
+        );
+        assert_eq!(
+            filter(Some(threshold), Some(threshold)),
+            vec![exact.clone()]
+        );
+    }
+
+    #[test]
+    fn test_modification_time_window() {
+        use std::time::{Duration, SystemTime};
+
+        let temp_dir = TempDir::new().unwrap();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let now = SystemTime::now();
+        let aged = |name: &str, age: Duration| {
+            let path = temp_dir.path().join(name);
+            let file = File::create(&path).unwrap();
+            file.set_modified(now - age).unwrap();
+            path
+        };
+        let fresh = aged("fresh.txt", Duration::ZERO);
+        let week = aged("week.txt", 7 * day);
+        let year = aged("year.txt", 365 * day);
+
+        let filter = |newer_than: Option<Duration>, older_than: Option<Duration>| {
+            FileFilter::new(FileFilterOptions {
+                newer_than: newer_than.map(|age| now - age),
+                older_than: older_than.map(|age| now - age),
+                ..Default::default()
+            })
+            .filter_files(vec![fresh.clone(), week.clone(), year.clone()])
+        };
+        assert_eq!(filter(Some(2 * day), None), vec![fresh.clone()]);
+        assert_eq!(
+            filter(None, Some(2 * day)),
+            vec![week.clone(), year.clone()]
+        );
+        assert_eq!(filter(Some(30 * day), Some(2 * day)), vec![week.clone()]);
+        assert_eq!(filter(None, None).len(), 3);
+    }
 }
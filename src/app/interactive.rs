@@ -0,0 +1,144 @@
+/// Line-based interactive search for `rfgrep interactive`
+///
+/// The session holds the results of a search and refines them with commands
+/// read one line at a time, so it needs no terminal UI and can be scripted:
+/// `printf 'f todo\ns todo.json\nq\n' | rfgrep interactive fixme --output-format json`
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use crate::output_formats::OutputFormatter;
+use crate::processor::SearchMatch;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// What the caller should do after a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Read the next command
+    Continue,
+    /// Search again for this pattern and hand the results to `set_results`
+    Search(String),
+    Quit,
+}
+
+/// Results of the current search and the filters narrowing them
+pub struct InteractiveSession {
+    pattern: String,
+    root: PathBuf,
+    results: Vec<SearchMatch>,
+    /// Each filter keeps the matches whose line or path contains it, ignoring case
+    filters: Vec<String>,
+    /// Formats the results written by `save`
+    formatter: OutputFormatter,
+}
+
+impl InteractiveSession {
+    pub fn new(root: PathBuf, formatter: OutputFormatter) -> Self {
+        Self {
+            pattern: String::new(),
+            root,
+            results: Vec::new(),
+            filters: Vec::new(),
+            formatter,
+        }
+    }
+
+    /// Replace the results with those of a new search, dropping the filters
+    pub fn set_results(&mut self, pattern: String, results: Vec<SearchMatch>) {
+        self.pattern = pattern;
+        self.results = results;
+        self.filters.clear();
+    }
+
+    /// The results that pass every filter
+    pub fn visible(&self) -> Vec<SearchMatch> {
+        let filters: Vec<String> = self.filters.iter().map(|f| f.to_lowercase()).collect();
+        self.results
+            .iter()
+            .filter(|m| {
+                let line = m.line.to_lowercase();
+                let path = m.path.to_string_lossy().to_lowercase();
+                filters
+                    .iter()
+                    .all(|filter| line.contains(filter) || path.contains(filter))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Print the visible results as `path:line:col: text`, then a summary
+    pub fn show(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let visible = self.visible();
+        for m in &visible {
+            writeln!(
+                out,
+                "{}:{}:{}: {}",
+                m.path.display(),
+                m.line_number,
+                m.column_start + 1,
+                m.line
+            )?;
+        }
+        if self.filters.is_empty() {
+            writeln!(out, "{} matches for '{}'", visible.len(), self.pattern)
+        } else {
+            writeln!(
+                out,
+                "{} of {} matches for '{}' (filters: {})",
+                visible.len(),
+                self.results.len(),
+                self.pattern,
+                self.filters.join(", ")
+            )
+        }
+    }
+
+    /// Run one command line
+    pub fn execute(&mut self, input: &str, out: &mut impl Write) -> RfgrepResult<Step> {
+        let input = input.trim();
+        let (command, argument) = match input.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (input, ""),
+        };
+
+        match (command, argument) {
+            ("", _) => {}
+            ("q" | "quit", _) => return Ok(Step::Quit),
+            ("h" | "help", _) => Self::help(out)?,
+            ("n" | "new", "") => writeln!(out, "Usage: new <pattern>")?,
+            ("n" | "new", pattern) => return Ok(Step::Search(pattern.to_string())),
+            ("f" | "filter", "") => writeln!(out, "Usage: filter <text>")?,
+            ("f" | "filter", text) => {
+                self.filters.push(text.to_string());
+                self.show(out)?;
+            }
+            ("c" | "clear", _) => {
+                self.filters.clear();
+                self.show(out)?;
+            }
+            ("s" | "save", "") => writeln!(out, "Usage: save <file>")?,
+            ("s" | "save", file) => {
+                let visible = self.visible();
+                let content = self
+                    .formatter
+                    .format_results(&visible, &self.pattern, &self.root);
+                std::fs::write(file, content).map_err(|e| {
+                    RfgrepError::Other(format!("Failed to save results to {file}: {e}"))
+                })?;
+                writeln!(out, "Saved {} matches to {file}", visible.len())?;
+            }
+            (command, _) => writeln!(out, "Unknown command '{command}' (h for help)")?,
+        }
+        Ok(Step::Continue)
+    }
+
+    fn help(out: &mut impl Write) -> std::io::Result<()> {
+        writeln!(out, "Commands:")?;
+        writeln!(out, "  n, new <pattern>  Start a new search")?;
+        writeln!(
+            out,
+            "  f, filter <text>  Keep matches whose line or path contains text"
+        )?;
+        writeln!(out, "  c, clear          Clear all filters")?;
+        writeln!(out, "  s, save <file>    Save the shown results to a file")?;
+        writeln!(out, "  q, quit           Exit interactive mode")
+    }
+}
@@ -3,7 +3,11 @@
 /// This module contains the decomposed components of the main application,
 /// separated by responsibility for better maintainability and testability.
 pub mod filters;
+pub mod interactive;
 pub mod stdin;
+pub mod watch;
 
 pub use filters::{FileFilter, FileFilterOptions};
+pub use interactive::InteractiveSession;
 pub use stdin::StdinSearcher;
+pub use watch::LogWatcher;
@@ -2,12 +2,12 @@
 ///
 /// This module provides dedicated functionality for searching content from stdin,
 /// enabling Unix pipeline integration like: `cat file.log | rfgrep search "pattern"`
-use crate::cli::OutputFormat as CliOutputFormat;
+use crate::cli::{LineRange, OutputFormat as CliOutputFormat};
 use crate::error::{Result as RfgrepResult, RfgrepError};
-use crate::output_formats::OutputFormatter;
-use crate::processor::SearchMatch;
+use crate::output_formats::{OutputFormatter, Palette};
+use crate::processor::{is_whole_word, OffsetLines, SearchMatch};
 use colored::Colorize;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 
 /// Configuration options for stdin search
@@ -15,13 +15,43 @@ pub struct StdinSearchOptions {
     pub search_pattern: String,
     pub original_pattern: String,
     pub case_sensitive: bool,
+    /// Only count matches with no word character on either side (`-w`)
+    pub word_regexp: bool,
     pub invert_match: bool,
+    /// Match the regex against all of stdin so it can span lines
+    pub multiline: bool,
     pub max_matches: Option<usize>,
+    /// Only search these lines of the input
+    pub line_range: Option<LineRange>,
     pub output_format: CliOutputFormat,
     pub ndjson: bool,
+    /// End NDJSON output with a summary record (`--json-summary`)
+    pub json_summary: bool,
     pub count: bool,
+    /// Count every occurrence instead of matching lines
+    pub count_matches: bool,
     pub files_with_matches: bool,
+    /// Print the label only when nothing matched (`-L`)
+    pub files_without_match: bool,
+    /// Terminate records with `\0` instead of newline
+    pub null: bool,
+    /// Report each match separately with only its matched text
+    pub only_matching: bool,
+    /// Report each match separately as `label:line:col:line`
+    pub vimgrep: bool,
+    /// Always show the column of each match
+    pub column: bool,
+    /// Echo every line, highlighting the matches
+    pub passthru: bool,
+    /// Print each match as this template with its capture groups filled in
+    pub format_template: Option<String>,
+    /// Print nothing; only report whether anything matched (`-q`)
+    pub silent: bool,
     pub quiet: bool,
+    /// Colors for the matches, or `None` for plain output (`--color`)
+    pub colors: Option<Palette>,
+    /// Name reported for the input, `<stdin>` unless set with `--label`
+    pub label: String,
 }
 
 /// Handler for searching stdin input
@@ -41,7 +71,7 @@ impl StdinSearcher {
     ///
     /// # Returns
     ///
-    /// Result indicating success or error
+    /// Whether any line matched (or, with `invert_match`, didn't match)
     ///
     /// # Examples
     ///
@@ -55,38 +85,101 @@ impl StdinSearcher {
     ///     search_pattern: "error".to_string(),
     ///     original_pattern: "error".to_string(),
     ///     case_sensitive: true,
+    ///     word_regexp: false,
     ///     invert_match: false,
+    ///     multiline: false,
     ///     max_matches: None,
+    ///     line_range: None,
     ///     output_format: OutputFormat::Text,
     ///     ndjson: false,
+    ///     json_summary: false,
     ///     count: false,
+    ///     count_matches: false,
     ///     files_with_matches: false,
+    ///     files_without_match: false,
+    ///     null: false,
+    ///     only_matching: false,
+    ///     vimgrep: false,
+    ///     column: false,
+    ///     passthru: false,
+    ///     format_template: None,
+    ///     silent: false,
     ///     quiet: false,
+    ///     colors: None,
+    ///     label: "<stdin>".to_string(),
     /// };
     ///
     /// let searcher = StdinSearcher::new();
-    /// searcher.search(options).await?;
+    /// let found = searcher.search(options).await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn search(&self, options: StdinSearchOptions) -> RfgrepResult<()> {
-        let regex_pattern = if options.case_sensitive {
-            options.search_pattern.clone()
-        } else {
-            format!("(?i){}", options.search_pattern)
-        };
-
-        let regex = crate::processor::get_or_compile_regex(&regex_pattern)?;
+    pub async fn search(&self, options: StdinSearchOptions) -> RfgrepResult<bool> {
+        let started = std::time::Instant::now();
+        let regex = crate::processor::get_or_compile_regex(&Self::regex_pattern(&options))?;
         let stdin = std::io::stdin();
-        let reader = BufReader::new(stdin.lock());
+        let mut reader = BufReader::new(stdin.lock());
+
+        if options.multiline {
+            let mut content = String::new();
+            reader
+                .read_to_string(&mut content)
+                .map_err(RfgrepError::Io)?;
+            let mut matches = crate::processor::find_matches_multiline(
+                &content,
+                &regex,
+                Path::new(&options.label),
+                0,
+                0,
+            )?;
+            if options.word_regexp {
+                matches.retain(|m| {
+                    is_whole_word(
+                        &content,
+                        m.byte_offset,
+                        m.byte_offset + m.matched_text.len(),
+                    )
+                });
+            }
+            if let Some(max) = options.max_matches {
+                matches.truncate(max);
+            }
+            let occurrences = matches.len();
+            let mut lines: Vec<usize> = matches.iter().map(|m| m.line_number).collect();
+            lines.dedup();
+            let match_count = lines.len();
+            self.output_results(
+                matches,
+                match_count,
+                occurrences,
+                started.elapsed(),
+                &options,
+            )?;
+            return Ok(Self::found(match_count, &options));
+        }
+
+        if options.passthru {
+            return self.search_passthru(reader, &regex, &options);
+        }
 
         let mut matches = Vec::new();
         let mut match_count = 0;
+        let mut occurrences = 0;
 
-        for (line_number, line_result) in reader.lines().enumerate() {
-            let line = line_result.map_err(RfgrepError::Io)?;
+        for (index, line_result) in OffsetLines::new(reader).enumerate() {
+            let (line_start, line) = line_result.map_err(RfgrepError::Io)?;
+            // 1-based, like file searches
+            let line_number = index + 1;
+            if let Some(range) = options.line_range {
+                if range.is_past(line_number) {
+                    break;
+                }
+                if !range.contains(line_number) {
+                    continue;
+                }
+            }
 
-            let is_match = regex.is_match(&line);
+            let is_match = Self::find_iter(&regex, &line, &options).next().is_some();
             let should_include = if options.invert_match {
                 !is_match
             } else {
@@ -95,17 +188,42 @@ impl StdinSearcher {
 
             if should_include {
                 match_count += 1;
+                occurrences += if options.count_matches && !options.invert_match {
+                    Self::find_iter(&regex, &line, &options).count()
+                } else {
+                    1
+                };
 
-                if !options.count && !options.files_with_matches {
+                let collect = !options.count
+                    && !options.count_matches
+                    && !options.files_with_matches
+                    && !options.files_without_match;
+                let per_occurrence =
+                    options.only_matching || options.vimgrep || options.format_template.is_some();
+                if collect && per_occurrence && !options.invert_match {
+                    // Every occurrence on the line becomes its own record
+                    for mat in Self::find_iter(&regex, &line, &options) {
+                        matches.push(SearchMatch {
+                            path: PathBuf::from(&options.label),
+                            line_number,
+                            line: line.clone(),
+                            matched_text: mat.as_str().to_string(),
+                            column_start: mat.start(),
+                            column_end: mat.end(),
+                            byte_offset: line_start + mat.start(),
+                            ..Default::default()
+                        });
+                    }
+                } else if collect {
                     let (matched_text, column_start, column_end) =
-                        if let Some(mat) = regex.find(&line) {
+                        if let Some(mat) = Self::find_iter(&regex, &line, &options).next() {
                             (mat.as_str().to_string(), mat.start(), mat.end())
                         } else {
                             (String::default(), 0, line.len())
                         };
 
                     let search_match = SearchMatch {
-                        path: PathBuf::from("<stdin>"),
+                        path: PathBuf::from(&options.label),
                         line_number,
                         line: line.clone(),
                         context_before: Vec::new(),
@@ -113,6 +231,10 @@ impl StdinSearcher {
                         matched_text,
                         column_start,
                         column_end,
+                        byte_offset: line_start + column_start,
+                        distance: None,
+                        binary: false,
+                        excerpt: None,
                     };
                     matches.push(search_match);
                 }
@@ -125,7 +247,99 @@ impl StdinSearcher {
             }
         }
 
-        self.output_results(matches, match_count, &options)
+        self.output_results(
+            matches,
+            match_count,
+            occurrences,
+            started.elapsed(),
+            &options,
+        )?;
+        Ok(Self::found(match_count, &options))
+    }
+
+    /// The regex's matches on `line`, keeping only whole words under `-w`
+    fn find_iter<'a>(
+        regex: &'a regex::Regex,
+        line: &'a str,
+        options: &'a StdinSearchOptions,
+    ) -> impl Iterator<Item = regex::Match<'a>> {
+        regex
+            .find_iter(line)
+            .filter(|m| !options.word_regexp || is_whole_word(line, m.start(), m.end()))
+    }
+
+    /// `search_pattern` with the flags for case-insensitive and multiline matching
+    fn regex_pattern(options: &StdinSearchOptions) -> String {
+        let flags = match (options.case_sensitive, options.multiline) {
+            (true, false) => "",
+            (false, false) => "(?i)",
+            (true, true) => "(?s)",
+            (false, true) => "(?si)",
+        };
+        format!("{}{}", flags, options.search_pattern)
+    }
+
+    /// Echo every line of the input with its matches highlighted
+    fn search_passthru(
+        &self,
+        reader: impl BufRead,
+        regex: &regex::Regex,
+        options: &StdinSearchOptions,
+    ) -> RfgrepResult<bool> {
+        let formatter = Self::colored(
+            OutputFormatter::new(crate::output_formats::OutputFormat::Text),
+            options,
+        );
+        let mut out = BufWriter::new(std::io::stdout().lock());
+        let mut found = false;
+
+        for (index, line_result) in reader.lines().enumerate() {
+            let line = line_result.map_err(RfgrepError::Io)?;
+            let line_number = index + 1;
+            // Lines outside --line-range are still echoed, just never matched
+            let spans: Vec<(usize, usize)> = if options
+                .line_range
+                .is_none_or(|range| range.contains(line_number))
+            {
+                Self::find_iter(regex, &line, options)
+                    .map(|m| (m.start(), m.end()))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            found |= !spans.is_empty();
+            if options.silent {
+                if found {
+                    break;
+                }
+                continue;
+            }
+            out.write_all(
+                formatter
+                    .format_passthru_line(None, line_number, &line, &spans)
+                    .as_bytes(),
+            )
+            .map_err(RfgrepError::Io)?;
+        }
+
+        out.flush().map_err(RfgrepError::Io)?;
+        Ok(found)
+    }
+
+    /// `formatter` with the colors from `--color`
+    fn colored(formatter: OutputFormatter, options: &StdinSearchOptions) -> OutputFormatter {
+        formatter
+            .with_color(options.colors.is_some())
+            .with_palette(options.colors.clone().unwrap_or_default())
+    }
+
+    /// Whether the search produced a result; `-L` reports stdin when nothing matched
+    fn found(match_count: usize, options: &StdinSearchOptions) -> bool {
+        if options.files_without_match && !options.silent {
+            match_count == 0
+        } else {
+            match_count > 0
+        }
     }
 
     /// Output search results in the appropriate format
@@ -133,18 +347,40 @@ impl StdinSearcher {
         &self,
         matches: Vec<SearchMatch>,
         match_count: usize,
+        occurrences: usize,
+        elapsed: std::time::Duration,
         options: &StdinSearchOptions,
     ) -> RfgrepResult<()> {
-        if options.count {
+        if options.silent {
+            return Ok(());
+        }
+        if options.files_without_match {
+            if match_count == 0 {
+                print!(
+                    "{}{}",
+                    options.label,
+                    if options.null { '\0' } else { '\n' }
+                );
+            }
+        } else if options.count {
             println!("{}", match_count);
+        } else if options.count_matches {
+            println!("{}", occurrences);
         } else if options.files_with_matches {
             if match_count > 0 {
-                println!("<stdin>");
+                print!(
+                    "{}{}",
+                    options.label,
+                    if options.null { '\0' } else { '\n' }
+                );
             }
-        } else if matches.is_empty() {
+        } else if matches.is_empty()
+            && options.output_format != CliOutputFormat::Sarif
+            && !options.json_summary
+        {
             self.output_no_matches(options);
         } else {
-            self.output_matches(&matches, options)?;
+            self.output_matches(&matches, elapsed, options)?;
         }
 
         Ok(())
@@ -152,7 +388,7 @@ impl StdinSearcher {
 
     /// Handle output when no matches are found
     fn output_no_matches(&self, options: &StdinSearchOptions) {
-        if options.output_format != CliOutputFormat::Json && !options.quiet {
+        if !options.output_format.is_json() && !options.quiet {
             println!("{}", "No matches found".yellow());
         }
     }
@@ -161,9 +397,30 @@ impl StdinSearcher {
     fn output_matches(
         &self,
         matches: &[SearchMatch],
+        elapsed: std::time::Duration,
         options: &StdinSearchOptions,
     ) -> RfgrepResult<()> {
-        if !options.quiet && options.output_format != CliOutputFormat::Json && !options.ndjson {
+        // NUL-separated and only-matching records only apply to the plain text format
+        let plain_text = options.output_format == CliOutputFormat::Text && !options.ndjson;
+        let null = options.null && plain_text;
+        let only_matching = options.only_matching && plain_text;
+        let vimgrep = options.vimgrep && plain_text;
+        let bare = null || only_matching || vimgrep;
+        // Every line of these is read as a record, so no banner or padding
+        let records_only = options.output_format.is_json()
+            || options.output_format == CliOutputFormat::GithubActions
+            || options.ndjson;
+
+        if let Some(template) = options.format_template.as_deref().filter(|_| plain_text) {
+            let regex = crate::search_algorithms::RegexSearch::new(&Self::regex_pattern(options));
+            let mut out = BufWriter::new(std::io::stdout().lock());
+            return OutputFormatter::new(crate::output_formats::OutputFormat::Text)
+                .write_templated(&mut out, matches, &regex, template, false)
+                .and_then(|()| out.flush())
+                .map_err(RfgrepError::Io);
+        }
+
+        if !options.quiet && !bare && !records_only {
             println!(
                 "\n{} {} {}",
                 "Found".green(),
@@ -172,31 +429,50 @@ impl StdinSearcher {
             );
         }
 
-        let formatter = OutputFormatter::new(if options.ndjson {
-            crate::output_formats::OutputFormat::Json
-        } else {
-            match options.output_format {
-                CliOutputFormat::Text => crate::output_formats::OutputFormat::Text,
-                CliOutputFormat::Json => crate::output_formats::OutputFormat::Json,
-                CliOutputFormat::Xml => crate::output_formats::OutputFormat::Xml,
-                CliOutputFormat::Html => crate::output_formats::OutputFormat::Html,
-                CliOutputFormat::Markdown => crate::output_formats::OutputFormat::Markdown,
-                CliOutputFormat::Csv => crate::output_formats::OutputFormat::Csv,
-                CliOutputFormat::Tsv => crate::output_formats::OutputFormat::Tsv,
-            }
-        })
-        .with_ndjson(options.ndjson);
-
-        let output =
-            formatter.format_results(matches, &options.original_pattern, Path::new("<stdin>"));
+        let formatter = Self::colored(
+            OutputFormatter::new(if options.ndjson {
+                crate::output_formats::OutputFormat::Json
+            } else {
+                match options.output_format {
+                    CliOutputFormat::Text => crate::output_formats::OutputFormat::Text,
+                    CliOutputFormat::Json => crate::output_formats::OutputFormat::Json,
+                    CliOutputFormat::Xml => crate::output_formats::OutputFormat::Xml,
+                    CliOutputFormat::Html => crate::output_formats::OutputFormat::Html,
+                    CliOutputFormat::Markdown => crate::output_formats::OutputFormat::Markdown,
+                    CliOutputFormat::Csv => crate::output_formats::OutputFormat::Csv,
+                    CliOutputFormat::Tsv => crate::output_formats::OutputFormat::Tsv,
+                    CliOutputFormat::Sarif => crate::output_formats::OutputFormat::Sarif,
+                    CliOutputFormat::GithubActions => {
+                        crate::output_formats::OutputFormat::GithubActions
+                    }
+                }
+            })
+            .with_ndjson(options.ndjson)
+            .with_null_separator(null)
+            .with_only_matching(only_matching)
+            .with_vimgrep(vimgrep)
+            .with_column(options.column)
+            .with_summary(options.json_summary.then_some(elapsed)),
+            options,
+        );
 
-        if options.output_format == CliOutputFormat::Json || options.ndjson {
-            print!("{output}");
-        } else {
-            println!("\n{output}");
+        let padded = !(records_only || bare);
+        let mut out = BufWriter::new(std::io::stdout().lock());
+        if padded {
+            writeln!(out).map_err(RfgrepError::Io)?;
         }
-
-        Ok(())
+        formatter
+            .write_results(
+                &mut out,
+                matches,
+                &options.original_pattern,
+                Path::new(&options.label),
+            )
+            .map_err(RfgrepError::Io)?;
+        if padded {
+            writeln!(out).map_err(RfgrepError::Io)?;
+        }
+        out.flush().map_err(RfgrepError::Io)
     }
 }
 
@@ -0,0 +1,196 @@
+/// Following a growing file, like `tail -f | grep`
+///
+/// The file is polled for appended bytes and each new complete line is
+/// searched as it arrives. A file that's truncated, or replaced by a new one
+/// (log rotation), is read again from the start.
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use regex::Regex;
+use std::collections::VecDeque;
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How often the file is checked for new lines unless told otherwise
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Prints the lines matching a pattern as they're appended to a file
+pub struct LogWatcher {
+    path: PathBuf,
+    regex: Regex,
+    poll_interval: Duration,
+    after: usize,
+}
+
+/// The file being read and how far into it we are
+struct Position {
+    file: File,
+    identity: Option<(u64, u64)>,
+    offset: u64,
+    line_number: usize,
+    /// Bytes after the last newline, waiting for the rest of their line
+    partial: Vec<u8>,
+}
+
+impl LogWatcher {
+    pub fn new(path: impl Into<PathBuf>, regex: Regex) -> Self {
+        Self {
+            path: path.into(),
+            regex,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            after: 0,
+        }
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Also search the last `lines` lines already in the file, like `tail -n`
+    pub fn with_after(mut self, lines: usize) -> Self {
+        self.after = lines;
+        self
+    }
+
+    /// Follow the file until `shutdown` is set, writing `path:line:col: text`
+    /// for each matching line. Returns how many lines matched.
+    pub fn run(&self, shutdown: &AtomicBool, out: &mut impl Write) -> RfgrepResult<usize> {
+        let mut matched = 0;
+        let mut position = self.open_at_end(out, &mut matched)?;
+
+        while !shutdown.load(Ordering::Relaxed) {
+            matched += self.read_new_lines(&mut position, out)?;
+
+            match std::fs::metadata(&self.path) {
+                Ok(metadata) => {
+                    let rotated =
+                        position.identity.is_some() && identity(&metadata) != position.identity;
+                    if rotated || metadata.len() < position.offset {
+                        // Finish what was written to the old file first
+                        if rotated {
+                            matched += self.read_new_lines(&mut position, out)?;
+                        }
+                        log::debug!(
+                            "{} was rotated or truncated, reopening",
+                            self.path.display()
+                        );
+                        position = self.open()?;
+                        continue;
+                    }
+                }
+                // Between a rotation's rename and the new file's creation
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(RfgrepError::Io(e)),
+            }
+            std::thread::sleep(self.poll_interval);
+        }
+        Ok(matched)
+    }
+
+    /// Open the file past its current lines, searching the last `after` of them
+    fn open_at_end(&self, out: &mut impl Write, matched: &mut usize) -> RfgrepResult<Position> {
+        let mut position = self.open()?;
+        let mut reader = BufReader::new(&position.file);
+        let mut tail = VecDeque::with_capacity(self.after);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            let read = reader
+                .read_until(b'\n', &mut line)
+                .map_err(RfgrepError::Io)?;
+            if read == 0 || line.last() != Some(&b'\n') {
+                // A line still being written is left for the first poll
+                break;
+            }
+            position.offset += read as u64;
+            position.line_number += 1;
+            if self.after > 0 {
+                if tail.len() == self.after {
+                    tail.pop_front();
+                }
+                tail.push_back((position.line_number, line.clone()));
+            }
+        }
+        for (line_number, line) in tail {
+            *matched += usize::from(self.print_if_match(line_number, &line, out)?);
+        }
+        Ok(position)
+    }
+
+    fn open(&self) -> RfgrepResult<Position> {
+        let file = File::open(&self.path).map_err(RfgrepError::Io)?;
+        let identity = file.metadata().ok().and_then(|m| identity(&m));
+        Ok(Position {
+            file,
+            identity,
+            offset: 0,
+            line_number: 0,
+            partial: Vec::new(),
+        })
+    }
+
+    /// Search the complete lines appended since the last read
+    fn read_new_lines(&self, position: &mut Position, out: &mut impl Write) -> RfgrepResult<usize> {
+        let mut appended = Vec::new();
+        position
+            .file
+            .seek(SeekFrom::Start(position.offset))
+            .map_err(RfgrepError::Io)?;
+        (&position.file)
+            .read_to_end(&mut appended)
+            .map_err(RfgrepError::Io)?;
+        position.offset += appended.len() as u64;
+        position.partial.extend_from_slice(&appended);
+
+        let mut matched = 0;
+        let Some(end) = position.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(0);
+        };
+        let rest = position.partial.split_off(end + 1);
+        for line in position.partial.split_inclusive(|&b| b == b'\n') {
+            position.line_number += 1;
+            matched += usize::from(self.print_if_match(position.line_number, line, out)?);
+        }
+        position.partial = rest;
+        Ok(matched)
+    }
+
+    fn print_if_match(
+        &self,
+        line_number: usize,
+        line: &[u8],
+        out: &mut impl Write,
+    ) -> RfgrepResult<bool> {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim_end_matches(['\n', '\r']);
+        let Some(found) = self.regex.find(line) else {
+            return Ok(false);
+        };
+        writeln!(
+            out,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            line_number,
+            found.start() + 1,
+            line
+        )
+        .and_then(|()| out.flush())
+        .map_err(RfgrepError::Io)?;
+        Ok(true)
+    }
+}
+
+/// The device and inode of a file, which change when a log is rotated
+#[cfg(unix)]
+fn identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Without inodes only truncation is noticed
+#[cfg(not(unix))]
+fn identity(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
@@ -4,27 +4,32 @@ use crate::cli::{
     Cli, Commands, PluginCommands, SearchAlgorithm as CliSearchAlgorithm, SearchMode,
 };
 use crate::error::{Result as RfgrepResult, RfgrepError};
-use crate::output_formats::OutputFormatter;
+use crate::file_types::has_extension;
+use crate::metrics::Metrics;
+use crate::output_formats::{OutputFormatter, Palette};
 use crate::plugin_cli::PluginCli;
 use crate::plugin_system::{EnhancedPluginManager, PluginRegistry};
 use crate::processor::search_file;
-use crate::search_algorithms::SearchAlgorithm;
-use crate::streaming_search::{StreamingConfig, StreamingSearchPipeline};
+use crate::search_algorithms::{RegexSearch, SearchAlgorithm};
+use crate::streaming_search::{SearchProgress, StreamingConfig, StreamingSearchPipeline};
 use crate::tui::{init_terminal, restore_terminal, TuiApp};
-use crate::walker::{walk_dir_with_options, WalkerOptions};
+use crate::walker::{walk_dir_with_options, walk_files, FileEntry, WalkerOptions};
 use colored::Colorize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 /// Simplified application that uses existing components
 pub struct RfgrepApp {
     plugin_manager: Arc<EnhancedPluginManager>,
+    /// Set on Ctrl-C; searches stop early and report what they found so far
+    shutdown: Arc<AtomicBool>,
 }
 
 impl RfgrepApp {
     /// Create a new application instance
     pub fn new() -> RfgrepResult<Self> {
-        let plugin_manager = Arc::new(EnhancedPluginManager::new());
+        let plugin_manager = Arc::new(EnhancedPluginManager::load_saved());
         let registry = PluginRegistry::new(plugin_manager.clone());
 
         // Use the existing tokio runtime if available, otherwise create a new one
@@ -32,7 +37,10 @@ impl RfgrepApp {
             Ok(handle) => {
                 // We're already in an async context, use the current runtime
                 handle.block_on(async { registry.load_plugins().await })?;
-                return Ok(Self { plugin_manager });
+                return Ok(Self {
+                    plugin_manager,
+                    shutdown: Arc::default(),
+                });
             }
             Err(_) => {
                 // No current runtime, create a new one
@@ -43,84 +51,233 @@ impl RfgrepApp {
         };
 
         rt.block_on(async { registry.load_plugins().await })?;
-        Ok(Self { plugin_manager })
+        Ok(Self {
+            plugin_manager,
+            shutdown: Arc::default(),
+        })
     }
 
     /// Create a new application instance with async support
     pub async fn new_async() -> RfgrepResult<Self> {
-        let plugin_manager = Arc::new(EnhancedPluginManager::new());
+        let plugin_manager = Arc::new(EnhancedPluginManager::load_saved());
         let registry = PluginRegistry::new(plugin_manager.clone());
 
         // Load plugins asynchronously
         registry.load_plugins().await?;
 
-        Ok(Self { plugin_manager })
+        Ok(Self {
+            plugin_manager,
+            shutdown: Arc::default(),
+        })
     }
 
-    /// Run the application with the given CLI arguments
-    pub async fn run(&self, cli: Cli) -> RfgrepResult<()> {
-        if let Some(log_path) = &cli.log {
-            std::fs::write(log_path, "rfgrep log file created\n").map_err(RfgrepError::Io)?;
-        }
+    /// Stop searches early once `shutdown` is set (e.g. by a Ctrl-C handler)
+    pub fn with_shutdown(mut self, shutdown: Arc<AtomicBool>) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
 
+    /// Run the application with the given CLI arguments
+    ///
+    /// Returns whether anything was found, so the caller can exit like grep;
+    /// only searches can report `false`.
+    pub async fn run(&self, cli: Cli) -> RfgrepResult<bool> {
         let is_piped = !is_terminal::is_terminal(&std::io::stdout());
         let quiet = cli.quiet || is_piped;
 
-        match &cli.command {
+        let result = match &cli.command {
             Commands::Search {
                 pattern,
+                patterns,
+                pattern_file,
                 mode,
+                fixed_strings,
+                word_regexp,
                 algorithm,
+                max_distance,
                 recursive,
+                follow_symlinks,
+                no_ignore_dirs,
+                files_from,
+                files_from0,
+                label,
                 context_lines,
+                before_context,
+                after_context,
                 case_sensitive,
+                ignore_case,
+                smart_case,
                 invert_match,
+                multiline,
                 max_matches,
+                max_count,
+                line_range,
+                sort,
+                sort_files,
+                encoding,
+                binary_mode,
+                binary_as_text,
                 timeout_per_file,
+                pre,
+                pre_globs,
                 path: cmd_path,
                 path_flag: cmd_path_flag,
                 output_format,
                 file_types,
+                extensions,
                 include_extensions,
                 exclude_extensions,
+                types,
+                type_not,
+                type_list,
+                include_globs,
+                exclude_globs,
                 search_all_files,
+                hidden,
                 text_only,
                 ndjson,
                 count,
+                count_total,
+                count_matches,
                 files_with_matches,
+                files_without_match,
+                null,
+                only_matching,
+                json_file_type,
+                json_summary,
+                vimgrep,
+                column,
+                no_messages,
+                passthru,
+                format_template,
+                heading,
+                no_heading,
+                with_filename,
+                no_filename,
+                progress,
+                stats,
+                copy,
                 ..
             } => {
-                self.handle_search(
-                    pattern,
-                    mode.clone(),
-                    algorithm.clone(),
-                    *recursive,
-                    *context_lines,
-                    *case_sensitive,
-                    *invert_match,
-                    *max_matches,
-                    *timeout_per_file,
-                    cmd_path
-                        .as_ref()
-                        .or(cmd_path_flag.as_ref())
-                        .map(|p| p.as_path())
-                        .unwrap_or(&cli.path),
-                    cli.max_size,
-                    cli.skip_binary,
-                    output_format.clone(),
-                    file_types.clone(),
-                    include_extensions.clone(),
-                    exclude_extensions.clone(),
-                    *search_all_files,
-                    *text_only,
-                    cli.safety_policy.clone(),
-                    cli.threads,
-                    *ndjson,
-                    *count,
-                    *files_with_matches,
-                    quiet,
-                )
-                .await
+                if *type_list {
+                    self.print_type_list();
+                    return Ok(true);
+                }
+
+                // The positional pattern comes first, followed by any -e patterns
+                // and then those read from -f
+                let file_patterns = match pattern_file {
+                    Some(path) => self.read_pattern_file(path)?,
+                    None => Vec::new(),
+                };
+                let mut all_patterns: Vec<String> = Vec::new();
+                for p in pattern
+                    .iter()
+                    .chain(patterns.iter())
+                    .chain(file_patterns.iter())
+                {
+                    if !all_patterns.contains(p) {
+                        all_patterns.push(p.clone());
+                    }
+                }
+
+                return self
+                    .handle_search(
+                        &all_patterns,
+                        mode.clone(),
+                        *fixed_strings,
+                        *word_regexp,
+                        algorithm.clone(),
+                        *max_distance,
+                        *recursive,
+                        *follow_symlinks,
+                        *no_ignore_dirs,
+                        files_from
+                            .as_deref()
+                            .map(|list| (list, b'\n'))
+                            .or(files_from0.as_deref().map(|list| (list, b'\0'))),
+                        label.as_deref(),
+                        before_context.unwrap_or(*context_lines),
+                        after_context.unwrap_or(*context_lines),
+                        crate::cli::CaseMode::from_flags(
+                            *case_sensitive,
+                            *ignore_case,
+                            *smart_case,
+                        ),
+                        *invert_match,
+                        *multiline,
+                        *max_matches,
+                        *max_count,
+                        *line_range,
+                        sort.clone(),
+                        *sort_files,
+                        *encoding,
+                        if *binary_as_text {
+                            crate::cli::BinaryMode::Text
+                        } else {
+                            *binary_mode
+                        },
+                        *timeout_per_file,
+                        pre.as_deref(),
+                        pre_globs,
+                        cmd_path
+                            .as_ref()
+                            .or(cmd_path_flag.as_ref())
+                            .map(|p| p.as_path())
+                            .unwrap_or(&cli.path),
+                        cli.max_file_bytes(),
+                        cli.min_filesize,
+                        cli.skip_binary,
+                        cli.newer_than,
+                        cli.older_than,
+                        output_format.clone(),
+                        file_types.clone(),
+                        // --extensions is shorthand for --include-extensions
+                        Self::with_extra_extensions(
+                            include_extensions.clone(),
+                            extensions.as_deref().unwrap_or_default(),
+                        ),
+                        exclude_extensions.clone(),
+                        cli.case_sensitive_extensions,
+                        types,
+                        type_not,
+                        include_globs.clone(),
+                        exclude_globs.clone(),
+                        *search_all_files,
+                        *hidden,
+                        *text_only,
+                        cli.safety_policy.clone(),
+                        cli.threads,
+                        *ndjson,
+                        *count || *count_total,
+                        *count_total,
+                        *count_matches,
+                        *files_with_matches,
+                        *files_without_match,
+                        *null,
+                        *only_matching,
+                        *json_file_type,
+                        *json_summary,
+                        *vimgrep,
+                        *column,
+                        *passthru,
+                        format_template.as_deref(),
+                        // Like rg, group matches under headings unless piped
+                        *heading || !*no_heading && !is_piped,
+                        match (*with_filename, *no_filename) {
+                            (true, _) => Some(true),
+                            (_, true) => Some(false),
+                            _ => None,
+                        },
+                        *progress,
+                        *stats,
+                        *copy,
+                        cli.color,
+                        cli.quiet,
+                        *no_messages,
+                        quiet,
+                    )
+                    .await;
             }
             Commands::List {
                 extensions,
@@ -136,17 +293,25 @@ impl RfgrepApp {
                 reverse,
                 limit,
                 copy,
+                duplicates,
+                binary_only,
+                text_only,
+                tree,
                 output_format,
                 path: cmd_path,
                 path_flag: cmd_path_flag,
             } => {
                 self.handle_list(
                     extensions.as_deref(),
+                    cli.case_sensitive_extensions,
                     *long,
                     *recursive,
                     *show_hidden,
-                    *max_size,
-                    *min_size,
+                    // The list's own limits are in megabytes
+                    cli.max_filesize
+                        .or(max_size.map(|mb| mb as u64 * 1024 * 1024)),
+                    cli.min_filesize
+                        .or(min_size.map(|mb| mb as u64 * 1024 * 1024)),
                     *detailed,
                     *simple,
                     *stats,
@@ -154,6 +319,12 @@ impl RfgrepApp {
                     *reverse,
                     *limit,
                     *copy,
+                    *duplicates,
+                    *binary_only,
+                    *text_only,
+                    *tree,
+                    cli.newer_than,
+                    cli.older_than,
                     output_format.clone(),
                     cmd_path.as_ref().map(|p| p.as_path()),
                     cmd_path_flag.as_ref().map(|p| p.as_path()),
@@ -161,98 +332,79 @@ impl RfgrepApp {
                 )
                 .await
             }
-            Commands::Interactive { .. } => {
-                println!("Interactive command not yet implemented in simplified version");
-                Ok(())
+            Commands::Interactive {
+                pattern,
+                algorithm,
+                extensions,
+                recursive,
+                output_format,
+                path: cmd_path,
+                path_flag: cmd_path_flag,
+            } => {
+                self.handle_interactive(
+                    pattern,
+                    algorithm,
+                    extensions.as_deref(),
+                    cli.case_sensitive_extensions,
+                    *recursive,
+                    output_format,
+                    cmd_path
+                        .as_ref()
+                        .or(cmd_path_flag.as_ref())
+                        .map(|p| p.as_path())
+                        .unwrap_or(&cli.path),
+                )
+                .await
             }
             Commands::Completions { shell } => self.handle_completions(*shell),
-            Commands::Simulate {} => {
-                use std::fs;
-                use std::time::Instant;
-                let results_dir = cli.path.join("results");
-                if let Err(e) = fs::create_dir_all(&results_dir) {
-                    return Err(RfgrepError::Io(e));
-                }
-
-                let search_root = cli.path.join("bench_data");
-                let search_root = if search_root.exists() {
-                    search_root
-                } else {
-                    cli.path.clone()
-                };
-
-                let entries: Vec<_> = crate::walker::walk_dir(&search_root, true, true).collect();
-                let files: Vec<_> = entries
-                    .into_iter()
-                    .filter(|e| e.path().is_file())
-                    .map(|e| e.path().to_path_buf())
-                    .collect();
-
-                if files.is_empty() {
-                    println!(
-                        "Warning: No files found in search directory: {}",
-                        search_root.display()
-                    );
-                    println!("Creating a small test file for simulation...");
-
-                    let test_file = search_root.join("test_simulation.txt");
-                    let test_content = "This is a test file for simulation.\nIt contains some error messages.\nTODO: Add more test cases.\nThe quick brown fox jumps over the lazy dog.\n";
-                    fs::write(&test_file, test_content).map_err(RfgrepError::Io)?;
-
-                    let entries: Vec<_> =
-                        crate::walker::walk_dir(&search_root, true, true).collect();
-                    let files: Vec<_> = entries
-                        .into_iter()
-                        .filter(|e| e.path().is_file())
-                        .map(|e| e.path().to_path_buf())
-                        .collect();
-
-                    if files.is_empty() {
-                        return Err(RfgrepError::Other(
-                            "No files available for simulation".to_string(),
-                        ));
-                    }
-                }
-
-                println!(
-                    "Running simulations on {} files in {}",
-                    files.len(),
-                    search_root.display()
-                );
-
-                let scenarios = vec![
-                    ("regex_short", r"error".to_string()),
-                    ("word_boundary", r"\bTODO\b".to_string()),
-                    ("literal_long", "the quick brown fox jumps over".to_string()),
-                ];
-
-                let mut report = String::from("Scenario,Millis,Matches,Files\n");
-                for (name, pat) in scenarios {
-                    let start = Instant::now();
-                    let mut total = 0usize;
-                    let mut files_processed = 0usize;
-
-                    let regex = crate::processor::get_or_compile_regex(&pat)?;
-                    for f in &files {
-                        if let Ok(matches) = crate::processor::search_file(f, &regex) {
-                            total += matches.len();
-                            files_processed += 1;
-                        }
-                    }
-                    let elapsed = start.elapsed().as_millis();
-                    report.push_str(&format!(
-                        "{},{},{},{}\n",
-                        name, elapsed, total, files_processed
-                    ));
-                }
-
-                let report_path = results_dir.join("simulations.csv");
-                fs::write(&report_path, &report).map_err(RfgrepError::Io)?;
-                println!("Simulations complete. Report: {}", report_path.display());
-                println!("\n{}", report);
-                Ok(())
-            }
+            Commands::Simulate {
+                format,
+                patterns,
+                parallel,
+            } => self.handle_simulate(&cli.path, *format, patterns, *parallel, cli.threads),
+            Commands::Replace {
+                pattern,
+                replacement,
+                mode,
+                in_place,
+                backup,
+                extensions,
+                recursive,
+                case_sensitive,
+                ignore_case,
+                path: cmd_path,
+                path_flag: cmd_path_flag,
+            } => self.handle_replace(
+                pattern,
+                replacement,
+                mode.clone(),
+                *in_place && !cli.dry_run,
+                *backup,
+                extensions.as_deref(),
+                cli.case_sensitive_extensions,
+                *recursive,
+                // Case-sensitive unless -i comes after any -s
+                *case_sensitive || !*ignore_case,
+                cmd_path
+                    .as_ref()
+                    .or(cmd_path_flag.as_ref())
+                    .map(|p| p.as_path())
+                    .unwrap_or(&cli.path),
+                cli.max_file_bytes(),
+                cli.min_filesize,
+                cli.newer_than,
+                cli.older_than,
+                cli.color,
+                quiet,
+            ),
             Commands::Worker { path, pattern } => self.handle_worker(path, pattern).await,
+            Commands::Daemon { socket } => {
+                let socket = socket.clone();
+                let shutdown = self.shutdown.clone();
+                tokio::task::spawn_blocking(move || crate::daemon::serve(&socket, &shutdown))
+                    .await
+                    .map_err(|e| RfgrepError::Other(format!("Daemon stopped: {}", e)))?
+            }
             Commands::Plugins { command } => self.handle_plugin_command(command).await,
             Commands::Tui {
                 pattern,
@@ -272,41 +424,186 @@ impl RfgrepApp {
                 )
                 .await
             }
+            Commands::Watch {
+                pattern,
+                path,
+                mode,
+                case_sensitive,
+                ignore_case,
+                after,
+                poll_interval,
+            } => self.handle_watch(
+                pattern,
+                path,
+                mode.clone(),
+                *case_sensitive || !*ignore_case,
+                *after,
+                std::time::Duration::from_millis(*poll_interval),
+            ),
             Commands::Config { action } => {
                 crate::cli_config::handle_config_action(action.clone()).await
             }
-        }
+        };
+        result.map(|()| true)
     }
 
     async fn handle_search(
         &self,
-        pattern: &str,
+        patterns: &[String],
         mode: crate::cli::SearchMode,
+        fixed_strings: bool,
+        word_regexp: bool,
         algorithm: CliSearchAlgorithm,
+        max_distance: usize,
         recursive: bool,
-        context_lines: usize,
-        case_sensitive: bool,
+        follow_symlinks: bool,
+        no_ignore_dirs: bool,
+        files_from: Option<(&Path, u8)>,
+        label: Option<&str>,
+        context_before: usize,
+        context_after: usize,
+        case_mode: Option<crate::cli::CaseMode>,
         invert_match: bool,
+        multiline: bool,
         max_matches: Option<usize>,
+        max_count: Option<usize>,
+        line_range: Option<crate::cli::LineRange>,
+        sort: crate::cli::MatchSort,
+        sort_files: bool,
+        encoding: crate::cli::Encoding,
+        binary_mode: crate::cli::BinaryMode,
         timeout_per_file: Option<u64>,
+        pre: Option<&str>,
+        pre_globs: &[String],
         search_path: &Path,
-        max_size: Option<usize>,
+        max_filesize: Option<u64>,
+        min_filesize: Option<u64>,
         _skip_binary: bool,
+        newer_than: Option<std::time::SystemTime>,
+        older_than: Option<std::time::SystemTime>,
         output_format: crate::cli::OutputFormat,
         file_types: crate::cli::FileTypeStrategy,
         include_extensions: Option<Vec<String>>,
         exclude_extensions: Option<Vec<String>>,
+        case_sensitive_extensions: bool,
+        types: &[String],
+        type_not: &[String],
+        include_globs: Vec<String>,
+        exclude_globs: Vec<String>,
         search_all_files: bool,
+        hidden: bool,
         text_only: bool,
         safety_policy: crate::cli::SafetyPolicy,
         threads: Option<usize>,
         ndjson: bool,
         count: bool,
+        count_total: bool,
+        count_matches: bool,
         files_with_matches: bool,
+        files_without_match: bool,
+        null: bool,
+        only_matching: bool,
+        json_file_type: bool,
+        json_summary: bool,
+        vimgrep: bool,
+        column: bool,
+        passthru: bool,
+        format_template: Option<&str>,
+        heading: bool,
+        with_filename: Option<bool>,
+        progress: bool,
+        stats: bool,
+        copy: bool,
+        color: crate::cli::ColorChoice,
+        silent: bool,
+        no_messages: bool,
         quiet: bool,
-    ) -> RfgrepResult<()> {
-        let search_pattern = self.build_search_pattern(pattern, mode);
-        let search_algorithm = self.map_search_algorithm(algorithm);
+    ) -> RfgrepResult<bool> {
+        // Load config
+        let config = crate::config::Config::load().unwrap_or_default();
+        // --json-file-type names languages after the --type definitions
+        let languages = json_file_type.then_some(&config.type_definitions);
+
+        // --type/--type-not add their extensions to the include/exclude lists.
+        // The config's excluded extensions are binaries, so they apply unless
+        // -a or --binary-mode asked for binary files.
+        let include_extensions = Self::with_extra_extensions(
+            include_extensions,
+            &config.type_definitions.resolve(types)?,
+        );
+        let config_excludes: &[String] = if binary_mode == crate::cli::BinaryMode::Skip {
+            &config.filters.exclude_extensions
+        } else {
+            &[]
+        };
+        let exclude_extensions = Self::with_extra_extensions(
+            Self::with_extra_extensions(exclude_extensions, config_excludes),
+            &config.type_definitions.resolve(type_not)?,
+        );
+
+        let case_sensitive = self.resolve_case_sensitivity(
+            patterns,
+            &mode,
+            fixed_strings,
+            case_mode.unwrap_or_else(|| config.search.case_mode()),
+        );
+
+        // -F takes precedence over --mode and --algorithm: patterns are always
+        // literal and searched with a literal algorithm
+        let (mode, algorithm) = if fixed_strings {
+            let algorithm = match algorithm {
+                CliSearchAlgorithm::Regex => CliSearchAlgorithm::BoyerMoore,
+                other => other,
+            };
+            (SearchMode::Text, algorithm)
+        } else if format_template.is_some() && mode == SearchMode::Text {
+            // Capture groups only exist in regexes
+            (SearchMode::Regex, CliSearchAlgorithm::Regex)
+        } else {
+            (mode, algorithm)
+        };
+
+        // Several plain-text patterns are searched together with Aho-Corasick;
+        // everything else is folded into a single regex alternation
+        let multi_literal = patterns.len() > 1
+            && !multiline
+            && mode == SearchMode::Text
+            && !matches!(
+                algorithm,
+                CliSearchAlgorithm::Regex | CliSearchAlgorithm::Fuzzy
+            );
+        let literal_patterns = if multi_literal {
+            patterns.to_vec()
+        } else {
+            Vec::new()
+        };
+        let display_pattern = patterns.join(" | ");
+        let pattern = display_pattern.as_str();
+        // -w is checked on each hit rather than with `\b` in the pattern, so
+        // every algorithm agrees on patterns starting or ending with `-` etc.
+        let combined_pattern = self.build_combined_pattern(patterns, mode.clone(), fixed_strings);
+        let search_algorithm =
+            if multiline || mode != SearchMode::Text || patterns.len() > 1 && !multi_literal {
+                // Word and regex modes produce regex syntax, which literal algorithms can't run,
+                // and multiline matching runs the regex over whole files
+                SearchAlgorithm::Regex
+            } else {
+                self.map_search_algorithm(algorithm)
+            };
+        let counters_before = crate::processor::SearchCounters::snapshot();
+        let search_pattern = if matches!(search_algorithm, SearchAlgorithm::Regex) {
+            // Report a bad regex once, up front, rather than from every file
+            crate::processor::get_or_compile_regex(&combined_pattern)?;
+            combined_pattern.clone()
+        } else {
+            patterns[0].clone()
+        };
+
+        // Passthru echoes the input, so it only applies to plain text output
+        let passthru = passthru && output_format == crate::cli::OutputFormat::Text && !ndjson;
+        // Likewise templates replace the plain text records
+        let format_template =
+            format_template.filter(|_| output_format == crate::cli::OutputFormat::Text && !ndjson);
 
         // Check if stdin has data (piped input)
         // Only search stdin if it's not a terminal AND the search path is explicitly NOT provided
@@ -315,115 +612,910 @@ impl RfgrepApp {
         let search_path_str = search_path.to_string_lossy();
         let is_default_path = search_path_str == "." || search_path_str.is_empty();
 
+        // A file list replaces the walk; read from stdin, it also means stdin
+        // isn't content to search
+        let listed_files = match files_from {
+            Some((list, separator)) => Some(self.read_file_list(list, separator)?),
+            None => None,
+        };
+
         // Only use stdin if it's piped AND we're searching the default path
         // If a specific path is given, prefer file search even if stdin is piped
-        if stdin_is_piped && is_default_path {
+        if stdin_is_piped && is_default_path && listed_files.is_none() {
             // Handle piped input from stdin using dedicated stdin module
             let stdin_searcher = StdinSearcher::new();
             let options = StdinSearchOptions {
-                search_pattern,
+                search_pattern: combined_pattern,
                 original_pattern: pattern.to_string(),
                 case_sensitive,
+                word_regexp,
                 invert_match,
-                max_matches,
+                multiline,
+                // Stdin is a single input, so the per-file limit caps it too
+                max_matches: match (max_count, max_matches) {
+                    _ if silent => Some(1),
+                    (Some(count), Some(total)) => Some(count.min(total)),
+                    (count, total) => count.or(total),
+                },
+                line_range,
                 output_format,
                 ndjson,
+                json_summary,
                 count,
+                count_matches,
                 files_with_matches,
+                files_without_match,
+                null,
+                only_matching,
+                vimgrep,
+                column,
+                passthru,
+                format_template: format_template.map(str::to_string),
+                silent,
                 quiet,
+                colors: Self::use_color(color, config.output.color)
+                    .then(|| Palette::from_scheme(&config.output.colors)),
+                label: label.unwrap_or("<stdin>").to_string(),
             };
             return stdin_searcher.search(options).await;
         }
 
-        // Load config
-        let config = crate::config::Config::load().unwrap_or_default();
-
         let walker_options = WalkerOptions {
             recursive,
-            show_hidden: search_all_files,
+            show_hidden: hidden || search_all_files,
             respect_gitignore: config.git.respect_gitignore && !search_all_files,
             respect_global_gitignore: config.git.respect_global_gitignore && !search_all_files,
             respect_git_exclude: config.git.respect_git_exclude && !search_all_files,
             search_dot_git: config.git.search_dot_git,
-            ignore_hidden: !search_all_files,
+            ignore_hidden: !(hidden || search_all_files),
             max_depth: if recursive { None } else { Some(1) },
-            follow_links: config.git.submodules.follow,
+            follow_links: follow_symlinks || config.git.submodules.follow,
             overrides: Vec::new(),
+            ignore_directories: if no_ignore_dirs {
+                Vec::new()
+            } else {
+                config.filters.ignore_directories.clone()
+            },
+            respect_rfgrepignore: !search_all_files,
         };
 
-        let files = self.collect_files(search_path, walker_options);
+        let walk_started = std::time::Instant::now();
+        // Listed files that couldn't be opened; like grep, the search still
+        // runs over the rest but fails at the end
+        let mut unopened = 0;
+        let files = match &listed_files {
+            Some(paths) => paths
+                .iter()
+                .filter_map(|path| match FileEntry::from_path(path) {
+                    Ok(file) if file.is_file() => Some(file),
+                    Ok(_) => None,
+                    Err(e) => {
+                        unopened += 1;
+                        if no_messages {
+                            log::debug!("{}: {e}", path.display());
+                        } else {
+                            eprintln!("rfgrep: {}: {e}", path.display());
+                        }
+                        None
+                    }
+                })
+                .collect(),
+            None => {
+                // The walk skips paths it can't read, so a missing root would
+                // otherwise look like a search that found nothing
+                std::fs::metadata(search_path).map_err(|e| RfgrepError::FileProcessing {
+                    path: search_path.to_path_buf(),
+                    source: Box::new(e),
+                })?;
+                self.collect_files(search_path, walker_options)
+            }
+        };
 
         // Use the FileFilter module for filtering
         let filter_options = FileFilterOptions {
-            max_size,
+            max_size: None,
+            max_filesize,
+            min_filesize,
             skip_binary: _skip_binary,
+            binary_mode,
             safety_policy,
             include_extensions,
             exclude_extensions,
+            case_sensitive_extensions,
+            include_globs,
+            exclude_globs,
+            glob_root: Some(search_path.to_path_buf()),
+            exclude_patterns: config.filters.exclude_patterns.clone(),
             search_all_files,
             text_only,
             file_types,
+            newer_than,
+            older_than,
         };
         let file_filter = FileFilter::new(filter_options);
-        let filtered_files = file_filter.filter_files(files);
-
-        if !quiet && output_format != crate::cli::OutputFormat::Json && !ndjson {
+        let mut filtered_files: Vec<PathBuf> = file_filter
+            .filter_entries(files)
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        if sort_files {
+            filtered_files.sort();
+        }
+        let walk_elapsed = walk_started.elapsed();
+
+        if !quiet
+            && !null
+            && !passthru
+            && format_template.is_none()
+            && !output_format.is_json()
+            && !ndjson
+        {
             println!("Searching {} files...", filtered_files.len());
         }
 
-        let all_matches = self
-            .perform_search(
+        // Like `grep -q`, a silent search only needs to know whether anything matches;
+        // `-L` likewise only needs to know which files match before listing the rest
+        let files_without_match = files_without_match && !silent;
+        let files_with_matches = files_with_matches || files_without_match || silent;
+        let max_matches = if silent { Some(1) } else { max_matches };
+
+        let archive_limits: crate::archive::ArchiveLimits = (&config.compression).into();
+        // A preprocessor's output is held like a decompressed file's, under the same cap
+        let preprocessor = pre
+            .map(|command| {
+                crate::preprocess::Preprocessor::new(command)
+                    .with_globs(pre_globs)
+                    .map(|preprocessor| {
+                        preprocessor
+                            .with_timeout(timeout_per_file.map_or(
+                                crate::preprocess::DEFAULT_TIMEOUT,
+                                std::time::Duration::from_secs,
+                            ))
+                            .with_max_output_bytes(archive_limits.max_decompressed_bytes)
+                    })
+            })
+            .transpose()?;
+
+        let search_started = std::time::Instant::now();
+        let metrics = stats.then(Metrics::new);
+        let all_matches = if multiline {
+            self.perform_multiline_search(
+                &filtered_files,
+                &search_pattern,
+                context_before,
+                context_after,
+                case_sensitive,
+                word_regexp,
+                max_matches,
+                max_count,
+                &sort,
+                preprocessor.as_ref(),
+                metrics.as_ref(),
+            )?
+        } else {
+            self.perform_search(
                 &filtered_files,
                 &search_pattern,
+                literal_patterns,
                 search_algorithm,
-                context_lines,
+                max_distance,
+                context_before,
+                context_after,
                 case_sensitive,
+                word_regexp,
                 invert_match,
                 max_matches,
+                max_count,
+                line_range,
                 timeout_per_file,
-                threads,
+                config.search.thread_count(threads),
                 files_with_matches,
-                count,
+                count || count_matches,
+                &sort,
+                encoding,
+                binary_mode,
+                config.performance.max_memory_usage_mb * 1024 * 1024,
+                archive_limits,
+                preprocessor,
+                no_messages,
+                // Progress is drawn on stderr, but only alongside text results in a terminal
+                progress && !quiet && !output_format.is_json() && !ndjson,
+                metrics.as_ref(),
             )
-            .await?;
+            .await?
+        };
+        let search_elapsed = search_started.elapsed();
+        let output_started = std::time::Instant::now();
+        if let Some(metrics) = &metrics {
+            metrics.matches_found.inc_by(all_matches.len() as u64);
+            metrics.record_counters(
+                crate::processor::SearchCounters::snapshot().since(counters_before),
+            );
+        }
+
+        // Like grep, only name the file of each match when there could be several
+        let show_filename =
+            with_filename.unwrap_or_else(|| listed_files.is_some() || search_path.is_dir());
+        let palette = Palette::from_scheme(&config.output.colors);
+        let colors = Self::use_color(color, config.output.color).then_some(&palette);
+
+        if passthru {
+            if !silent {
+                self.output_passthru(
+                    &filtered_files,
+                    &all_matches,
+                    encoding,
+                    archive_limits,
+                    show_filename,
+                    colors,
+                )?;
+            }
+            return Self::with_unopened_files(!all_matches.is_empty(), unopened);
+        }
+
+        if files_without_match {
+            let unmatched = self.files_without_match(&filtered_files, &all_matches);
+            self.output_files(&unmatched, null);
+            return Self::with_unopened_files(!unmatched.is_empty(), unopened);
+        }
+
+        let found = !all_matches.is_empty();
+        let listing = count || count_matches || files_with_matches;
+        if let Some(template) = format_template.filter(|_| !silent && !listing) {
+            let flags = match (case_sensitive, multiline) {
+                (true, false) => "",
+                (false, false) => "(?i)",
+                (true, true) => "(?s)",
+                (false, true) => "(?si)",
+            };
+            let regex = RegexSearch::new(&format!("{flags}{search_pattern}"));
+            let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+            self.results_formatter(
+                &output_format,
+                ndjson,
+                false,
+                false,
+                None,
+                false,
+                column,
+                heading,
+                show_filename,
+                colors,
+            )
+            .write_templated(&mut out, &all_matches, &regex, template, show_filename)
+            .and_then(|()| std::io::Write::flush(&mut out))
+            .map_err(RfgrepError::Io)?;
+            return Self::with_unopened_files(found, unopened);
+        }
+        if !silent {
+            self.output_results(
+                &all_matches,
+                pattern,
+                search_path,
+                output_format.clone(),
+                ndjson,
+                count,
+                count_total,
+                count_matches,
+                files_with_matches,
+                null,
+                only_matching,
+                languages,
+                json_summary.then(|| walk_started.elapsed()),
+                vimgrep,
+                column,
+                heading,
+                show_filename,
+                colors,
+                quiet,
+            )?;
+        }
+        if copy && found && !silent {
+            let plain_text = output_format == crate::cli::OutputFormat::Text && !ndjson;
+            let text = self
+                .results_formatter(
+                    &output_format,
+                    ndjson,
+                    null && plain_text,
+                    only_matching && plain_text,
+                    languages,
+                    vimgrep && plain_text,
+                    column,
+                    heading,
+                    show_filename,
+                    None,
+                )
+                .format_results(&all_matches, pattern, search_path);
+            self.copy_to_clipboard(&text);
+        }
+        if let Some(metrics) = metrics.filter(|_| !silent) {
+            let phases = [
+                ("walk", walk_elapsed),
+                ("search", search_elapsed),
+                ("output", output_started.elapsed()),
+            ];
+            self.print_search_stats(&metrics, &phases, output_format.is_json() || ndjson);
+        }
+        Self::with_unopened_files(found, unopened)
+    }
+
+    /// A search's result once its output is written: an error if any file
+    /// from `--files-from` couldn't be opened, so the exit code is 2 as in grep
+    fn with_unopened_files(found: bool, unopened: usize) -> RfgrepResult<bool> {
+        match unopened {
+            0 => Ok(found),
+            1 => Err(RfgrepError::Other(
+                "1 listed file couldn't be opened".to_string(),
+            )),
+            n => Err(RfgrepError::Other(format!(
+                "{n} listed files couldn't be opened"
+            ))),
+        }
+    }
+
+    /// Summarize a search on stderr so it never mixes with the results, as a
+    /// JSON object alongside JSON results
+    fn print_search_stats(
+        &self,
+        metrics: &Metrics,
+        phases: &[(&str, std::time::Duration)],
+        json: bool,
+    ) {
+        let elapsed: std::time::Duration = phases.iter().map(|(_, time)| *time).sum();
+        let bytes = metrics.bytes_scanned.get();
+        let megabytes = bytes as f64 / (1024.0 * 1024.0);
+        let seconds = elapsed.as_secs_f64();
+        let throughput = if seconds > 0.0 {
+            megabytes / seconds
+        } else {
+            0.0
+        };
+
+        if json {
+            let phases: serde_json::Map<String, serde_json::Value> = phases
+                .iter()
+                .map(|(name, time)| (name.to_string(), time.as_secs_f64().into()))
+                .collect();
+            let stats = serde_json::json!({
+                "files_scanned": metrics.files_scanned.get(),
+                "bytes_scanned": bytes,
+                "matches": metrics.matches_found.get(),
+                "regex_cache": {
+                    "hits": metrics.regex_cache_hits.get(),
+                    "misses": metrics.regex_cache_misses.get(),
+                },
+                "files_mmapped": metrics.files_mmapped.get(),
+                "files_streamed": metrics.files_streamed.get(),
+                "phases_seconds": phases,
+                "elapsed_seconds": elapsed.as_secs_f64(),
+                "throughput_mb_per_second": throughput,
+            });
+            eprintln!("{stats}");
+            return;
+        }
 
-        self.output_results(
-            &all_matches,
-            pattern,
-            search_path,
+        eprintln!("\n{}", "Search statistics:".bold());
+        eprintln!("  Files scanned: {}", metrics.files_scanned.get());
+        eprintln!("  Bytes scanned: {bytes} ({megabytes:.2} MB)");
+        eprintln!(
+            "  Read: {} memory-mapped, {} streamed",
+            metrics.files_mmapped.get(),
+            metrics.files_streamed.get()
+        );
+        eprintln!(
+            "  Regex cache: {} hits, {} misses",
+            metrics.regex_cache_hits.get(),
+            metrics.regex_cache_misses.get()
+        );
+        eprintln!("  Matches: {}", metrics.matches_found.get());
+        for (name, time) in phases {
+            eprintln!("  {:<8}{time:.2?}", format!("{name}:"));
+        }
+        eprintln!("  Elapsed: {elapsed:.2?}");
+        eprintln!("  Throughput: {throughput:.2} MB/s");
+    }
+
+    /// Search once, then refine the results with commands read from stdin
+    /// until `quit` or the end of input
+    async fn handle_interactive(
+        &self,
+        pattern: &str,
+        algorithm: &crate::cli::InteractiveAlgorithm,
+        extensions: Option<&[String]>,
+        case_sensitive_extensions: bool,
+        recursive: bool,
+        output_format: &crate::cli::OutputFormat,
+        search_path: &Path,
+    ) -> RfgrepResult<()> {
+        use crate::app::interactive::Step;
+        use crate::cli::InteractiveAlgorithm;
+        use std::io::{BufRead, Write};
+
+        let config = crate::config::Config::load().unwrap_or_default();
+        let walker_options = WalkerOptions {
+            recursive,
+            show_hidden: false,
+            respect_gitignore: config.git.respect_gitignore,
+            respect_global_gitignore: config.git.respect_global_gitignore,
+            respect_git_exclude: config.git.respect_git_exclude,
+            search_dot_git: false,
+            ignore_hidden: true,
+            max_depth: if recursive { None } else { Some(1) },
+            follow_links: config.git.submodules.follow,
+            overrides: Vec::new(),
+            ignore_directories: config.filters.ignore_directories.clone(),
+            respect_rfgrepignore: true,
+        };
+        let files: Vec<PathBuf> = self
+            .collect_files(search_path, walker_options)
+            .into_iter()
+            .map(|file| file.path)
+            .filter(|path| {
+                extensions.is_none_or(|exts| has_extension(path, exts, case_sensitive_extensions))
+            })
+            .collect();
+
+        let (mode, algorithm) = match algorithm {
+            InteractiveAlgorithm::BoyerMoore => (SearchMode::Text, SearchAlgorithm::BoyerMoore),
+            InteractiveAlgorithm::Regex => (SearchMode::Regex, SearchAlgorithm::Regex),
+            InteractiveAlgorithm::Simple => (SearchMode::Text, SearchAlgorithm::Simple),
+        };
+        let search = |pattern: String| {
+            let case_sensitive = self.resolve_case_sensitivity(
+                std::slice::from_ref(&pattern),
+                &mode,
+                false,
+                config.search.case_mode(),
+            );
+            let files = &files;
+            let algorithm = algorithm.clone();
+            let archive_limits = (&config.compression).into();
+            let max_memory_bytes = config.performance.max_memory_usage_mb * 1024 * 1024;
+            let thread_count = config.search.thread_count(None);
+            async move {
+                if matches!(algorithm, SearchAlgorithm::Regex) {
+                    crate::processor::get_or_compile_regex(&pattern)?;
+                }
+                self.perform_search(
+                    files,
+                    &pattern,
+                    Vec::new(),
+                    algorithm,
+                    0,
+                    0,
+                    0,
+                    case_sensitive,
+                    false,
+                    false,
+                    None,
+                    None,
+                    None,
+                    None,
+                    thread_count,
+                    false,
+                    false,
+                    &crate::cli::MatchSort::Path,
+                    crate::cli::Encoding::Auto,
+                    crate::cli::BinaryMode::Skip,
+                    max_memory_bytes,
+                    archive_limits,
+                    None,
+                    false,
+                    false,
+                    None,
+                )
+                .await
+                .map(|matches| (pattern, matches))
+            }
+        };
+
+        let formatter = self.results_formatter(
             output_format,
-            ndjson,
-            count,
-            files_with_matches,
-            quiet,
-        )
+            false,
+            false,
+            false,
+            None,
+            false,
+            false,
+            false,
+            true,
+            None,
+        );
+        let mut session = crate::app::InteractiveSession::new(search_path.to_path_buf(), formatter);
+        let (pattern, matches) = search(pattern.to_string()).await?;
+        session.set_results(pattern, matches);
+
+        let stdin = std::io::stdin();
+        let prompt = is_terminal::is_terminal(&stdin);
+        let mut out = std::io::stdout();
+        session.show(&mut out)?;
+        let mut lines = stdin.lock().lines();
+        loop {
+            if prompt {
+                write!(out, "> ")?;
+                out.flush()?;
+            }
+            let Some(line) = lines.next().transpose()? else {
+                break;
+            };
+            match session.execute(&line, &mut out) {
+                Ok(Step::Continue) => {}
+                Ok(Step::Quit) => break,
+                // A bad pattern keeps the current results
+                Ok(Step::Search(pattern)) => match search(pattern).await {
+                    Ok((pattern, matches)) => {
+                        session.set_results(pattern, matches);
+                        session.show(&mut out)?;
+                    }
+                    Err(e) => eprintln!("rfgrep: {e}"),
+                },
+                Err(e) => eprintln!("rfgrep: {e}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Search and replace across files
+    ///
+    /// Files are only rewritten when `write` is set; otherwise a unified diff of
+    /// the pending changes is printed, colored like text search output. The content is replaced as a whole, so
+    /// line endings that the pattern doesn't touch are preserved byte for byte.
+    fn handle_replace(
+        &self,
+        pattern: &str,
+        replacement: &str,
+        mode: crate::cli::SearchMode,
+        write: bool,
+        backup: bool,
+        extensions: Option<&[String]>,
+        case_sensitive_extensions: bool,
+        recursive: bool,
+        case_sensitive: bool,
+        search_path: &Path,
+        max_filesize: Option<u64>,
+        min_filesize: Option<u64>,
+        newer_than: Option<std::time::SystemTime>,
+        older_than: Option<std::time::SystemTime>,
+        color: crate::cli::ColorChoice,
+        quiet: bool,
+    ) -> RfgrepResult<()> {
+        // Text mode is a literal replacement; word and regex modes may use `$1` etc.
+        let is_literal = mode == SearchMode::Text;
+        let search_pattern = self.build_search_pattern(pattern, mode, true);
+        let search_pattern = if case_sensitive {
+            search_pattern
+        } else {
+            format!("(?i){search_pattern}")
+        };
+        let regex = crate::processor::get_or_compile_regex(&search_pattern)?;
+
+        let config = crate::config::Config::load().unwrap_or_default();
+        let color = Self::use_color(color, config.output.color);
+        let walker_options = WalkerOptions {
+            recursive,
+            show_hidden: false,
+            respect_gitignore: config.git.respect_gitignore,
+            respect_global_gitignore: config.git.respect_global_gitignore,
+            respect_git_exclude: config.git.respect_git_exclude,
+            search_dot_git: false,
+            ignore_hidden: true,
+            max_depth: if recursive { None } else { Some(1) },
+            follow_links: config.git.submodules.follow,
+            overrides: Vec::new(),
+            ignore_directories: config.filters.ignore_directories.clone(),
+            respect_rfgrepignore: true,
+        };
+
+        let mut files: Vec<PathBuf> = self
+            .collect_files(search_path, walker_options)
+            .into_iter()
+            .filter(|file| {
+                let path = file.path.as_path();
+                let extension_ok = extensions
+                    .is_none_or(|exts| has_extension(path, exts, case_sensitive_extensions));
+                let size = file.metadata.len();
+                let size_ok = max_filesize.is_none_or(|max| size <= max)
+                    && min_filesize.is_none_or(|min| size >= min);
+                // Compressed files are searchable but can't be rewritten in place
+                extension_ok
+                    && size_ok
+                    && crate::app::filters::modified_within(&file.metadata, newer_than, older_than)
+                    && !crate::compression::is_compressed(path)
+                    && !crate::processor::is_binary(path)
+            })
+            .map(|file| file.path)
+            .collect();
+        files.sort();
+
+        let mut total_replacements = 0;
+        let mut changed_files = 0;
+        for path in &files {
+            let matches = match search_file(path, &regex) {
+                Ok(matches) => matches,
+                Err(e) => {
+                    eprintln!("Error searching {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            if matches.is_empty() {
+                continue;
+            }
+
+            let original = match std::fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    eprintln!("Skipping {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let replaced = if is_literal {
+                regex.replace_all(&original, regex::NoExpand(replacement))
+            } else {
+                regex.replace_all(&original, replacement)
+            };
+            if replaced == original {
+                continue;
+            }
+
+            let count = regex.find_iter(&original).count();
+            total_replacements += count;
+            changed_files += 1;
+
+            if write {
+                if backup {
+                    let mut backup_path = path.as_os_str().to_owned();
+                    backup_path.push(".bak");
+                    std::fs::copy(path, &backup_path).map_err(RfgrepError::Io)?;
+                }
+                std::fs::write(path, replaced.as_bytes()).map_err(RfgrepError::Io)?;
+                if !quiet {
+                    println!("{}: {} replacement(s)", path.display(), count);
+                }
+            } else {
+                let display = path.display().to_string();
+                let diff = similar::TextDiff::from_lines(original.as_str(), replaced.as_ref());
+                let diff = diff
+                    .unified_diff()
+                    .context_radius(3)
+                    .header(&display, &display)
+                    .to_string();
+                if color {
+                    print!("{}", Self::color_diff(&diff));
+                } else {
+                    print!("{diff}");
+                }
+            }
+        }
+
+        if !quiet {
+            let verb = if write { "Replaced" } else { "Would replace" };
+            eprintln!("{verb} {total_replacements} occurrence(s) in {changed_files} file(s)");
+        }
+
+        Ok(())
+    }
+
+    /// Color a unified diff like `git diff`: removed lines red, added lines
+    /// green and hunk headers cyan
+    fn color_diff(diff: &str) -> String {
+        diff.split_inclusive('\n')
+            .map(|line| {
+                let sgr = if line.starts_with("---") || line.starts_with("+++") {
+                    "1"
+                } else if line.starts_with("@@") {
+                    "36"
+                } else if line.starts_with('-') {
+                    "31"
+                } else if line.starts_with('+') {
+                    "32"
+                } else {
+                    return line.to_string();
+                };
+                let (text, newline) = match line.strip_suffix('\n') {
+                    Some(text) => (text, "\n"),
+                    None => (line, ""),
+                };
+                format!("\x1b[{sgr}m{text}\x1b[0m{newline}")
+            })
+            .collect()
     }
 
     /// Build search pattern based on mode
-    fn build_search_pattern(&self, pattern: &str, mode: crate::cli::SearchMode) -> String {
+    ///
+    /// With `fixed_strings` a text-mode pattern is escaped so regex-based
+    /// consumers match it literally.
+    fn build_search_pattern(
+        &self,
+        pattern: &str,
+        mode: crate::cli::SearchMode,
+        fixed_strings: bool,
+    ) -> String {
         match mode {
+            crate::cli::SearchMode::Text if fixed_strings => regex::escape(pattern),
             crate::cli::SearchMode::Text => pattern.to_string(),
             crate::cli::SearchMode::Word => format!(r"\b{}\b", regex::escape(pattern)),
             crate::cli::SearchMode::Regex => pattern.to_string(),
         }
     }
 
+    /// `extensions` extended by `extra`, e.g. those resolved from `--type`
+    fn with_extra_extensions(
+        extensions: Option<Vec<String>>,
+        extra: &[String],
+    ) -> Option<Vec<String>> {
+        if extra.is_empty() {
+            return extensions;
+        }
+        let mut extensions = extensions.unwrap_or_default();
+        extensions.extend(extra.iter().cloned());
+        Some(extensions)
+    }
+
+    fn print_type_list(&self) {
+        let config = crate::config::Config::load().unwrap_or_default();
+        for (name, extensions) in config.type_definitions.all() {
+            let globs: Vec<String> = extensions.iter().map(|ext| format!("*.{ext}")).collect();
+            println!("{}: {}", name, globs.join(", "));
+        }
+    }
+
+    /// Patterns listed in a `-f` file, one per line. Blank lines and lines
+    /// starting with `#` are skipped; other whitespace is part of the pattern.
+    fn read_pattern_file(&self, path: &Path) -> RfgrepResult<Vec<String>> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            RfgrepError::Other(format!(
+                "Failed to read pattern file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let patterns: Vec<String> = contents
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+        if patterns.is_empty() {
+            return Err(RfgrepError::Other(format!(
+                "Pattern file {} contains no patterns",
+                path.display()
+            )));
+        }
+        Ok(patterns)
+    }
+
+    /// Read the paths for --files-from (`-` is stdin), split on `separator`;
+    /// empty entries are skipped
+    fn read_file_list(&self, list: &Path, separator: u8) -> RfgrepResult<Vec<PathBuf>> {
+        let contents = if list == Path::new("-") {
+            let mut contents = Vec::new();
+            std::io::Read::read_to_end(&mut std::io::stdin(), &mut contents).map(|_| contents)
+        } else {
+            std::fs::read(list)
+        }
+        .map_err(|e| {
+            RfgrepError::Other(format!(
+                "Failed to read file list {}: {}",
+                list.display(),
+                e
+            ))
+        })?;
+
+        Ok(contents
+            .split(|&byte| byte == separator)
+            .map(|entry| match separator {
+                b'\n' => entry.strip_suffix(b"\r").unwrap_or(entry),
+                _ => entry,
+            })
+            .filter(|entry| !entry.is_empty())
+            .map(Self::path_from_bytes)
+            .collect())
+    }
+
+    #[cfg(unix)]
+    fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+    }
+
+    #[cfg(not(unix))]
+    fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+        PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Combine one or more patterns into a single regex alternation
+    ///
+    /// Text-mode patterns are escaped, so a literal that isn't valid regex
+    /// syntax (e.g. `a(b`) can be mixed with other patterns safely.
+    fn build_combined_pattern(
+        &self,
+        patterns: &[String],
+        mode: crate::cli::SearchMode,
+        fixed_strings: bool,
+    ) -> String {
+        if let [single] = patterns {
+            return self.build_search_pattern(single, mode, fixed_strings);
+        }
+
+        patterns
+            .iter()
+            .map(|p| format!("(?:{})", self.build_search_pattern(p, mode.clone(), true)))
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+
+    /// Decide whether to match case-sensitively
+    ///
+    /// In smart case, any uppercase letter in a pattern makes the search
+    /// case-sensitive. Escape sequences like `\W` don't count in regex mode.
+    fn resolve_case_sensitivity(
+        &self,
+        patterns: &[String],
+        mode: &crate::cli::SearchMode,
+        fixed_strings: bool,
+        case_mode: crate::cli::CaseMode,
+    ) -> bool {
+        match case_mode {
+            crate::cli::CaseMode::Sensitive => return true,
+            crate::cli::CaseMode::Insensitive => return false,
+            crate::cli::CaseMode::Smart => {}
+        }
+
+        let is_regex = *mode == SearchMode::Regex && !fixed_strings;
+        patterns.iter().any(|pattern| {
+            let mut escaped = false;
+            pattern.chars().any(|c| {
+                let literal = !escaped;
+                escaped = is_regex && !escaped && c == '\\';
+                literal && c.is_uppercase()
+            })
+        })
+    }
+
     /// Map CLI search algorithm to internal algorithm
     fn map_search_algorithm(&self, algorithm: CliSearchAlgorithm) -> SearchAlgorithm {
         match algorithm {
             CliSearchAlgorithm::BoyerMoore => SearchAlgorithm::BoyerMoore,
             CliSearchAlgorithm::Regex => SearchAlgorithm::Regex,
             CliSearchAlgorithm::Simple => SearchAlgorithm::Simple,
+            CliSearchAlgorithm::Fuzzy => SearchAlgorithm::Fuzzy,
         }
     }
 
     /// Collect files from directory
-    fn collect_files(&self, search_path: &Path, options: WalkerOptions) -> Vec<std::path::PathBuf> {
-        let entries: Vec<_> = walk_dir_with_options(search_path, options).collect();
-        entries
+    fn collect_files(&self, search_path: &Path, options: WalkerOptions) -> Vec<FileEntry> {
+        let follow_links = options.follow_links;
+        let files = walk_dir_with_options(search_path, options)
+            // Symlinks found while walking are only searched when following links;
+            // an explicitly given path is always searched
+            .filter(|entry| follow_links || entry.depth() == 0 || !entry.path_is_symlink())
+            .filter_map(|entry| FileEntry::from_dir_entry(&entry))
+            .filter(FileEntry::is_file)
+            .collect();
+        Self::dedupe_files(files)
+    }
+
+    /// Drop files that resolve to one already listed (through a symlink or,
+    /// on Unix, a hard link), keeping the first path each was found under
+    fn dedupe_files(files: Vec<FileEntry>) -> Vec<FileEntry> {
+        let mut seen_paths = std::collections::HashSet::new();
+        #[cfg(unix)]
+        let mut seen_inodes = std::collections::HashSet::new();
+        files
             .into_iter()
-            .filter(|entry| entry.path().is_file())
-            .map(|entry| entry.path().to_path_buf())
+            .filter(|file| {
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::MetadataExt;
+                    if !seen_inodes.insert((file.metadata.dev(), file.metadata.ino())) {
+                        return false;
+                    }
+                }
+                match file.path.canonicalize() {
+                    Ok(canonical) => seen_paths.insert(canonical),
+                    Err(_) => true,
+                }
+            })
             .collect()
     }
 
@@ -432,29 +1524,68 @@ impl RfgrepApp {
         &self,
         filtered_files: &[std::path::PathBuf],
         search_pattern: &str,
+        literal_patterns: Vec<String>,
         search_algorithm: SearchAlgorithm,
-        context_lines: usize,
+        max_distance: usize,
+        context_before: usize,
+        context_after: usize,
         case_sensitive: bool,
+        word_regexp: bool,
         invert_match: bool,
         max_matches: Option<usize>,
+        max_count: Option<usize>,
+        line_range: Option<crate::cli::LineRange>,
         timeout_per_file: Option<u64>,
-        threads: Option<usize>,
+        thread_count: usize,
         files_with_matches: bool,
         count: bool,
+        sort: &crate::cli::MatchSort,
+        encoding: crate::cli::Encoding,
+        binary_mode: crate::cli::BinaryMode,
+        max_memory_bytes: u64,
+        archive_limits: crate::archive::ArchiveLimits,
+        preprocessor: Option<crate::preprocess::Preprocessor>,
+        no_messages: bool,
+        show_progress: bool,
+        metrics: Option<&Metrics>,
     ) -> RfgrepResult<Vec<crate::processor::SearchMatch>> {
         let config = StreamingConfig {
             algorithm: search_algorithm,
-            context_lines,
+            patterns: literal_patterns,
+            context_before,
+            context_after,
             case_sensitive,
+            word_regexp,
             invert_match,
             max_matches,
+            max_count,
+            line_range,
+            encoding,
+            binary_mode,
+            max_memory_bytes: Some(max_memory_bytes),
+            archive_limits,
             timeout_per_file,
+            max_distance,
             chunk_size: 8192,
             buffer_size: 65536,
+            max_line_bytes: crate::streaming_search::DEFAULT_MAX_LINE_BYTES,
+            mmap_threshold: crate::processor::get_adaptive_mmap_threshold(),
+            preprocessor,
+            no_messages,
         };
 
-        let thread_count = threads.unwrap_or_else(|| num_cpus::get().min(8));
-        let pipeline = StreamingSearchPipeline::new(config);
+        // Statistics come from the same per-file accounting as the progress bar
+        let progress =
+            (show_progress || metrics.is_some()).then(|| Arc::new(SearchProgress::default()));
+        let progress_bar = progress
+            .as_ref()
+            .filter(|_| show_progress)
+            .map(|progress| self.start_progress_bar(filtered_files.len(), progress.clone()));
+        let mut pipeline =
+            StreamingSearchPipeline::new(config).with_cancellation(self.shutdown.clone());
+        if let Some(progress) = &progress {
+            pipeline = pipeline.with_progress(progress.clone());
+        }
         let file_refs: Vec<&Path> = filtered_files.iter().map(|p| p.as_path()).collect();
 
         // Dynamic parallel threshold based on mode
@@ -467,14 +1598,23 @@ impl RfgrepApp {
         };
 
         // Aggressively parallelize files-with-matches mode
-        if files_with_matches {
+        let mut all_matches = if files_with_matches {
             use rayon::prelude::*;
+            // Per-file timeouts need the runtime's timer on rayon's threads too
+            let runtime = tokio::runtime::Handle::current();
             let matches: Vec<crate::processor::SearchMatch> = file_refs
                 .par_iter()
                 .filter_map(|file| {
+                    if self.shutdown.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    let _runtime = runtime.enter();
                     let found = futures::executor::block_on(
                         pipeline.search_file_fast_exit(file, search_pattern),
                     );
+                    if let Some(progress) = &progress {
+                        progress.file_done(file);
+                    }
                     match found {
                         Ok(true) => Some(crate::processor::SearchMatch {
                             path: file.to_path_buf(),
@@ -484,23 +1624,179 @@ impl RfgrepApp {
                     }
                 })
                 .collect();
-            Ok(matches)
+            matches
         } else if file_refs.len() > parallel_threshold {
             pipeline
                 .search_files_parallel(&file_refs, search_pattern, thread_count)
-                .await
+                .await?
         } else {
             let mut all_matches = Vec::new();
             for file in filtered_files {
+                if self.shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
                 match pipeline.search_file(file, search_pattern).await {
                     Ok(matches) => all_matches.extend(matches),
+                    Err(e) if no_messages => {
+                        log::debug!("Error searching {}: {}", file.display(), e);
+                    }
                     Err(e) => {
                         eprintln!("Error searching {}: {}", file.display(), e);
                     }
                 }
+                if let Some(progress) = &progress {
+                    progress.file_done(file);
+                }
+            }
+            all_matches
+        };
+
+        if let Some((bar, ticker)) = progress_bar {
+            ticker.abort();
+            bar.finish_and_clear();
+        }
+        if let (Some(metrics), Some(progress)) = (metrics, &progress) {
+            metrics.files_scanned.inc_by(progress.files_done() as u64);
+            metrics.bytes_scanned.inc_by(progress.bytes_done());
+        }
+
+        // Serial and parallel searches must report matches in the same order
+        self.sort_matches(&mut all_matches, sort);
+        if let Some(max) = max_matches.filter(|_| !files_with_matches) {
+            all_matches.truncate(max);
+        }
+        Ok(all_matches)
+    }
+
+    /// Draw a bar on stderr that follows `progress` until the ticker is aborted
+    fn start_progress_bar(
+        &self,
+        total_files: usize,
+        progress: Arc<SearchProgress>,
+    ) -> (indicatif::ProgressBar, tokio::task::JoinHandle<()>) {
+        let bar = indicatif::ProgressBar::with_draw_target(
+            Some(total_files as u64),
+            indicatif::ProgressDrawTarget::stderr(),
+        );
+        if let Ok(style) = indicatif::ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} files {wide_msg}",
+        ) {
+            bar.set_style(style.progress_chars("=>-"));
+        }
+
+        let ticker = tokio::spawn({
+            let bar = bar.clone();
+            async move {
+                loop {
+                    bar.set_position(progress.files_done() as u64);
+                    if let Some(file) = progress.current_file() {
+                        bar.set_message(file.display().to_string());
+                    }
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                }
             }
-            Ok(all_matches)
+        });
+        (bar, ticker)
+    }
+
+    /// Order matches for output
+    fn sort_matches(
+        &self,
+        matches: &mut [crate::processor::SearchMatch],
+        sort: &crate::cli::MatchSort,
+    ) {
+        match sort {
+            crate::cli::MatchSort::Path => {
+                matches.sort_by(|a, b| {
+                    (&a.path, a.line_number, a.column_start).cmp(&(
+                        &b.path,
+                        b.line_number,
+                        b.column_start,
+                    ))
+                });
+            }
+            crate::cli::MatchSort::Line => {
+                matches.sort_by(|a, b| {
+                    (a.line_number, &a.path, a.column_start).cmp(&(
+                        b.line_number,
+                        &b.path,
+                        b.column_start,
+                    ))
+                });
+            }
+            crate::cli::MatchSort::None => {}
+        }
+    }
+
+    /// Search whole files with a regex that may span lines (`-U`)
+    fn perform_multiline_search(
+        &self,
+        filtered_files: &[std::path::PathBuf],
+        search_pattern: &str,
+        context_before: usize,
+        context_after: usize,
+        case_sensitive: bool,
+        word_regexp: bool,
+        max_matches: Option<usize>,
+        max_count: Option<usize>,
+        sort: &crate::cli::MatchSort,
+        preprocessor: Option<&crate::preprocess::Preprocessor>,
+        metrics: Option<&Metrics>,
+    ) -> RfgrepResult<Vec<crate::processor::SearchMatch>> {
+        use rayon::prelude::*;
+
+        let flags = if case_sensitive { "(?s)" } else { "(?si)" };
+        let regex = crate::processor::get_or_compile_regex(&format!("{flags}{search_pattern}"))?;
+
+        let mut matches: Vec<_> = filtered_files
+            .par_iter()
+            .flat_map_iter(|file| {
+                if self.shutdown.load(Ordering::Relaxed) {
+                    return Vec::new();
+                }
+                // Files that aren't valid UTF-8 can't be matched as a whole
+                let content = match preprocessor.filter(|pre| pre.applies_to(file)) {
+                    Some(preprocessor) => preprocessor.run(file).and_then(|output| {
+                        String::from_utf8(output).map_err(|e| RfgrepError::Other(e.to_string()))
+                    }),
+                    None => std::fs::read_to_string(file).map_err(RfgrepError::Io),
+                };
+                let content = match content {
+                    Ok(content) => content,
+                    Err(e) => {
+                        log::debug!("Skipping {} for multiline search: {}", file.display(), e);
+                        return Vec::new();
+                    }
+                };
+                if let Some(metrics) = metrics {
+                    metrics.files_scanned.inc();
+                    metrics.bytes_scanned.inc_by(content.len() as u64);
+                }
+                let mut matches = crate::processor::find_matches_multiline(
+                    &content,
+                    &regex,
+                    file,
+                    context_before,
+                    context_after,
+                )
+                .unwrap_or_default();
+                if word_regexp {
+                    matches.retain(|m| {
+                        let end = m.byte_offset + m.matched_text.len();
+                        crate::processor::is_whole_word(&content, m.byte_offset, end)
+                    });
+                }
+                if let Some(max) = max_count {
+                    matches.truncate(max);
+                }
+                matches
+            })
+            .collect();
+        self.sort_matches(&mut matches, sort);
+        if let Some(max) = max_matches {
+            matches.truncate(max);
         }
+        Ok(matches)
     }
 
     /// Output the search results
@@ -512,15 +1808,47 @@ impl RfgrepApp {
         output_format: crate::cli::OutputFormat,
         ndjson: bool,
         count: bool,
+        count_total: bool,
+        count_matches: bool,
         files_with_matches: bool,
+        null: bool,
+        only_matching: bool,
+        languages: Option<&crate::config::TypeDefinitions>,
+        json_summary: Option<std::time::Duration>,
+        vimgrep: bool,
+        column: bool,
+        heading: bool,
+        show_filename: bool,
+        colors: Option<&Palette>,
         quiet: bool,
     ) -> RfgrepResult<()> {
-        if all_matches.is_empty() {
-            self.output_no_matches(count, files_with_matches, output_format)
-        } else if count {
-            println!("{}", all_matches.len());
+        // A single explicit file has nothing to break the count down by,
+        // unless -H asks for its name anyway
+        let total_only = count_total || count && search_path.is_file() && !show_filename;
+
+        // An empty SARIF log is still a valid report for CI uploads, and a
+        // JSON summary is still owed when nothing matched
+        if all_matches.is_empty()
+            && output_format != crate::cli::OutputFormat::Sarif
+            && json_summary.is_none()
+        {
+            self.output_no_matches(
+                total_only,
+                files_with_matches || count || count_matches,
+                output_format,
+            )
+        } else if total_only {
+            // Like grep, count lines; a line with several hits counts once
+            use std::collections::HashSet;
+            let lines: HashSet<(&Path, usize)> = all_matches
+                .iter()
+                .map(|m| (m.path.as_path(), m.line_number))
+                .collect();
+            println!("{}", lines.len());
+        } else if count || count_matches {
+            self.output_file_counts(all_matches, count, show_filename, null)
         } else if files_with_matches {
-            self.output_files_with_matches(all_matches)
+            self.output_files_with_matches(all_matches, null)
         } else {
             self.output_matches(
                 all_matches,
@@ -528,8 +1856,17 @@ impl RfgrepApp {
                 search_path,
                 output_format,
                 ndjson,
+                null,
+                only_matching,
+                languages,
+                json_summary,
+                vimgrep,
+                column,
+                heading,
+                show_filename,
+                colors,
                 quiet,
-            )
+            )?
         }
 
         Ok(())
@@ -539,27 +1876,148 @@ impl RfgrepApp {
     fn output_no_matches(
         &self,
         count: bool,
-        files_with_matches: bool,
+        per_file: bool,
         output_format: crate::cli::OutputFormat,
     ) {
         if count {
             println!("0");
-        } else if files_with_matches {
-            // Print nothing
-        } else if output_format != crate::cli::OutputFormat::Json {
+        } else if per_file {
+            // Files without matches aren't listed
+        } else if !output_format.is_json() {
             println!("{}", "No matches found".yellow());
         }
     }
 
     /// Output list of files containing matches
-    fn output_files_with_matches(&self, all_matches: &[crate::processor::SearchMatch]) {
+    fn output_files_with_matches(&self, all_matches: &[crate::processor::SearchMatch], null: bool) {
         use std::collections::HashSet;
         let mut unique_files: HashSet<String> = HashSet::new();
         for m in all_matches {
             unique_files.insert(m.path.to_string_lossy().to_string());
         }
-        let mut files: Vec<_> = unique_files.into_iter().collect();
+        let files: Vec<_> = unique_files.into_iter().collect();
+        self.output_files(&files, null);
+    }
+
+    /// Output `path:count` for every file with matches, or just the count
+    /// without file names, counting either matching lines or each occurrence
+    fn output_file_counts(
+        &self,
+        all_matches: &[crate::processor::SearchMatch],
+        lines_only: bool,
+        show_filename: bool,
+        null: bool,
+    ) {
+        use std::collections::{BTreeMap, HashSet};
+        let mut counts: BTreeMap<&Path, usize> = BTreeMap::new();
+        let mut seen_lines = HashSet::new();
+        for m in all_matches {
+            if lines_only && !seen_lines.insert((m.path.as_path(), m.line_number)) {
+                continue;
+            }
+            *counts.entry(m.path.as_path()).or_default() += 1;
+        }
+
+        let terminator = if null { '\0' } else { '\n' };
+        for (path, count) in counts {
+            if show_filename {
+                print!("{}:{count}{terminator}", path.display());
+            } else {
+                print!("{count}{terminator}");
+            }
+        }
+    }
+
+    /// Echo every line of the searched files, highlighting the matches
+    fn output_passthru(
+        &self,
+        searched_files: &[PathBuf],
+        all_matches: &[crate::processor::SearchMatch],
+        encoding: crate::cli::Encoding,
+        archive_limits: crate::archive::ArchiveLimits,
+        show_path: bool,
+        colors: Option<&Palette>,
+    ) -> RfgrepResult<()> {
+        use std::collections::HashMap;
+        use std::io::Write;
+
+        // Every occurrence is its own match, so its spans are all there is to highlight
+        let mut spans: HashMap<(&Path, usize), Vec<(usize, usize)>> = HashMap::new();
+        for m in all_matches {
+            spans
+                .entry((m.path.as_path(), m.line_number))
+                .or_default()
+                .push((m.column_start, m.column_end));
+        }
+        for line_spans in spans.values_mut() {
+            line_spans.sort_unstable();
+        }
+
+        let mut files: Vec<&PathBuf> = searched_files.iter().collect();
         files.sort();
+        let formatter = OutputFormatter::new(crate::output_formats::OutputFormat::Text)
+            .with_color(colors.is_some())
+            .with_palette(colors.cloned().unwrap_or_default());
+        // Lines are read the way the search read them, so their numbers agree
+        let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+            encoding,
+            archive_limits,
+            ..Default::default()
+        });
+        let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+        for file in files {
+            match crate::compression::CompressionType::from_extension(file) {
+                // Archive entries are reported under virtual paths and can't be echoed
+                Some(
+                    crate::compression::CompressionType::Zip
+                    | crate::compression::CompressionType::Tar,
+                ) => continue,
+                None if crate::processor::is_binary(file) => continue,
+                _ => {}
+            }
+
+            let path = show_path.then_some(file.as_path());
+            let lines = crate::processor::OffsetLines::new(pipeline.open_text(file)?).lossy(true);
+            for (index, line) in lines.enumerate() {
+                let (_, line) = line.map_err(RfgrepError::Io)?;
+                let line_number = index + 1;
+                let line_spans = spans
+                    .get(&(file.as_path(), line_number))
+                    .map_or(&[][..], Vec::as_slice);
+                out.write_all(
+                    formatter
+                        .format_passthru_line(path, line_number, &line, line_spans)
+                        .as_bytes(),
+                )?;
+            }
+        }
+        out.flush().map_err(RfgrepError::Io)
+    }
+
+    /// Searched files that have no entry in `all_matches`
+    fn files_without_match(
+        &self,
+        searched_files: &[PathBuf],
+        all_matches: &[crate::processor::SearchMatch],
+    ) -> Vec<String> {
+        use std::collections::HashSet;
+        let matched: HashSet<&Path> = all_matches.iter().map(|m| m.path.as_path()).collect();
+        searched_files
+            .iter()
+            .filter(|file| !matched.contains(file.as_path()))
+            .map(|file| file.to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Print file names sorted, one per record
+    fn output_files(&self, files: &[String], null: bool) {
+        let mut files = files.to_vec();
+        files.sort();
+
+        let terminator = if null { '\0' } else { '\n' };
+        for file in files {
+            print!("{file}{terminator}");
+        }
     }
 
     /// Output the actual matches
@@ -570,9 +2028,31 @@ impl RfgrepApp {
         search_path: &Path,
         output_format: crate::cli::OutputFormat,
         ndjson: bool,
+        null: bool,
+        only_matching: bool,
+        languages: Option<&crate::config::TypeDefinitions>,
+        json_summary: Option<std::time::Duration>,
+        vimgrep: bool,
+        column: bool,
+        heading: bool,
+        show_filename: bool,
+        colors: Option<&Palette>,
         quiet: bool,
-    ) {
-        if !quiet && output_format != crate::cli::OutputFormat::Json && !ndjson {
+    ) -> RfgrepResult<()> {
+        use std::io::Write;
+
+        // NUL-separated, only-matching and vimgrep records only apply to the plain text format
+        let plain_text = output_format == crate::cli::OutputFormat::Text && !ndjson;
+        let null = null && plain_text;
+        let only_matching = only_matching && plain_text;
+        let vimgrep = vimgrep && plain_text;
+        let bare = null || only_matching || vimgrep;
+        // Every line of these is read as a record, so no banner or padding
+        let records_only = output_format.is_json()
+            || output_format == crate::cli::OutputFormat::GithubActions
+            || ndjson;
+
+        if !quiet && !bare && !records_only {
             println!(
                 "\n{} {} {}",
                 "Found".green(),
@@ -581,7 +2061,52 @@ impl RfgrepApp {
             );
         }
 
-        let formatter = OutputFormatter::new(if ndjson {
+        let formatter = self
+            .results_formatter(
+                &output_format,
+                ndjson,
+                null,
+                only_matching,
+                languages,
+                vimgrep,
+                column,
+                heading,
+                show_filename,
+                colors,
+            )
+            .with_summary(json_summary);
+
+        // Stream straight to stdout so large JSON results are never held in memory
+        let padded = !(records_only || bare);
+        let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+        if padded {
+            writeln!(out).map_err(RfgrepError::Io)?;
+        }
+        formatter
+            .write_results(&mut out, all_matches, pattern, search_path)
+            .map_err(RfgrepError::Io)?;
+        if padded {
+            writeln!(out).map_err(RfgrepError::Io)?;
+        }
+        out.flush().map_err(RfgrepError::Io)
+    }
+
+    /// Formatter for match output; `null`, `only_matching` and `vimgrep` must
+    /// already be limited to plain text
+    fn results_formatter(
+        &self,
+        output_format: &crate::cli::OutputFormat,
+        ndjson: bool,
+        null: bool,
+        only_matching: bool,
+        languages: Option<&crate::config::TypeDefinitions>,
+        vimgrep: bool,
+        column: bool,
+        heading: bool,
+        show_filename: bool,
+        colors: Option<&Palette>,
+    ) -> OutputFormatter {
+        OutputFormatter::new(if ndjson {
             crate::output_formats::OutputFormat::Json
         } else {
             match output_format {
@@ -592,19 +2117,254 @@ impl RfgrepApp {
                 crate::cli::OutputFormat::Markdown => crate::output_formats::OutputFormat::Markdown,
                 crate::cli::OutputFormat::Csv => crate::output_formats::OutputFormat::Csv,
                 crate::cli::OutputFormat::Tsv => crate::output_formats::OutputFormat::Tsv,
+                crate::cli::OutputFormat::Sarif => crate::output_formats::OutputFormat::Sarif,
+                crate::cli::OutputFormat::GithubActions => {
+                    crate::output_formats::OutputFormat::GithubActions
+                }
             }
         })
-        .with_ndjson(ndjson);
+        .with_ndjson(ndjson)
+        .with_null_separator(null)
+        .with_only_matching(only_matching)
+        .with_file_type(languages.cloned())
+        .with_vimgrep(vimgrep)
+        .with_column(column)
+        .with_heading(heading)
+        .with_filename(show_filename)
+        .with_color(colors.is_some())
+        .with_palette(colors.cloned().unwrap_or_default())
+    }
 
-        let output = formatter.format_results(all_matches, pattern, search_path);
+    /// Whether to color output: `--color` wins, then the config's
+    /// `output.color`, and otherwise only a terminal gets colors
+    fn use_color(choice: crate::cli::ColorChoice, configured: crate::config::ColorMode) -> bool {
+        use crate::cli::ColorChoice;
+        use crate::config::ColorMode;
+        match (choice, configured) {
+            (ColorChoice::Always, _) => true,
+            (ColorChoice::Never, _) => false,
+            (ColorChoice::Auto, ColorMode::Always) => true,
+            (ColorChoice::Auto, ColorMode::Never) => false,
+            (ColorChoice::Auto, ColorMode::Auto) => is_terminal::is_terminal(&std::io::stdout()),
+        }
+    }
 
-        if output_format == crate::cli::OutputFormat::Json || ndjson {
-            print!("{output}");
+    /// Put `text` on the system clipboard, warning instead of failing when
+    /// there is no clipboard (e.g. over SSH or in CI)
+    fn copy_to_clipboard(&self, text: &str) {
+        // Lets tests observe what would have been copied
+        let copied = if let Ok(path) = std::env::var("RFGREP_CLIPBOARD_FILE") {
+            std::fs::write(path, text).map_err(|e| e.to_string())
         } else {
-            println!("\n{output}");
+            arboard::Clipboard::new()
+                .and_then(|mut clipboard| clipboard.set_text(text))
+                .map_err(|e| e.to_string())
+        };
+        match copied {
+            Ok(()) => eprintln!("{}", "Copied results to clipboard".green()),
+            Err(e) => eprintln!("{} {}", "Warning: could not copy to clipboard:".yellow(), e),
         }
     }
 
+    /// Print the lines matching `pattern` as they're appended to `path`,
+    /// until Ctrl-C
+    fn handle_watch(
+        &self,
+        pattern: &str,
+        path: &Path,
+        mode: crate::cli::SearchMode,
+        case_sensitive: bool,
+        after: usize,
+        poll_interval: std::time::Duration,
+    ) -> RfgrepResult<()> {
+        let search_pattern = self.build_search_pattern(pattern, mode, true);
+        let search_pattern = if case_sensitive {
+            search_pattern
+        } else {
+            format!("(?i){search_pattern}")
+        };
+        let regex = crate::processor::get_or_compile_regex(&search_pattern)?;
+        let watcher = crate::app::LogWatcher::new(path, regex)
+            .with_poll_interval(poll_interval)
+            .with_after(after);
+        watcher.run(&self.shutdown, &mut std::io::stdout().lock())?;
+        Ok(())
+    }
+
+    /// Time each scenario (or the given patterns) over `root`'s corpus and
+    /// write the report to `root/results`
+    fn handle_simulate(
+        &self,
+        root: &Path,
+        format: crate::cli::SimulationFormat,
+        patterns: &[String],
+        parallel: bool,
+        threads: Option<usize>,
+    ) -> RfgrepResult<()> {
+        use crate::cli::SimulationFormat;
+        use rayon::prelude::*;
+        use std::fs;
+        use std::time::Instant;
+
+        // Keep stdout for the report itself when it's meant for machines
+        let json = format == SimulationFormat::Json;
+        let note = |message: String| {
+            if json {
+                eprintln!("{message}");
+            } else {
+                println!("{message}");
+            }
+        };
+
+        let results_dir = root.join("results");
+        fs::create_dir_all(&results_dir).map_err(RfgrepError::Io)?;
+
+        let search_root = root.join("bench_data");
+        let search_root = if search_root.exists() {
+            search_root
+        } else {
+            root.to_path_buf()
+        };
+
+        let collect = || -> Vec<PathBuf> {
+            crate::walker::walk_dir(&search_root, true, true)
+                .filter(|e| e.path().is_file())
+                .map(|e| e.path().to_path_buf())
+                .collect()
+        };
+        let mut files = collect();
+        if files.is_empty() {
+            note(format!(
+                "Warning: No files found in search directory: {}",
+                search_root.display()
+            ));
+            note("Creating a small test file for simulation...".to_string());
+
+            let test_file = search_root.join("test_simulation.txt");
+            let test_content = "This is a test file for simulation.\nIt contains some error messages.\nTODO: Add more test cases.\nThe quick brown fox jumps over the lazy dog.\n";
+            fs::write(&test_file, test_content).map_err(RfgrepError::Io)?;
+
+            files = collect();
+            if files.is_empty() {
+                return Err(RfgrepError::Other(
+                    "No files available for simulation".to_string(),
+                ));
+            }
+        }
+
+        note(format!(
+            "Running simulations on {} files in {}",
+            files.len(),
+            search_root.display()
+        ));
+
+        let scenarios: Vec<(String, String)> = if patterns.is_empty() {
+            vec![
+                ("regex_short".to_string(), r"error".to_string()),
+                ("word_boundary".to_string(), r"\bTODO\b".to_string()),
+                (
+                    "literal_long".to_string(),
+                    "the quick brown fox jumps over".to_string(),
+                ),
+            ]
+        } else {
+            patterns
+                .iter()
+                .enumerate()
+                .map(|(i, pattern)| (format!("pattern_{}", i + 1), pattern.clone()))
+                .collect()
+        };
+
+        // Scenarios run serially unless asked, so timings stay comparable
+        // with earlier reports
+        let thread_count = if parallel {
+            let config = crate::config::Config::load().unwrap_or_default();
+            config.search.thread_count(threads)
+        } else {
+            1
+        };
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(thread_count)
+            .build()
+            .map_err(|e| RfgrepError::Other(format!("Failed to start thread pool: {e}")))?;
+
+        let mut results = Vec::new();
+        for (name, pattern) in scenarios {
+            let regex = crate::processor::get_or_compile_regex(&pattern)?;
+            let start = Instant::now();
+            let (matches, files_processed) = if parallel {
+                pool.install(|| {
+                    files
+                        .par_iter()
+                        .filter_map(|f| search_file(f, &regex).ok())
+                        .map(|matches| (matches.len(), 1))
+                        .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1))
+                })
+            } else {
+                files
+                    .iter()
+                    .filter_map(|f| search_file(f, &regex).ok())
+                    .fold((0, 0), |(total, count), matches| {
+                        (total + matches.len(), count + 1)
+                    })
+            };
+            results.push(serde_json::json!({
+                "name": name,
+                "pattern": pattern,
+                "millis": start.elapsed().as_millis() as u64,
+                "matches": matches,
+                "files": files_processed,
+            }));
+        }
+
+        let (report, report_path) = if json {
+            let report = serde_json::json!({
+                "environment": {
+                    "parallel": parallel,
+                    "threads": thread_count,
+                    "simd_backend": crate::simd::detected_backend().name(),
+                    "files": files.len(),
+                    "root": search_root.display().to_string(),
+                },
+                "scenarios": results,
+            });
+            let report = serde_json::to_string_pretty(&report)
+                .map_err(|e| RfgrepError::Other(format!("Failed to encode report: {e}")))?;
+            (report + "\n", results_dir.join("simulations.json"))
+        } else {
+            // Parallel timings get their own column so they aren't mistaken
+            // for the serial ones
+            let mut report = String::from("Scenario,Millis,Matches,Files");
+            report.push_str(if parallel { ",Threads\n" } else { "\n" });
+            for result in &results {
+                report.push_str(&format!(
+                    "{},{},{},{}",
+                    result["name"].as_str().unwrap_or_default(),
+                    result["millis"],
+                    result["matches"],
+                    result["files"]
+                ));
+                if parallel {
+                    report.push_str(&format!(",{thread_count}"));
+                }
+                report.push('\n');
+            }
+            (report, results_dir.join("simulations.csv"))
+        };
+
+        fs::write(&report_path, &report).map_err(RfgrepError::Io)?;
+        note(format!(
+            "Simulations complete. Report: {}",
+            report_path.display()
+        ));
+        if json {
+            print!("{report}");
+        } else {
+            println!("\n{report}");
+        }
+        Ok(())
+    }
+
     fn handle_completions(&self, shell: clap_complete::Shell) -> RfgrepResult<()> {
         use clap::CommandFactory;
         let mut cmd = Cli::command();
@@ -672,6 +2432,7 @@ impl RfgrepApp {
             CliSearchAlgorithm::BoyerMoore => SearchAlgorithm::BoyerMoore,
             CliSearchAlgorithm::Regex => SearchAlgorithm::Regex,
             CliSearchAlgorithm::Simple => SearchAlgorithm::Simple,
+            CliSearchAlgorithm::Fuzzy => SearchAlgorithm::Fuzzy,
         };
 
         let tui_mode = match mode {
@@ -706,6 +2467,8 @@ impl RfgrepApp {
                 max_depth: None,
                 follow_links: config.git.submodules.follow,
                 overrides: Vec::new(),
+                ignore_directories: config.filters.ignore_directories.clone(),
+                respect_rfgrepignore: true,
             };
 
             let entries: Vec<_> = walk_dir_with_options(&search_root, walker_options).collect();
@@ -730,117 +2493,105 @@ impl RfgrepApp {
     async fn handle_list(
         &self,
         extensions: Option<&[String]>,
+        case_sensitive_extensions: bool,
         long: bool,
         recursive: bool,
         show_hidden: bool,
-        max_size: Option<usize>,
-        min_size: Option<usize>,
+        max_filesize: Option<u64>,
+        min_filesize: Option<u64>,
         _detailed: bool,
         simple: bool,
         stats: bool,
         sort: crate::cli::SortCriteria,
         reverse: bool,
         limit: Option<usize>,
-        _copy: bool,
-        _output_format: crate::cli::OutputFormat,
+        copy: bool,
+        duplicates: bool,
+        binary_only: bool,
+        text_only: bool,
+        tree: bool,
+        newer_than: Option<std::time::SystemTime>,
+        older_than: Option<std::time::SystemTime>,
+        output_format: crate::cli::OutputFormat,
         cmd_path: Option<&Path>,
         cmd_path_flag: Option<&Path>,
         default_path: &Path,
     ) -> RfgrepResult<()> {
-        use crate::walker::{walk_dir_with_options, WalkerOptions};
         let search_path = cmd_path_flag.or(cmd_path).unwrap_or(default_path);
 
         // Load config
         let config = crate::config::Config::load().unwrap_or_default();
 
-        // Show hidden defaults to respecting gitignore unless show_hidden is explicitly true
-        // But logic in walker.rs is: respect_gitignore defaults to !show_hidden
-        // Here we override with config but still respect show_hidden implication if user wants to see hidden things
+        // Like search's --hidden, showing dotfiles leaves ignore files applied
         let walker_options = WalkerOptions {
             recursive,
             show_hidden,
-            respect_gitignore: config.git.respect_gitignore && !show_hidden,
-            respect_global_gitignore: config.git.respect_global_gitignore && !show_hidden,
-            respect_git_exclude: config.git.respect_git_exclude && !show_hidden,
+            respect_gitignore: config.git.respect_gitignore,
+            respect_global_gitignore: config.git.respect_global_gitignore,
+            respect_git_exclude: config.git.respect_git_exclude,
             search_dot_git: config.git.search_dot_git,
             ignore_hidden: !show_hidden,
             max_depth: if recursive { None } else { Some(1) },
             follow_links: config.git.submodules.follow,
             overrides: Vec::new(),
+            ignore_directories: config.filters.ignore_directories.clone(),
+            respect_rfgrepignore: true,
         };
 
-        let entries: Vec<_> = walk_dir_with_options(search_path, walker_options).collect();
-        let mut files: Vec<_> = entries
-            .into_iter()
-            .filter(|entry| entry.path().is_file())
-            .map(|entry| entry.path().to_path_buf())
-            .collect();
+        // Each file is stat-ed once while walking; filters and sorting reuse it
+        let mut files: Vec<FileEntry> = walk_files(search_path, walker_options).collect();
 
-        files.retain(|path| {
+        files.retain(|file| {
+            let path = &file.path;
             if let Some(exts) = extensions {
-                if let Some(ext) = path.extension() {
-                    if let Some(ext_str) = ext.to_str() {
-                        if !exts.iter().any(|e| e.eq_ignore_ascii_case(ext_str)) {
-                            return false;
-                        }
-                    } else {
-                        return false;
-                    }
-                } else {
+                if !has_extension(path, exts, case_sensitive_extensions) {
                     return false;
                 }
             }
 
-            if let Ok(metadata) = path.metadata() {
-                let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-                if let Some(max) = max_size {
-                    if size_mb > max as f64 {
-                        return false;
-                    }
-                }
-                if let Some(min) = min_size {
-                    if size_mb < min as f64 {
-                        return false;
-                    }
-                }
+            let size = file.metadata.len();
+            if max_filesize.is_some_and(|max| size > max)
+                || min_filesize.is_some_and(|min| size < min)
+            {
+                return false;
+            }
+
+            if !crate::app::filters::modified_within(&file.metadata, newer_than, older_than) {
+                return false;
             }
 
-            true
+            // Checked last, since it reads the file
+            match (binary_only, text_only) {
+                (true, _) => file.is_binary(),
+                (_, true) => !file.is_binary(),
+                _ => true,
+            }
         });
 
+        if duplicates {
+            let groups = crate::list::find_duplicates(&files);
+            return crate::list::print_duplicates(&groups, &output_format);
+        }
+
         match sort {
             crate::cli::SortCriteria::Name => {
-                files.sort_by(|a, b| a.file_name().cmp(&b.file_name()))
+                files.sort_by(|a, b| a.path.file_name().cmp(&b.path.file_name()))
             }
             crate::cli::SortCriteria::Size => {
-                files.sort_by(|a, b| {
-                    let size_a = a.metadata().map(|m| m.len()).unwrap_or(0);
-                    let size_b = b.metadata().map(|m| m.len()).unwrap_or(0);
-                    size_a.cmp(&size_b)
-                });
+                files.sort_by_key(|file| file.metadata.len());
             }
             crate::cli::SortCriteria::Date => {
-                files.sort_by(|a, b| {
-                    let time_a = a
-                        .metadata()
-                        .and_then(|m| m.modified())
-                        .unwrap_or(std::time::UNIX_EPOCH);
-                    let time_b = b
-                        .metadata()
-                        .and_then(|m| m.modified())
-                        .unwrap_or(std::time::UNIX_EPOCH);
-                    time_a.cmp(&time_b)
-                });
+                files.sort_by_key(|file| file.metadata.modified().unwrap_or(std::time::UNIX_EPOCH));
             }
             crate::cli::SortCriteria::Type => {
                 files.sort_by(|a, b| {
-                    let ext_a = a.extension().and_then(|e| e.to_str()).unwrap_or("");
-                    let ext_b = b.extension().and_then(|e| e.to_str()).unwrap_or("");
+                    let ext_a = a.path.extension().and_then(|e| e.to_str()).unwrap_or("");
+                    let ext_b = b.path.extension().and_then(|e| e.to_str()).unwrap_or("");
                     ext_a.cmp(ext_b)
                 });
             }
             crate::cli::SortCriteria::Path => {
-                files.sort();
+                files.sort_by(|a, b| a.path.cmp(&b.path));
             }
         }
 
@@ -852,32 +2603,44 @@ impl RfgrepApp {
             files.truncate(limit);
         }
 
-        if stats {
+        // JSON, CSV and TSV describe each file; other formats list it as text
+        let structured = if simple || output_format == crate::cli::OutputFormat::Text {
+            None
+        } else {
+            let infos: Vec<crate::list::FileInfo> = files
+                .iter()
+                .map(crate::list::FileInfo::from_entry)
+                .collect();
+            crate::list::format_listing(&infos, &output_format, long, stats)?
+        };
+
+        if tree {
+            let paths: Vec<PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+            print!("{}", crate::list::format_tree(search_path, &paths));
+            println!("Summary: {} files found", files.len());
+        } else if let Some(listing) = structured {
+            print!("{listing}");
+        } else if stats {
             println!("Summary: {} files found", files.len());
         } else if simple {
             for file in &files {
-                println!("{}", file.display());
+                println!("{}", file.path.display());
             }
         } else {
             for file in &files {
                 if long {
-                    if let Ok(metadata) = file.metadata() {
-                        let size = metadata.len();
-                        let modified = metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
-                        println!(
-                            "{} {} {}",
-                            size,
-                            modified
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs(),
-                            file.display()
-                        );
-                    } else {
-                        println!("{}", file.display());
-                    }
+                    let modified = file.metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
+                    println!(
+                        "{} {} {}",
+                        file.metadata.len(),
+                        modified
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                        file.path.display()
+                    );
                 } else {
-                    println!("{}", file.display());
+                    println!("{}", file.path.display());
                 }
             }
             println!("Summary: {} files found", files.len());
@@ -886,7 +2649,7 @@ impl RfgrepApp {
                 let mut extensions: std::collections::HashMap<String, usize> =
                     std::collections::HashMap::new();
                 for file in &files {
-                    if let Some(ext) = file.extension() {
+                    if let Some(ext) = file.path.extension() {
                         if let Some(ext_str) = ext.to_str() {
                             *extensions.entry(ext_str.to_string()).or_insert(0) += 1;
                         }
@@ -903,6 +2666,14 @@ impl RfgrepApp {
             }
         }
 
+        if copy && !files.is_empty() {
+            let paths: Vec<String> = files
+                .iter()
+                .map(|file| file.path.display().to_string())
+                .collect();
+            self.copy_to_clipboard(&(paths.join("\n") + "\n"));
+        }
+
         Ok(())
     }
 }
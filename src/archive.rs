@@ -1,71 +1,359 @@
-use crate::error::Result as RfgrepResult;
+use crate::compression::CompressionType;
+use crate::config::CompressionConfig;
+use crate::error::{Result as RfgrepResult, RfgrepError};
 use crate::processor::{find_matches_streaming, SearchMatch};
 use regex::Regex;
+use std::cell::Cell;
 use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
+use std::io::{self, BufReader, Cursor, Read, Seek};
+use std::path::{Component, Path, PathBuf};
 
-pub fn search_zip(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch>> {
-    let file = File::open(path).map_err(crate::error::RfgrepError::Io)?;
-    let mut archive =
-        zip::ZipArchive::new(file).map_err(|e| crate::error::RfgrepError::Other(e.to_string()))?;
-    let mut matches = Vec::new();
-
-    for i in 0..archive.len() {
-        let mut file = archive
-            .by_index(i)
-            .map_err(|e| crate::error::RfgrepError::Other(e.to_string()))?;
-        if file.is_file() {
-            // Safety: sanitize filename to prevent path traversal in display
-            let entry_name = file.name().to_string();
-            // Construct a virtual path for the match: archive.zip:entry/path
-            // We use a custom separator or just join.
-            // But Path::join might act weird if entry is absolute (shouldn't be in zip).
-            // A clearer representation might be needed, currently just joining.
-            let entry_path = path.join(&entry_name);
-
-            // Pass the reader wrapper. find_matches_streaming takes BufReader<R>.
-            // Since ZipFile implements Read, we can just use BufReader::new(&mut file).
-            // We clone entry_path to pass it.
-            let reader = BufReader::new(&mut file);
-            if let Ok(file_matches) = find_matches_streaming(reader, pattern, &entry_path) {
-                matches.extend(file_matches);
+/// Bounds on how deep and how much an archive search decompresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveLimits {
+    /// How many levels of archives inside archives are opened; 0 only
+    /// searches the entries of the archive itself
+    pub max_depth: usize,
+    /// Total bytes one archive's entries, or one compressed file, may decompress to
+    pub max_decompressed_bytes: u64,
+}
+
+impl From<&CompressionConfig> for ArchiveLimits {
+    fn from(config: &CompressionConfig) -> Self {
+        Self {
+            max_depth: config.max_archive_depth,
+            max_decompressed_bytes: config.max_decompressed_size_mb * 1024 * 1024,
+        }
+    }
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self::from(&CompressionConfig::default())
+    }
+}
+
+/// Reader that charges every byte it yields to the remaining budget, so a
+/// zip bomb fails instead of filling memory or disk
+struct Limited<'a, R> {
+    inner: R,
+    remaining: &'a Cell<u64>,
+}
+
+impl<R: Read> Read for Limited<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        match self.remaining.get().checked_sub(n as u64) {
+            Some(remaining) => {
+                self.remaining.set(remaining);
+                Ok(n)
+            }
+            None => {
+                self.remaining.set(0);
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "archive exceeds the decompressed size limit",
+                ))
             }
         }
     }
-    Ok(matches)
 }
 
-pub fn search_tar(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch>> {
-    let file = File::open(path).map_err(crate::error::RfgrepError::Io)?;
-    let mut archive = tar::Archive::new(file);
-    let mut matches = Vec::new();
-
-    // tar::Archive::entries returns iterator of Result<Entry>
-    for entry_result in archive.entries().map_err(crate::error::RfgrepError::Io)? {
-        let mut entry = entry_result.map_err(crate::error::RfgrepError::Io)?;
-        if entry.header().entry_type().is_file() {
-            let path_cow = entry.path().map_err(crate::error::RfgrepError::Io)?;
-            let entry_path = path.join(path_cow);
-
-            let reader = BufReader::new(&mut entry);
-            if let Ok(file_matches) = find_matches_streaming(reader, pattern, &entry_path) {
-                matches.extend(file_matches);
+/// State of one archive search, shared by every nesting level
+struct ArchiveSearch<'a> {
+    pattern: &'a Regex,
+    max_depth: usize,
+    remaining: Cell<u64>,
+    matches: Vec<SearchMatch>,
+}
+
+impl<'a> ArchiveSearch<'a> {
+    fn new(pattern: &'a Regex, limits: &ArchiveLimits) -> Self {
+        Self {
+            pattern,
+            max_depth: limits.max_depth,
+            remaining: Cell::new(limits.max_decompressed_bytes),
+            matches: Vec::new(),
+        }
+    }
+
+    fn search_zip<R: Read + Seek>(
+        &mut self,
+        reader: R,
+        path: &Path,
+        depth: usize,
+    ) -> RfgrepResult<()> {
+        let mut archive =
+            zip::ZipArchive::new(reader).map_err(|e| RfgrepError::Other(e.to_string()))?;
+
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| RfgrepError::Other(e.to_string()))?;
+            if entry.is_file() {
+                if let Some(entry_path) = entry_path(path, entry.name()) {
+                    self.search_entry(&mut entry, &entry_path, depth)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn search_tar<R: Read>(&mut self, reader: R, path: &Path, depth: usize) -> RfgrepResult<()> {
+        let mut archive = tar::Archive::new(reader);
+
+        for entry_result in archive.entries().map_err(RfgrepError::Io)? {
+            let mut entry = entry_result.map_err(RfgrepError::Io)?;
+            if entry.header().entry_type().is_file() {
+                let name = entry.path_bytes();
+                if let Some(entry_path) = entry_path(path, &String::from_utf8_lossy(&name)) {
+                    self.search_entry(&mut entry, &entry_path, depth)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Search one entry, opening it as a nested archive or compressed stream
+    /// while the depth allows
+    fn search_entry(
+        &mut self,
+        entry: &mut dyn Read,
+        path: &Path,
+        depth: usize,
+    ) -> RfgrepResult<()> {
+        let nested = CompressionType::from_extension(path).filter(|_| depth < self.max_depth);
+        let result = match nested {
+            Some(CompressionType::Zip) => self.search_nested_zip(entry, path, depth + 1),
+            Some(CompressionType::Tar) => self.search_tar(entry, path, depth + 1),
+            Some(compression) => {
+                match crate::compression::decompress_stream(entry, compression) {
+                    // `logs.tar.gz` holds a tar; anything else is a single compressed file
+                    Ok(decoder) => {
+                        match CompressionType::from_extension(&path.with_extension("")) {
+                            Some(CompressionType::Tar) => self.search_tar(decoder, path, depth + 1),
+                            _ => self.search_text(decoder, path),
+                        }
+                    }
+                    Err(e) => Err(RfgrepError::Io(e)),
+                }
+            }
+            None => self.search_text(entry, path),
+        };
+
+        match result {
+            Err(e) if self.remaining.get() == 0 => Err(e),
+            // An unreadable entry (binary, corrupt) doesn't stop the rest of the archive
+            Err(e) => {
+                log::debug!("Skipping archive entry {}: {}", path.display(), e);
+                Ok(())
+            }
+            Ok(()) => Ok(()),
+        }
+    }
+
+    fn search_nested_zip(
+        &mut self,
+        entry: &mut dyn Read,
+        path: &Path,
+        depth: usize,
+    ) -> RfgrepResult<()> {
+        // Zip needs to seek, so the inner archive is read into memory
+        let mut buffer = Vec::new();
+        let mut limited = Limited {
+            inner: entry,
+            remaining: &self.remaining,
+        };
+        limited.read_to_end(&mut buffer).map_err(RfgrepError::Io)?;
+        self.search_zip(Cursor::new(buffer), path, depth)
+    }
+
+    fn search_text<R: Read>(&mut self, reader: R, path: &Path) -> RfgrepResult<()> {
+        let reader = BufReader::new(Limited {
+            inner: reader,
+            remaining: &self.remaining,
+        });
+        let matches = find_matches_streaming(reader, self.pattern, path)?;
+        self.matches.extend(matches);
+        Ok(())
+    }
+}
+
+/// Virtual path of an archive entry: `outer.zip:inner.tar.gz:file.txt`
+///
+/// Entry names come from the archive itself, so absolute names and `..`
+/// components are refused (like `zip`'s `enclosed_name`) and the entry skipped.
+fn entry_path(archive: &Path, entry_name: &str) -> Option<PathBuf> {
+    let name = entry_name.replace('\\', "/");
+    let mut parts = Vec::new();
+    for component in Path::new(&name).components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy()),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                log::warn!(
+                    "Skipping entry with unsafe name {:?} in {}",
+                    entry_name,
+                    archive.display()
+                );
+                return None;
             }
         }
     }
-    Ok(matches)
+    if parts.is_empty() {
+        return None;
+    }
+    Some(PathBuf::from(format!(
+        "{}:{}",
+        archive.display(),
+        parts.join("/")
+    )))
+}
+
+pub fn search_zip(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch>> {
+    let file = File::open(path).map_err(RfgrepError::Io)?;
+    let mut search = ArchiveSearch::new(pattern, &ArchiveLimits::default());
+    search.search_zip(file, path, 0)?;
+    Ok(search.matches)
+}
+
+pub fn search_tar(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch>> {
+    let file = File::open(path).map_err(RfgrepError::Io)?;
+    let mut search = ArchiveSearch::new(pattern, &ArchiveLimits::default());
+    search.search_tar(file, path, 0)?;
+    Ok(search.matches)
 }
 
 pub fn search_archive(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch>> {
-    let ext = path
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-    match ext.as_str() {
-        "zip" | "jar" => search_zip(path, pattern),
-        "tar" => search_tar(path, pattern),
-        _ => Ok(vec![]), // Should not reach here if called correctly or maybe treat as error?
+    search_archive_with_limits(path, pattern, &ArchiveLimits::default())
+}
+
+/// Search the entries of a zip or tar archive, recursing into archives and
+/// compressed files inside it up to `limits.max_depth` levels
+pub fn search_archive_with_limits(
+    path: &Path,
+    pattern: &Regex,
+    limits: &ArchiveLimits,
+) -> RfgrepResult<Vec<SearchMatch>> {
+    let mut search = ArchiveSearch::new(pattern, limits);
+    let file = File::open(path).map_err(RfgrepError::Io)?;
+    match CompressionType::from_extension(path) {
+        Some(CompressionType::Zip) => search.search_zip(file, path, 0)?,
+        Some(CompressionType::Tar) => search.search_tar(file, path, 0)?,
+        _ => {}
+    }
+    Ok(search.matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz_bytes(name: &str, content: &[u8]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, content).unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_nested_archives_report_virtual_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner_tar = tar_gz_bytes("deep.txt", b"plain\nneedle in tar\n");
+        let inner_zip = zip_bytes(&[("file.txt", b"needle in zip\n")]);
+        let outer = dir.path().join("outer.zip");
+        std::fs::write(
+            &outer,
+            zip_bytes(&[
+                ("inner.zip", &inner_zip),
+                ("inner.tar.gz", &inner_tar),
+                ("top.txt", b"needle on top\n"),
+            ]),
+        )
+        .unwrap();
+
+        let pattern = Regex::new("needle").unwrap();
+        let mut paths: Vec<String> = search_archive(&outer, &pattern)
+            .unwrap()
+            .iter()
+            .map(|m| m.path.display().to_string())
+            .collect();
+        paths.sort();
+        let shown = outer.display();
+        assert_eq!(
+            paths,
+            vec![
+                format!("{shown}:inner.tar.gz:deep.txt"),
+                format!("{shown}:inner.zip:file.txt"),
+                format!("{shown}:top.txt"),
+            ]
+        );
+
+        // Without recursion the nested archives are searched as opaque entries
+        let limits = ArchiveLimits {
+            max_depth: 0,
+            ..Default::default()
+        };
+        let matches = search_archive_with_limits(&outer, &pattern, &limits).unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[test]
+    fn test_unsafe_entry_names_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("evil.zip");
+        std::fs::write(
+            &archive,
+            zip_bytes(&[
+                ("../../etc/passwd", b"needle\n"),
+                ("/abs/secret.txt", b"needle\n"),
+                ("..\\windows.txt", b"needle\n"),
+                ("./docs/../ok.txt", b"needle\n"),
+                ("docs/./fine.txt", b"needle\n"),
+            ]),
+        )
+        .unwrap();
+
+        let pattern = Regex::new("needle").unwrap();
+        let paths: Vec<String> = search_archive(&archive, &pattern)
+            .unwrap()
+            .iter()
+            .map(|m| m.path.display().to_string())
+            .collect();
+        assert_eq!(paths, vec![format!("{}:docs/fine.txt", archive.display())]);
+    }
+
+    #[test]
+    fn test_decompressed_size_limit_stops_the_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("bomb.zip");
+        let big = vec![b'a'; 64 * 1024];
+        let inner = zip_bytes(&[("big.txt", &big)]);
+        std::fs::write(&archive, zip_bytes(&[("inner.zip", &inner)])).unwrap();
+
+        let limits = ArchiveLimits {
+            max_depth: 3,
+            max_decompressed_bytes: 1024,
+        };
+        let pattern = Regex::new("a").unwrap();
+        assert!(search_archive_with_limits(&archive, &pattern, &limits).is_err());
     }
 }
@@ -47,10 +47,11 @@ pub struct Cli {
     #[clap(default_value = ".")]
     pub path: PathBuf,
 
+    /// Log debug records to stderr (or the `--log` file)
     #[clap(long, value_parser, default_value_t = false, global = true)]
     pub verbose: bool,
 
-    /// Suppress all non-essential output (useful for piping)
+    /// Suppress all non-essential output; searches print nothing and only set the exit code
     #[clap(
         long,
         short = 'q',
@@ -63,6 +64,7 @@ pub struct Cli {
     #[clap(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
     pub color: ColorChoice,
 
+    /// Write log records to this file instead of stderr
     #[clap(long, value_parser, global = true)]
     pub log: Option<PathBuf>,
 
@@ -73,24 +75,86 @@ pub struct Cli {
     #[clap(long, value_parser, default_value_t = false, global = true)]
     pub allow_root: bool,
 
-    #[clap(long, value_parser, global = true)]
+    /// Deprecated: use --max-filesize. Skip files larger than this many megabytes
+    #[clap(long, value_name = "MB", value_parser, global = true)]
     pub max_size: Option<usize>,
 
+    /// Skip files larger than SIZE: bytes, or a number with K, M or G
+    /// (powers of 1024) like 500K, 10M or 2G
+    #[clap(long, value_name = "SIZE", value_parser = parse_size, global = true)]
+    pub max_filesize: Option<u64>,
+
+    /// Skip files smaller than SIZE (same units as --max-filesize)
+    #[clap(long, value_name = "SIZE", value_parser = parse_size, global = true)]
+    pub min_filesize: Option<u64>,
+
     #[clap(long, value_parser, default_value_t = false, global = true)]
     pub skip_binary: bool,
 
+    /// Match --extensions and --type exactly, so `rs` doesn't select `.RS`
+    /// files (extensions ignore case by default)
+    #[clap(long, value_parser, default_value_t = false, global = true)]
+    pub case_sensitive_extensions: bool,
+
+    /// Only files modified after WHEN: an age like 30m, 2d or 1w, or a time
+    /// like 2024-05-01 or 2024-05-01T12:00:00Z. Files whose modification
+    /// time can't be read are left out
+    #[clap(long, value_name = "WHEN", value_parser = parse_time_cutoff, global = true)]
+    pub newer_than: Option<std::time::SystemTime>,
+
+    /// Only files modified before WHEN (same forms as --newer-than)
+    #[clap(long, value_name = "WHEN", value_parser = parse_time_cutoff, global = true)]
+    pub older_than: Option<std::time::SystemTime>,
+
     /// Safety policy for file processing
     #[clap(long, value_enum, default_value_t = SafetyPolicy::Default, global = true)]
     pub safety_policy: SafetyPolicy,
 
-    /// Number of threads for parallel file processing
-    #[clap(long, value_parser, global = true)]
+    /// Number of threads for parallel file processing, at most 1024. 0 picks
+    /// one per CPU (up to 8); defaults to the config's `search.threads`
+    #[clap(long, value_parser = parse_thread_count, global = true)]
     pub threads: Option<usize>,
 
     #[clap(subcommand)]
     pub command: Commands,
 }
 
+impl Cli {
+    /// Largest file to search in bytes: `--max-filesize`, or else the
+    /// deprecated `--max-size` in megabytes
+    pub fn max_file_bytes(&self) -> Option<u64> {
+        self.max_filesize
+            .or(self.max_size.map(|mb| mb as u64 * 1024 * 1024))
+    }
+}
+
+/// How a search matches letter case
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CaseMode {
+    Sensitive,
+    Insensitive,
+    /// Insensitive unless the pattern contains an uppercase letter
+    Smart,
+}
+
+impl CaseMode {
+    /// The mode picked by `-s`, `-i` or `-S`, if any (clap keeps only the last)
+    pub fn from_flags(case_sensitive: bool, ignore_case: bool, smart_case: bool) -> Option<Self> {
+        match (case_sensitive, ignore_case, smart_case) {
+            (true, _, _) => Some(Self::Sensitive),
+            (_, true, _) => Some(Self::Insensitive),
+            (_, _, true) => Some(Self::Smart),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum SimulationFormat {
+    Csv,
+    Json,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
 pub enum ColorChoice {
     Auto,
@@ -99,20 +163,40 @@ pub enum ColorChoice {
 }
 
 #[derive(Subcommand, Debug)]
+// Parsed once per run, so the size of the search options doesn't matter
+#[allow(clippy::large_enum_variant)]
 pub enum Commands {
     /// Run simulations and performance benchmarks to evaluate the current implementation
-    #[clap(after_help = r#"
+    #[clap(
+        alias = "bench",
+        after_help = r#"
 SIMULATIONS:
-  Run built-in benchmark scenarios over a test corpus to evaluate performance and limitations.
+  Run built-in benchmark scenarios, or your own patterns, over a test corpus
+  to evaluate performance and limitations. The report is written to
+  results/simulations.csv (or .json) under the corpus path and printed.
 
 EXAMPLES:
   # Run simulations in the current directory (uses bench_data if present)
   rfgrep simulate
 
-  # Run simulations from a specific path
-  rfgrep simulate --path .
-"#)]
-    Simulate {},
+  # Benchmark your own queries over a corpus, as JSON
+  rfgrep ~/src simulate --format json -e 'fn\s+main' -e TODO
+"#
+    )]
+    Simulate {
+        /// Report format; JSON also records the thread count and SIMD backend
+        #[clap(long, value_enum, default_value_t = SimulationFormat::Csv)]
+        format: SimulationFormat,
+
+        /// Regex to benchmark instead of the built-in scenarios (repeatable)
+        #[clap(short = 'e', long = "pattern", value_name = "PATTERN")]
+        patterns: Vec<String>,
+
+        /// Spread each scenario's files over --threads workers instead of
+        /// searching them one by one
+        #[clap(long)]
+        parallel: bool,
+    },
 
     /// Configuration management
     Config {
@@ -139,6 +223,12 @@ EXAMPLES:
   # Search and copy results to clipboard
   rfgrep search "TODO" --copy --extensions rs,md
 
+  # Only search Rust sources (see --type-list for known types)
+  rfgrep search "unsafe" -t rust
+
+  # Print capture groups instead of whole lines
+  rfgrep search '(?P<key>\w+)=(\d+)' --format '${key} -> $2' config.ini
+
   # Pipe input from another command
   cat file.log | rfgrep search "error"
 
@@ -148,18 +238,51 @@ EXAMPLES:
   # Use with command substitution
   echo "test data" | rfgrep search "test"
 
+  # Search the files another tool found (the list wins over piped content)
+  fd -e rs | rfgrep search "unsafe" --files-from -
+
 PERFORMANCE TIPS:
   • Use --skip-binary for faster processing
   • Limit file size with --max-size
   • Use --dry-run to preview files first
   • Pipe data directly for faster processing
 "#)]
+    // -h is --no-filename, as in grep
+    #[clap(disable_help_flag = true)]
     Search {
-        pattern: String,
+        #[clap(required_unless_present_any = ["patterns", "pattern_file", "type_list"])]
+        pattern: Option<String>,
+
+        /// Additional pattern to search for; repeat to match any of several patterns
+        #[clap(long = "pattern", short = 'e', value_parser)]
+        patterns: Vec<String>,
+
+        /// Read patterns from a file, one per line; blank lines and lines starting with `#` are skipped
+        #[clap(short = 'f', long = "file", value_parser)]
+        pattern_file: Option<PathBuf>,
 
         #[clap(long, value_enum, default_value_t = SearchMode::Text)]
         mode: SearchMode,
 
+        /// Treat patterns as literal strings; takes precedence over --mode and a regex --algorithm
+        #[clap(
+            short = 'F',
+            long = "fixed-strings",
+            value_parser,
+            default_value_t = false
+        )]
+        fixed_strings: bool,
+
+        /// Only match whole words: hits must not touch word characters on either side
+        #[clap(
+            short = 'w',
+            long = "word-regexp",
+            value_parser,
+            default_value_t = false
+        )]
+        word_regexp: bool,
+
+        /// Copy the results to the system clipboard
         #[clap(long, value_parser, default_value_t = false)]
         copy: bool,
 
@@ -185,10 +308,35 @@ PERFORMANCE TIPS:
         #[clap(long, value_parser, use_value_delimiter = true)]
         exclude_extensions: Option<Vec<String>>,
 
+        /// Only search files of this type, e.g. rust or python (repeatable)
+        #[clap(long = "type", short = 't', value_parser)]
+        types: Vec<String>,
+
+        /// Skip files of this type (repeatable)
+        #[clap(long = "type-not", short = 'T', value_parser)]
+        type_not: Vec<String>,
+
+        /// Print the file types known to --type and their extensions, then exit
+        #[clap(long, value_parser, default_value_t = false)]
+        type_list: bool,
+
+        /// Only search files whose path matches this glob (repeatable, e.g. '**/*.rs')
+        #[clap(long = "include", value_parser)]
+        include_globs: Vec<String>,
+
+        /// Skip files whose path matches this glob (repeatable); wins over --include
+        #[clap(long = "exclude", value_parser)]
+        exclude_globs: Vec<String>,
+
         /// Search all file types (comprehensive mode)
         #[clap(long, value_parser, default_value_t = false)]
         search_all_files: bool,
 
+        /// Also search hidden files and directories (dotfiles), which are
+        /// skipped by default
+        #[clap(long, value_parser, default_value_t = false)]
+        hidden: bool,
+
         /// Only search text files (conservative mode)
         #[clap(long, value_parser, default_value_t = false)]
         text_only: bool,
@@ -196,33 +344,263 @@ PERFORMANCE TIPS:
         #[clap(short, long, value_parser, default_value_t = true)]
         recursive: bool,
 
-        #[clap(long, value_parser, default_value_t = 0)]
-        context_lines: usize,
+        /// Follow symbolic links to files and directories (cycles are detected)
+        #[clap(long, value_parser, default_value_t = false)]
+        follow_symlinks: bool,
 
+        /// Descend into directories listed in the config's `ignore_directories`
         #[clap(long, value_parser, default_value_t = false)]
+        no_ignore_dirs: bool,
+
+        /// Search the files listed in FILE (`-` for stdin), one per line, instead
+        /// of walking a directory; takes precedence over searching piped input
+        #[clap(long, value_name = "FILE", conflicts_with = "files_from0")]
+        files_from: Option<PathBuf>,
+
+        /// Like --files-from, with NUL-separated paths (e.g. from `fd -0`)
+        #[clap(long, value_name = "FILE")]
+        files_from0: Option<PathBuf>,
+
+        /// Name to report piped input under instead of `<stdin>`
+        #[clap(long, value_name = "NAME")]
+        label: Option<String>,
+
+        /// Lines of context before and after each match
+        #[clap(short = 'C', long, value_parser, default_value_t = 0)]
+        context_lines: usize,
+
+        /// Lines of context before each match; overrides -C
+        #[clap(short = 'B', long, value_parser)]
+        before_context: Option<usize>,
+
+        /// Lines of context after each match; overrides -C
+        #[clap(short = 'A', long, value_parser)]
+        after_context: Option<usize>,
+
+        /// Match letter case exactly (the default, unless the config enables
+        /// smart case). The last of -s, -i and -S wins
+        #[clap(
+            short = 's',
+            long,
+            overrides_with_all = ["ignore_case", "smart_case"]
+        )]
         case_sensitive: bool,
 
+        /// Ignore letter case
+        #[clap(
+            short = 'i',
+            long,
+            overrides_with_all = ["case_sensitive", "smart_case"]
+        )]
+        ignore_case: bool,
+
+        /// Ignore case unless the pattern contains an uppercase letter
+        #[clap(
+            short = 'S',
+            long,
+            overrides_with_all = ["case_sensitive", "ignore_case"]
+        )]
+        smart_case: bool,
+
         #[clap(long, value_parser, default_value_t = false)]
         invert_match: bool,
 
+        /// Let the regex match across lines (`.` also matches newlines)
+        #[clap(
+            short = 'U',
+            long = "multiline",
+            value_parser,
+            default_value_t = false,
+            conflicts_with = "invert_match"
+        )]
+        multiline: bool,
+
         /// Per-file timeout in seconds (abort scanning a file after this many seconds)
         #[clap(long, value_parser)]
         timeout_per_file: Option<u64>,
 
+        /// Search the output of COMMAND instead of each file's contents. It runs
+        /// in the shell with the file on stdin and its path as `$1`, e.g.
+        /// `--pre 'pdftotext "$1" -'`; matches report the original path
+        #[clap(long, value_name = "COMMAND")]
+        pre: Option<String>,
+
+        /// Only preprocess files matching GLOB (repeatable; default all files)
+        #[clap(long = "pre-glob", value_name = "GLOB", requires = "pre")]
+        pre_globs: Vec<String>,
+
         #[clap(long, value_parser)]
         max_matches: Option<usize>,
 
+        /// Stop searching each file after this many matches
+        #[clap(short = 'm', long = "max-count", value_parser)]
+        max_count: Option<usize>,
+
+        /// Only search lines START through END (1-based, inclusive); `START:` runs to the end
+        #[clap(
+            long,
+            value_parser,
+            value_name = "START:END",
+            conflicts_with = "multiline"
+        )]
+        line_range: Option<LineRange>,
+
+        /// Order of the reported matches
+        #[clap(long, value_enum, default_value_t = MatchSort::Path)]
+        sort: MatchSort,
+
+        /// Search files in path order rather than the order the filesystem
+        /// lists them, so output that follows file order (`--sort none`,
+        /// `--passthru`) is the same on every run
+        #[clap(long, value_parser, default_value_t = false)]
+        sort_files: bool,
+
+        /// Encoding of the searched files
+        #[clap(long, value_enum, default_value_t = Encoding::Auto)]
+        encoding: Encoding,
+
+        /// How files that look binary are handled
+        #[clap(long, value_enum, default_value_t = BinaryMode::Skip)]
+        binary_mode: BinaryMode,
+
+        /// Search binary files as if they were text (same as --binary-mode text)
+        #[clap(short = 'a', long = "text", value_parser, default_value_t = false)]
+        binary_as_text: bool,
+
         #[clap(long, value_enum, default_value_t = SearchAlgorithm::BoyerMoore)]
         algorithm: SearchAlgorithm,
 
-        /// Only show count of matches, not the matches themselves
+        /// Maximum edit distance for `--algorithm fuzzy`
+        #[clap(long, value_parser, default_value_t = 1)]
+        max_distance: usize,
+
+        /// Only show the number of matching lines in each file, not the matches themselves
         #[clap(long, short = 'c', value_parser, default_value_t = false)]
         count: bool,
 
+        /// Only show the total number of matching lines across all files
+        #[clap(
+            long = "count-total",
+            value_parser,
+            default_value_t = false,
+            conflicts_with = "count_matches"
+        )]
+        count_total: bool,
+
+        /// Only show the number of matches in each file, counting every occurrence
+        #[clap(
+            long = "count-matches",
+            value_parser,
+            default_value_t = false,
+            conflicts_with = "count"
+        )]
+        count_matches: bool,
+
         /// Only show filenames with matches, not the matches themselves
         #[clap(long, short = 'l', value_parser, default_value_t = false)]
         files_with_matches: bool,
 
+        /// Only show filenames without any match
+        #[clap(
+            short = 'L',
+            long = "files-without-match",
+            value_parser,
+            default_value_t = false,
+            conflicts_with = "files_with_matches"
+        )]
+        files_without_match: bool,
+
+        /// Terminate each output record with NUL instead of newline (for `xargs -0`)
+        #[clap(long = "null", short = '0', value_parser, default_value_t = false)]
+        null: bool,
+
+        /// Print only the matched parts of each line, one match per record
+        #[clap(
+            short = 'o',
+            long = "only-matching",
+            value_parser,
+            default_value_t = false
+        )]
+        only_matching: bool,
+
+        /// Add each match's `language` ("rust", "python", or null) to JSON and
+        /// NDJSON output
+        #[clap(long, value_parser, default_value_t = false)]
+        json_file_type: bool,
+
+        /// End NDJSON output with a `{"type":"summary",...}` line giving the
+        /// matched lines, files with matches and time taken
+        #[clap(long, value_parser, default_value_t = false, requires = "ndjson")]
+        json_summary: bool,
+
+        /// Print every match as `path:line:col:line` for editor quickfix lists,
+        /// repeating a line once per match on it
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            conflicts_with_all = ["only_matching", "passthru", "format_template"]
+        )]
+        vimgrep: bool,
+
+        /// Always show the 1-based column of each match, even with colored output
+        #[clap(long, value_parser, default_value_t = false)]
+        column: bool,
+
+        /// Don't print errors about files that can't be read or searched
+        #[clap(long, value_parser, default_value_t = false)]
+        no_messages: bool,
+
+        /// Print every line, matching or not, highlighting the matches
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            conflicts_with_all = ["invert_match", "only_matching"]
+        )]
+        passthru: bool,
+
+        /// Print each match as TEMPLATE with its capture groups filled in:
+        /// `$1` or `${1}` by number, `${name}` by name, `$$` for a literal `$`.
+        /// Text-mode patterns are treated as regexes.
+        #[clap(
+            long = "format",
+            value_name = "TEMPLATE",
+            value_parser,
+            conflicts_with_all = ["fixed_strings", "invert_match", "only_matching", "passthru"]
+        )]
+        format_template: Option<String>,
+
+        /// Print each file's path once above its matches (default in a terminal)
+        #[clap(long, overrides_with = "no_heading")]
+        heading: bool,
+
+        /// Print the path on every match line (default when piped)
+        #[clap(long, overrides_with = "heading")]
+        no_heading: bool,
+
+        /// Prefix matches with their file name (default when searching a directory)
+        #[clap(short = 'H', long, overrides_with = "no_filename")]
+        with_filename: bool,
+
+        /// Don't prefix matches with their file name (default for a single file)
+        #[clap(short = 'h', long, overrides_with = "with_filename")]
+        no_filename: bool,
+
+        /// Print help
+        #[clap(long, action = clap::ArgAction::Help)]
+        help: Option<bool>,
+
+        /// Show a progress bar on stderr while searching (terminal text output only)
+        #[clap(long, value_parser, default_value_t = false)]
+        progress: bool,
+
+        /// Print files and bytes scanned, how they were read, regex cache hits,
+        /// matches, per-phase timings and throughput on stderr (as JSON with
+        /// JSON output)
+        #[clap(long, value_parser, default_value_t = false, alias = "metrics")]
+        stats: bool,
+
         #[clap(value_parser, last = true)]
         path: Option<PathBuf>,
 
@@ -233,18 +611,18 @@ PERFORMANCE TIPS:
 
     #[clap(after_help = r#"
 INTERACTIVE FEATURES:
-  • Real-time search with live filtering
-  • Keyboard navigation and commands
-  • Result highlighting and selection
-  • Save results to file
-  • Memory-optimized processing
+  • Runs a search, then refines its results with commands read from stdin
+  • Filters narrow the results in place and stack until cleared
+  • Save results to file in the chosen --output-format
+  • Needs no terminal, so commands can be piped in from a script
 
 COMMANDS:
-  n/new   - Start a new search
-  f/filter - Filter current results
-  c/clear - Clear all filters
-  s/save  - Save results to file
-  q/quit  - Exit interactive mode
+  n/new <pattern> - Start a new search
+  f/filter <text> - Keep matches whose line or path contains text
+  c/clear         - Clear all filters
+  s/save <file>   - Save the shown results to a file
+  h/help          - Show the commands
+  q/quit          - Exit interactive mode
 
 EXAMPLES:
   # Start interactive search
@@ -252,6 +630,9 @@ EXAMPLES:
 
   # Interactive search with specific algorithm
   rfgrep interactive "test" --algorithm boyer-moore --recursive
+
+  # Scripted: narrow the results and save them as JSON
+  printf 'f timeout\ns errors.json\nq\n' | rfgrep interactive error --output-format json
 "#)]
     Interactive {
         pattern: String,
@@ -265,6 +646,10 @@ EXAMPLES:
         #[clap(short, long, value_parser, default_value_t = false)]
         recursive: bool,
 
+        /// Format of the results written by `save`
+        #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+        output_format: OutputFormat,
+
         #[clap(value_parser, last = true)]
         path: Option<PathBuf>,
 
@@ -285,11 +670,14 @@ EXAMPLES:
   rfgrep list --long --extensions rs,md
 
   # Recursive listing with hidden files
-  rfgrep list --recursive --show-hidden --extensions rs
+  rfgrep list --recursive --hidden --extensions rs
 
   # List files under 1MB
   rfgrep list --max-size 1 --extensions rs
 
+  # Tree view of a project's Rust sources
+  rfgrep list --tree --recursive --extensions rs
+
 FEATURES:
   • Extension statistics and file counts
   • Binary file detection
@@ -307,13 +695,16 @@ FEATURES:
         #[clap(short, long, value_parser, default_value_t = false)]
         recursive: bool,
 
-        #[clap(long, value_parser, default_value_t = false)]
+        /// Include hidden files and directories (dotfiles)
+        #[clap(long, value_parser, default_value_t = false, alias = "hidden")]
         show_hidden: bool,
 
-        #[clap(long, value_parser)]
+        /// Skip files larger than this many megabytes (--max-filesize takes precedence)
+        #[clap(long, value_name = "MB", value_parser)]
         max_size: Option<usize>,
 
-        #[clap(long, value_parser)]
+        /// Skip files smaller than this many megabytes (--min-filesize takes precedence)
+        #[clap(long, value_name = "MB", value_parser)]
         min_size: Option<usize>,
 
         #[clap(long, value_parser, default_value_t = false)]
@@ -334,9 +725,38 @@ FEATURES:
         #[clap(long, value_parser)]
         limit: Option<usize>,
 
+        /// Copy the results to the system clipboard
         #[clap(long, value_parser, default_value_t = false)]
         copy: bool,
 
+        /// Group the listed files that have identical content
+        #[clap(long, value_parser, default_value_t = false)]
+        duplicates: bool,
+
+        /// Only list files detected as binary
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            alias = "only-binary",
+            alias = "binary-files-only",
+            conflicts_with = "text_only"
+        )]
+        binary_only: bool,
+
+        /// Only list files not detected as binary
+        #[clap(long, value_parser, default_value_t = false)]
+        text_only: bool,
+
+        /// Show the files as a tree grouped by directory
+        #[clap(
+            long,
+            value_parser,
+            default_value_t = false,
+            conflicts_with_all = ["long", "simple", "duplicates", "output_format"]
+        )]
+        tree: bool,
+
         #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
         output_format: OutputFormat,
 
@@ -348,6 +768,61 @@ FEATURES:
         #[clap(long, value_parser, alias = "path-flag")]
         path_flag: Option<PathBuf>,
     },
+    /// Search and replace across files
+    #[clap(after_help = r#"
+EXAMPLES:
+  # Preview a rename as a unified diff
+  rfgrep replace "old_name" "new_name" --extensions rs
+
+  # Rewrite files, keeping a .bak copy of each original
+  rfgrep replace "old_name" "new_name" --extensions rs --in-place --backup
+
+  # Use capture groups in regex mode
+  rfgrep replace "(\w+)_v1" "${1}_v2" --mode regex --in-place
+
+NOTES:
+  Without --in-place (or with --dry-run) files are left untouched and a diff
+  of the pending changes is printed instead, colored on a terminal (see
+  --color). Binary and compressed files are skipped.
+"#)]
+    Replace {
+        pattern: String,
+
+        replacement: String,
+
+        #[clap(long, value_enum, default_value_t = SearchMode::Text)]
+        mode: SearchMode,
+
+        /// Write the changes back to the files instead of printing a diff
+        #[clap(long, value_parser, default_value_t = false)]
+        in_place: bool,
+
+        /// Keep a copy of each modified file with a `.bak` suffix
+        #[clap(long, value_parser, default_value_t = false)]
+        backup: bool,
+
+        #[clap(long, value_parser, use_value_delimiter = true)]
+        extensions: Option<Vec<String>>,
+
+        #[clap(short, long, value_parser, default_value_t = true)]
+        recursive: bool,
+
+        /// Match letter case exactly (the default)
+        #[clap(short = 's', long, overrides_with = "ignore_case")]
+        case_sensitive: bool,
+
+        /// Ignore letter case
+        #[clap(short = 'i', long, overrides_with = "case_sensitive")]
+        ignore_case: bool,
+
+        #[clap(value_parser, last = true)]
+        path: Option<PathBuf>,
+
+        /// Alternative explicit path flag (useful for scripts)
+        #[clap(long, value_parser, alias = "path-flag")]
+        path_flag: Option<PathBuf>,
+    },
+
     #[clap(after_help = r#"
 SUPPORTED SHELLS:
   bash     - Bash shell completions
@@ -433,6 +908,10 @@ CONTROLS:
   m         - Cycle search mode
   a         - Cycle algorithm
   r         - Refresh search
+  +/-       - Show more/less context around each match
+  /         - Search (results update as you type, ↑/↓ recall history)
+  p         - Toggle preview pane
+  y/Y       - Copy matched line / path:line
   Enter     - Open file in editor
 "#)]
     Tui {
@@ -454,6 +933,64 @@ CONTROLS:
         #[clap(long, default_value = ".")]
         path: String,
     },
+    #[clap(after_help = r#"
+EXAMPLES:
+  # Print errors as they're logged, like `tail -f app.log | grep ERROR`
+  rfgrep watch ERROR app.log
+
+  # Start with any matches among the last 100 lines
+  rfgrep watch "timeout|refused" app.log --mode regex --after 100
+
+NOTES:
+  Runs until interrupted. A log that's rotated (replaced by a new file) or
+  truncated is followed from the start of its new contents.
+"#)]
+    /// Follow a growing file and print new matching lines as they're appended
+    Watch {
+        pattern: String,
+
+        /// File to follow
+        path: PathBuf,
+
+        #[clap(long, value_enum, default_value_t = SearchMode::Text)]
+        mode: SearchMode,
+
+        /// Match letter case exactly (the default)
+        #[clap(short = 's', long, overrides_with = "ignore_case")]
+        case_sensitive: bool,
+
+        /// Ignore letter case
+        #[clap(short = 'i', long, overrides_with = "case_sensitive")]
+        ignore_case: bool,
+
+        /// Also search the last N lines already in the file, like `tail -n N -f`
+        #[clap(long, value_name = "N", default_value_t = 0)]
+        after: usize,
+
+        /// How often to check the file for new lines, in milliseconds
+        #[clap(long, value_name = "MS", default_value_t = 250)]
+        poll_interval: u64,
+    },
+    #[clap(after_help = r#"
+PROTOCOL:
+  Send one JSON request per line:
+    {"pattern": "error", "path": "src"}
+  Each match comes back as a JSON line, followed by
+    {"done": true, "matches": N, "cache_hit": true}
+
+EXAMPLES:
+  # Serve queries, keeping compiled patterns cached between them
+  rfgrep daemon --socket /tmp/rfgrep.sock
+
+  # Query it from a script
+  echo '{"pattern": "TODO", "path": "."}' | nc -U /tmp/rfgrep.sock
+"#)]
+    /// Serve searches over a Unix socket, reusing compiled patterns across queries
+    Daemon {
+        /// Path of the Unix socket to listen on
+        #[clap(long)]
+        socket: std::path::PathBuf,
+    },
     #[clap(hide = true)]
     Worker {
         path: std::path::PathBuf,
@@ -482,11 +1019,11 @@ pub enum PluginCommands {
         /// Plugin name
         name: String,
     },
-    /// Set plugin priority
+    /// Set plugin priority, saved for later runs
     Priority {
         /// Plugin name
         name: String,
-        /// Priority value (lower = higher priority)
+        /// Priority value (lower = higher priority; ties go by plugin name)
         priority: u32,
     },
     /// Show plugin configuration options
@@ -506,10 +1043,14 @@ pub enum PluginCommands {
 }
 
 #[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SearchMode {
     #[default]
+    #[serde(alias = "Text")]
     Text,
+    #[serde(alias = "Word")]
     Word,
+    #[serde(alias = "Regex")]
     Regex,
 }
 
@@ -550,6 +1091,199 @@ pub enum SearchAlgorithm {
     BoyerMoore,
     Regex,
     Simple,
+    Fuzzy,
+}
+
+/// Order of search results
+#[derive(ValueEnum, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MatchSort {
+    /// By path, then line and column
+    #[default]
+    Path,
+    /// By line number, then path and column
+    Line,
+    /// In the order matches were found
+    None,
+}
+
+/// Text encoding of searched files
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8; lines that aren't valid UTF-8 are skipped
+    #[value(name = "utf-8", alias = "utf8")]
+    Utf8,
+    /// UTF-16, little endian
+    #[value(name = "utf-16le", alias = "utf16le")]
+    Utf16le,
+    /// UTF-16, big endian
+    #[value(name = "utf-16be", alias = "utf16be")]
+    Utf16be,
+    /// ISO-8859-1
+    #[value(name = "latin1", alias = "iso-8859-1")]
+    Latin1,
+    /// UTF-8 unless the file starts with a byte order mark
+    #[default]
+    Auto,
+}
+
+/// How files that look binary are searched (`--binary-mode`)
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BinaryMode {
+    /// Don't search them (`-I` in grep)
+    #[default]
+    Skip,
+    /// Search them line by line like text (`-a`)
+    Text,
+    /// Search them, reporting only "Binary file PATH matches" on the first hit
+    Binary,
+}
+
+/// 1-based, inclusive range of lines to search (`--line-range START:END`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    /// `None` searches to the end of the file
+    pub end: Option<usize>,
+}
+
+impl LineRange {
+    pub fn contains(&self, line: usize) -> bool {
+        line >= self.start && self.end.is_none_or(|end| line <= end)
+    }
+
+    /// Whether `line` comes after the range, so the rest of the file can be skipped
+    pub fn is_past(&self, line: usize) -> bool {
+        self.end.is_some_and(|end| line > end)
+    }
+}
+
+impl std::str::FromStr for LineRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected START:END or START:, got '{s}'"))?;
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid line number '{n}'"))
+        };
+        let start = if start.trim().is_empty() {
+            1
+        } else {
+            parse(start)?
+        };
+        let end = if end.trim().is_empty() {
+            None
+        } else {
+            Some(parse(end)?)
+        };
+        if start == 0 {
+            return Err("line numbers start at 1".to_string());
+        }
+        if end.is_some_and(|end| end < start) {
+            return Err(format!("range ends before it starts: '{s}'"));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// Parse `--newer-than`/`--older-than`: an age before now made of
+/// `<number><unit>` parts (`s`, `m`, `h`, `d`, `w`; e.g. `1h30m`), or an RFC
+/// 3339 time, or a local `YYYY-MM-DD[THH:MM:SS]`
+pub fn parse_time_cutoff(s: &str) -> Result<std::time::SystemTime, String> {
+    use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+
+    let s = s.trim();
+    if let Some(age) = parse_age(s) {
+        return std::time::SystemTime::now()
+            .checked_sub(age)
+            .ok_or_else(|| format!("age too large: '{s}'"));
+    }
+
+    let local = |naive: NaiveDateTime| {
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(std::time::SystemTime::from)
+            .ok_or_else(|| format!("no such local time: '{s}'"))
+    };
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.into());
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, format) {
+            return local(naive);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return local(date.and_hms_opt(0, 0, 0).expect("midnight exists"));
+    }
+    Err(format!(
+        "expected an age like 30m, 2d or 1w, or a time like 2024-05-01T12:00:00Z, got '{s}'"
+    ))
+}
+
+/// A `--threads` count, rejecting more than the config allows
+fn parse_thread_count(s: &str) -> Result<usize, String> {
+    let max = crate::config::MAX_THREADS;
+    match s.trim().parse::<usize>() {
+        Ok(count) if count <= max => Ok(count),
+        Ok(count) => Err(format!(
+            "{count} threads is more than the maximum of {max} (0 picks one per CPU)"
+        )),
+        Err(_) => Err(format!("expected a number of threads, got '{s}'")),
+    }
+}
+
+/// Parse a size like `4096`, `500K`, `10M` or `2G` into bytes. Units are
+/// powers of 1024, case-insensitive, and may end in `B` or `iB` (`10MiB`)
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let number: u64 = s[..digits]
+        .parse()
+        .map_err(|_| format!("expected a size like 500K, 10M or 2G, got '{s}'"))?;
+    let unit = s[digits..].to_ascii_lowercase();
+    let unit = unit
+        .strip_suffix("ib")
+        .or_else(|| unit.strip_suffix('b'))
+        .unwrap_or(&unit);
+    let scale: u64 = match unit {
+        "" => 1,
+        "k" => 1024,
+        "m" => 1024 * 1024,
+        "g" => 1024 * 1024 * 1024,
+        _ => return Err(format!("unknown size unit in '{s}', expected K, M or G")),
+    };
+    number
+        .checked_mul(scale)
+        .ok_or_else(|| format!("size too large: '{s}'"))
+}
+
+/// `1h30m` and the like as a duration
+fn parse_age(s: &str) -> Option<std::time::Duration> {
+    let mut seconds: u64 = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number: u64 = rest[..digits].parse().ok()?;
+        let unit = rest[digits..].chars().next()?;
+        let scale = match unit {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        seconds = seconds.checked_add(number.checked_mul(scale)?)?;
+        rest = &rest[digits + unit.len_utf8()..];
+    }
+    (!s.is_empty()).then(|| std::time::Duration::from_secs(seconds))
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -571,6 +1305,17 @@ pub enum OutputFormat {
     Markdown,
     Csv,
     Tsv,
+    /// SARIF 2.1.0, for code scanning tools
+    Sarif,
+    /// GitHub Actions `::warning` commands, shown as annotations on PRs
+    GithubActions,
+}
+
+impl OutputFormat {
+    /// Whether the output is a JSON document that banners would corrupt
+    pub fn is_json(&self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Sarif)
+    }
 }
 
 impl fmt::Display for SearchMode {
@@ -582,3 +1327,103 @@ impl fmt::Display for SearchMode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thread_counts_are_capped() {
+        let threads = |value: &str| {
+            Cli::try_parse_from(["rfgrep", "--threads", value, "search", "x"])
+                .map(|cli| cli.threads)
+        };
+        assert_eq!(threads("0").unwrap(), Some(0));
+        assert_eq!(threads("1024").unwrap(), Some(1024));
+        let error = threads("5000").unwrap_err().to_string();
+        assert!(error.contains("more than the maximum of 1024"), "{error}");
+
+        let config = crate::config::SearchConfig::default();
+        let auto = num_cpus::get().min(8);
+        assert_eq!(config.thread_count(Some(0)), auto);
+        assert_eq!(config.thread_count(None), auto);
+        assert_eq!(config.thread_count(Some(3)), 3);
+        let configured = crate::config::SearchConfig {
+            threads: 2,
+            ..Default::default()
+        };
+        assert_eq!(configured.thread_count(None), 2);
+        assert_eq!(configured.thread_count(Some(0)), auto);
+    }
+
+    #[test]
+    fn sizes_parse_with_each_unit() {
+        assert_eq!(parse_size("4096"), Ok(4096));
+        assert_eq!(parse_size("500K"), Ok(500 * 1024));
+        assert_eq!(parse_size("10m"), Ok(10 * 1024 * 1024));
+        assert_eq!(parse_size("2G"), Ok(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("3KB"), Ok(3 * 1024));
+        assert_eq!(parse_size("1MiB"), Ok(1024 * 1024));
+        assert!(parse_size("10X").is_err());
+        assert!(parse_size("K").is_err());
+        assert!(parse_size("99999999999G").is_err());
+
+        let cli = Cli::parse_from(["rfgrep", "--max-size", "2", "search", "x"]);
+        assert_eq!(cli.max_file_bytes(), Some(2 * 1024 * 1024));
+        let cli = Cli::parse_from(["rfgrep", "--max-filesize", "512K", "search", "x"]);
+        assert_eq!(cli.max_file_bytes(), Some(512 * 1024));
+    }
+
+    #[test]
+    fn line_range_parses_closed_and_open_ranges() {
+        let range: LineRange = "3:5".parse().unwrap();
+        assert_eq!(
+            range,
+            LineRange {
+                start: 3,
+                end: Some(5)
+            }
+        );
+        assert!(!range.contains(2) && range.contains(3) && range.contains(5));
+        assert!(range.is_past(6));
+
+        let open: LineRange = "100:".parse().unwrap();
+        assert_eq!(open.end, None);
+        assert!(open.contains(usize::MAX) && !open.is_past(usize::MAX));
+        assert_eq!(":4".parse::<LineRange>().unwrap().start, 1);
+
+        for bad in ["5", "0:3", "5:3", "a:b"] {
+            assert!(bad.parse::<LineRange>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn time_cutoffs_parse_ages_and_timestamps() {
+        use std::time::{Duration, SystemTime};
+
+        let ago = |s: &str| {
+            SystemTime::now()
+                .duration_since(parse_time_cutoff(s).unwrap())
+                .unwrap()
+        };
+        let close_to = |actual: Duration, secs: u64| actual.as_secs().abs_diff(secs) <= 5;
+        assert!(close_to(ago("30m"), 30 * 60));
+        assert!(close_to(ago("2d"), 2 * 86400));
+        assert!(close_to(ago("1w"), 7 * 86400));
+        assert!(close_to(ago("1h30m"), 90 * 60));
+
+        let utc = parse_time_cutoff("2024-05-01T12:00:00Z").unwrap();
+        assert_eq!(
+            utc.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            1_714_564_800
+        );
+        assert!(parse_time_cutoff("2024-05-01").is_ok());
+        assert!(parse_time_cutoff("2024-05-01T08:30:00").is_ok());
+
+        for bad in ["", "2", "5y", "d", "yesterday", "2024-13-01"] {
+            assert!(parse_time_cutoff(bad).is_err(), "{bad}");
+        }
+    }
+}
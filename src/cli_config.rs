@@ -122,7 +122,20 @@ pub enum ConfigFormat {
     Json,
 }
 
+use crate::config::Config;
 use crate::error::{Result as RfgrepResult, RfgrepError};
+use std::path::Path;
+
+/// Path of the config file for a configuration level
+fn config_path(location: &ConfigLocation) -> RfgrepResult<PathBuf> {
+    Ok(match location {
+        ConfigLocation::User => dirs::config_dir()
+            .ok_or(RfgrepError::Other("No config directory found".to_string()))?
+            .join("rfgrep/config.toml"),
+        ConfigLocation::Project => PathBuf::from(".rfgreprc"),
+        ConfigLocation::System => PathBuf::from("/etc/rfgrep/config.toml"),
+    })
+}
 
 pub async fn handle_config_action(action: ConfigAction) -> RfgrepResult<()> {
     match action {
@@ -145,13 +158,7 @@ pub async fn handle_config_action(action: ConfigAction) -> RfgrepResult<()> {
             format: _,
             force,
         } => {
-            let path = match location {
-                ConfigLocation::User => dirs::config_dir()
-                    .ok_or(RfgrepError::Other("No config directory found".to_string()))?
-                    .join("rfgrep/config.toml"),
-                ConfigLocation::Project => PathBuf::from(".rfgreprc"),
-                ConfigLocation::System => PathBuf::from("/etc/rfgrep/config.toml"),
-            };
+            let path = config_path(&location)?;
 
             if path.exists() && !force {
                 println!(
@@ -226,9 +233,262 @@ pub async fn handle_config_action(action: ConfigAction) -> RfgrepResult<()> {
             print_keys(&json, "", prefix.as_deref());
             Ok(())
         }
+        ConfigAction::Set { key, value, level } => {
+            let path = config_path(&level)?;
+            set_config_value(&path, &key, &value)?;
+            println!("Set {} = {} in {:?}", key, value, path);
+            Ok(())
+        }
+        ConfigAction::Unset { key, level } => {
+            let path = config_path(&level)?;
+            if unset_config_value(&path, &key)? {
+                println!("Unset {} in {:?}", key, path);
+            } else {
+                println!("{} is not set in {:?}", key, path);
+            }
+            Ok(())
+        }
+        ConfigAction::Export { format, output } => {
+            let content = export_config(&Config::load()?, &format)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content).map_err(|e| {
+                        RfgrepError::Other(format!("Failed to write config file: {}", e))
+                    })?;
+                    println!("Exported configuration to {:?}", path);
+                }
+                None => print!("{}", content),
+            }
+            Ok(())
+        }
+        ConfigAction::Import { path, level, merge } => {
+            let target = config_path(&level)?;
+            import_config(&path, &target, merge)?;
+            println!("Imported {:?} into {:?}", path, target);
+            Ok(())
+        }
         _ => {
             println!("Config action not fully implemented yet");
             Ok(())
         }
     }
 }
+
+/// Read a config file as a raw TOML table, or an empty table if it doesn't exist
+fn read_config_table(path: &Path) -> RfgrepResult<toml::Table> {
+    if !path.exists() {
+        return Ok(toml::Table::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| RfgrepError::Other(format!("Failed to read config file {:?}: {}", path, e)))?;
+    content
+        .parse::<toml::Table>()
+        .map_err(|e| RfgrepError::Other(format!("Failed to parse TOML config {:?}: {}", path, e)))
+}
+
+/// Check that a raw table forms a valid config and write it back
+fn write_config_table(path: &Path, table: &toml::Table) -> RfgrepResult<()> {
+    let config: Config = toml::Value::Table(table.clone())
+        .try_into()
+        .map_err(|e| RfgrepError::Other(format!("Invalid configuration: {}", e)))?;
+    config.validate()?;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| RfgrepError::Other(format!("Failed to create directory: {}", e)))?;
+    }
+    let content = toml::to_string_pretty(table)
+        .map_err(|e| RfgrepError::Other(format!("Failed to serialize config: {}", e)))?;
+    std::fs::write(path, content)
+        .map_err(|e| RfgrepError::Other(format!("Failed to write config file: {}", e)))
+}
+
+/// Parse a command-line value as a TOML value, falling back to a plain string
+/// so `regex` and `"regex"` are equivalent
+fn parse_config_value(value: &str) -> toml::Value {
+    format!("value = {}", value)
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()))
+}
+
+/// Set a dot-separated key in the config file at `path`, keeping all other keys
+pub fn set_config_value(path: &Path, key: &str, value: &str) -> RfgrepResult<()> {
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(RfgrepError::Other(format!("Invalid key: {}", key)));
+    }
+
+    let mut table = read_config_table(path)?;
+    let (last, parents) = parts.split_last().expect("split yields at least one part");
+    let mut current = &mut table;
+    for part in parents {
+        current = current
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| RfgrepError::Other(format!("{} is not a table", part)))?;
+    }
+    let value = parse_config_value(value);
+    current.insert(last.to_string(), value.clone());
+
+    // Unknown keys are silently dropped on load, so make sure the key survives
+    // a round trip through `Config`
+    let config: Config = toml::Value::Table(table.clone())
+        .try_into()
+        .map_err(|e| RfgrepError::Other(format!("Invalid value for {}: {}", key, e)))?;
+    let resolved = toml::Value::try_from(&config)
+        .map_err(|e| RfgrepError::Other(format!("Failed to serialize config: {}", e)))?;
+    if parts
+        .iter()
+        .try_fold(&resolved, |val, part| val.get(part))
+        .is_none()
+    {
+        return Err(RfgrepError::Other(format!(
+            "Unknown configuration key: {}",
+            key
+        )));
+    }
+
+    write_config_table(path, &table)
+}
+
+/// Remove a dot-separated key from the config file at `path` so it reverts
+/// to its default. Returns whether the key was present.
+pub fn unset_config_value(path: &Path, key: &str) -> RfgrepResult<bool> {
+    let mut table = read_config_table(path)?;
+    let parts: Vec<&str> = key.split('.').collect();
+    let (last, parents) = parts.split_last().expect("split yields at least one part");
+
+    let mut current = &mut table;
+    for part in parents {
+        match current.get_mut(*part).and_then(|v| v.as_table_mut()) {
+            Some(next) => current = next,
+            None => return Ok(false),
+        }
+    }
+    if current.remove(*last).is_none() {
+        return Ok(false);
+    }
+
+    write_config_table(path, &table)?;
+    Ok(true)
+}
+
+/// Serialize a config in the requested format
+pub fn export_config(config: &Config, format: &ConfigFormat) -> RfgrepResult<String> {
+    match format {
+        ConfigFormat::Toml => toml::to_string_pretty(config)
+            .map_err(|e| RfgrepError::Other(format!("Failed to serialize config: {}", e))),
+        ConfigFormat::Yaml => serde_yaml::to_string(config)
+            .map_err(|e| RfgrepError::Other(format!("Failed to serialize config: {}", e))),
+        ConfigFormat::Json => serde_json::to_string_pretty(config)
+            .map(|json| json + "\n")
+            .map_err(|e| RfgrepError::Other(format!("Failed to serialize config: {}", e))),
+    }
+}
+
+/// Import a config file (format chosen by extension: `.json`, `.yaml`/`.yml`,
+/// otherwise TOML) into the TOML config file at `target`
+///
+/// With `merge`, keys from `source` are layered over the existing file;
+/// otherwise the file is replaced.
+pub fn import_config(source: &Path, target: &Path, merge: bool) -> RfgrepResult<()> {
+    let content = std::fs::read_to_string(source).map_err(|e| {
+        RfgrepError::Other(format!("Failed to read config file {:?}: {}", source, e))
+    })?;
+    let format = match source.extension().and_then(|e| e.to_str()) {
+        Some("json") => ConfigFormat::Json,
+        Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+        _ => ConfigFormat::Toml,
+    };
+    let imported: toml::Table = match format {
+        ConfigFormat::Toml => toml::from_str(&content).map_err(|e| e.to_string()),
+        ConfigFormat::Yaml => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+        ConfigFormat::Json => serde_json::from_str(&content).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| RfgrepError::Other(format!("Failed to parse config {:?}: {}", source, e)))?;
+
+    let table = if merge {
+        let mut existing = read_config_table(target)?;
+        merge_tables(&mut existing, imported);
+        existing
+    } else {
+        imported
+    };
+
+    write_config_table(target, &table)
+}
+
+/// Recursively layer `overlay` on top of `base`
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(nested)) => {
+                merge_tables(existing, nested)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::SearchMode;
+
+    #[test]
+    fn test_set_nested_key_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rfgrep/config.toml");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "[output]\nline_numbers = false\n").unwrap();
+
+        set_config_value(&path, "search.mode", "word").unwrap();
+        set_config_value(&path, "search.threads", "8").unwrap();
+
+        let config = Config::from_toml_file(&path).unwrap();
+        assert_eq!(config.search.mode, SearchMode::Word);
+        assert_eq!(config.search.threads, 8);
+        // Keys that weren't touched are kept
+        assert!(!config.output.line_numbers);
+
+        // Unknown keys, bad values and failed validation leave the file alone
+        assert!(set_config_value(&path, "search.nope", "1").is_err());
+        assert!(set_config_value(&path, "search.threads", "many").is_err());
+        assert!(set_config_value(&path, "search.threads", "4096").is_err());
+        assert_eq!(Config::from_toml_file(&path).unwrap().search.threads, 8);
+
+        assert!(unset_config_value(&path, "search.threads").unwrap());
+        assert!(!unset_config_value(&path, "search.threads").unwrap());
+        let config = Config::from_toml_file(&path).unwrap();
+        assert_eq!(config.search.threads, Config::default().search.threads);
+        assert_eq!(config.search.mode, SearchMode::Word);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.search.mode = SearchMode::Word;
+        config.search.threads = 3;
+
+        for (format, name) in [
+            (ConfigFormat::Toml, "export.toml"),
+            (ConfigFormat::Yaml, "export.yaml"),
+            (ConfigFormat::Json, "export.json"),
+        ] {
+            let exported = dir.path().join(name);
+            std::fs::write(&exported, export_config(&config, &format).unwrap()).unwrap();
+
+            let target = dir.path().join(format!("{name}.imported.toml"));
+            import_config(&exported, &target, false).unwrap();
+            let imported = Config::from_toml_file(&target).unwrap();
+            assert_eq!(imported.search.mode, SearchMode::Word);
+            assert_eq!(imported.search.threads, 3);
+        }
+    }
+}
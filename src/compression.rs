@@ -33,6 +33,51 @@ pub fn is_compressed(path: &Path) -> bool {
     CompressionType::from_extension(path).is_some()
 }
 
+/// Reader that fails once more than `remaining` bytes have been read, so a
+/// decompression bomb is cut off instead of streamed forever
+pub struct SizeLimitedReader<R> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R: Read> SizeLimitedReader<R> {
+    pub fn new(inner: R, max_bytes: u64) -> Self {
+        Self {
+            inner,
+            remaining: max_bytes,
+        }
+    }
+}
+
+impl<R: Read> Read for SizeLimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.remaining = self.remaining.checked_sub(n as u64).ok_or_else(|| {
+            io::Error::other("decompressed data exceeds compression.max_decompressed_size_mb")
+        })?;
+        Ok(n)
+    }
+}
+
+/// Wrap `reader` in the decoder for a single-stream `compression` format,
+/// e.g. an entry inside an archive
+pub fn decompress_stream<'a, R: Read + 'a>(
+    reader: R,
+    compression: CompressionType,
+) -> io::Result<Box<dyn Read + 'a>> {
+    match compression {
+        CompressionType::Gzip => Ok(Box::new(flate2::read::GzDecoder::new(reader))),
+        CompressionType::Bzip2 => Ok(Box::new(bzip2::read::BzDecoder::new(reader))),
+        CompressionType::Xz => Ok(Box::new(xz2::read::XzDecoder::new(reader))),
+        CompressionType::Zstd => Ok(Box::new(zstd::stream::read::Decoder::new(reader)?)),
+        CompressionType::Lz4 => Ok(Box::new(lz4_flex::frame::FrameDecoder::new(reader))),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Compression type {:?} is not a stream format", compression),
+        )),
+    }
+}
+
 pub fn open_compressed_stream(
     path: &Path,
     compression: CompressionType,
@@ -15,7 +15,7 @@ use std::path::{Path, PathBuf};
 /// 5. System-level config (/etc/rfgrep/config.toml)
 /// 6. Built-in defaults
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct Config {
     pub search: SearchConfig,
@@ -31,7 +31,10 @@ pub struct Config {
     pub experimental: ExperimentalConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Most worker threads `search.threads` or `--threads` may ask for
+pub const MAX_THREADS: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SearchConfig {
     pub mode: SearchMode,
@@ -50,8 +53,8 @@ impl Default for SearchConfig {
     fn default() -> Self {
         Self {
             mode: SearchMode::Regex,
-            case_sensitive: false,
-            smart_case: true,
+            case_sensitive: true,
+            smart_case: false,
             max_file_size_mb: 100, // 10MB
             skip_binary: true,
             context_before: 2,
@@ -63,7 +66,29 @@ impl Default for SearchConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl SearchConfig {
+    /// Worker threads for a search: `threads` (from `--threads`) or else the
+    /// config's, where 0 means one per CPU, up to 8
+    pub fn thread_count(&self, threads: Option<usize>) -> usize {
+        match threads.unwrap_or(self.threads) {
+            0 => num_cpus::get().min(8),
+            count => count.min(MAX_THREADS),
+        }
+    }
+
+    /// Case matching when no `-s`, `-i` or `-S` is given: smart case if
+    /// enabled, otherwise `case_sensitive`
+    pub fn case_mode(&self) -> crate::cli::CaseMode {
+        use crate::cli::CaseMode;
+        match (self.smart_case, self.case_sensitive) {
+            (true, _) => CaseMode::Smart,
+            (false, true) => CaseMode::Sensitive,
+            (false, false) => CaseMode::Insensitive,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct AlgorithmConfig {
     pub simple: String,
@@ -81,7 +106,7 @@ impl Default for AlgorithmConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct OutputConfig {
     pub format: OutputFormat,
@@ -124,7 +149,10 @@ pub enum ColorMode {
     Never,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Colors of text output: ANSI names like `red` or `bright-blue`, `#rrggbb`
+/// for 24-bit color, or `none`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ColorScheme {
     pub r#match: String,
     pub line_number: String,
@@ -135,15 +163,15 @@ pub struct ColorScheme {
 impl Default for ColorScheme {
     fn default() -> Self {
         Self {
-            r#match: "red".to_string(),
-            line_number: "green".to_string(),
-            filename: "blue".to_string(),
-            separator: "cyan".to_string(),
+            r#match: "#c25d15".to_string(),
+            line_number: "#a71dde".to_string(),
+            filename: "#28acc9".to_string(),
+            separator: "none".to_string(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct FilterConfig {
     pub extensions: Vec<String>,
@@ -188,7 +216,7 @@ impl Default for FilterConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SizeFilter {
     pub min_size: u64,
     pub max_size: u64,
@@ -203,7 +231,7 @@ impl Default for SizeFilter {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct PerformanceConfig {
     pub mmap_threshold_mb: u64,
@@ -233,7 +261,7 @@ impl Default for PerformanceConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct OptimizationConfig {
     pub literal_extraction: bool,
@@ -251,7 +279,7 @@ impl Default for OptimizationConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct GitConfig {
     pub respect_gitignore: bool,
@@ -273,19 +301,21 @@ impl Default for GitConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct GitSubmoduleConfig {
     pub follow: bool,
     pub search: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct CompressionConfig {
     pub enabled: bool,
     pub formats: Vec<String>,
     pub max_decompressed_size_mb: u64,
+    /// How many levels of archives inside archives are searched
+    pub max_archive_depth: usize,
     pub cache_decompressed: bool,
 }
 
@@ -301,12 +331,13 @@ impl Default for CompressionConfig {
                 "lz4".to_string(),
             ],
             max_decompressed_size_mb: 100,
+            max_archive_depth: 3,
             cache_decompressed: true,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct LoggingConfig {
     pub level: String,
@@ -324,7 +355,9 @@ impl Default for LoggingConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// File types selectable with `--type`, each a list of extensions.
+/// Entries other than the built-in names land in `custom`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct TypeDefinitions {
     pub rust: Vec<String>,
@@ -339,7 +372,74 @@ pub struct TypeDefinitions {
     pub custom: HashMap<String, Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for TypeDefinitions {
+    fn default() -> Self {
+        let exts = |list: &[&str]| list.iter().map(|ext| ext.to_string()).collect();
+        Self {
+            rust: exts(&["rs"]),
+            python: exts(&["py", "pyi", "pyw"]),
+            javascript: exts(&["js", "jsx", "mjs", "cjs"]),
+            typescript: exts(&["ts", "tsx", "mts", "cts"]),
+            web: exts(&[
+                "html", "htm", "css", "scss", "sass", "less", "js", "ts", "jsx", "tsx", "vue",
+                "svelte",
+            ]),
+            config: exts(&["toml", "yaml", "yml", "json", "ini", "cfg", "conf"]),
+            markdown: exts(&["md", "markdown", "mdx"]),
+            sql: exts(&["sql"]),
+            custom: HashMap::new(),
+        }
+    }
+}
+
+impl TypeDefinitions {
+    /// Every defined type and its extensions, sorted by name
+    pub fn all(&self) -> Vec<(&str, &[String])> {
+        let mut types: Vec<(&str, &[String])> = vec![
+            ("rust", &self.rust),
+            ("python", &self.python),
+            ("javascript", &self.javascript),
+            ("typescript", &self.typescript),
+            ("web", &self.web),
+            ("config", &self.config),
+            ("markdown", &self.markdown),
+            ("sql", &self.sql),
+        ];
+        types.extend(
+            self.custom
+                .iter()
+                .map(|(name, exts)| (name.as_str(), exts.as_slice())),
+        );
+        types.retain(|(_, exts)| !exts.is_empty());
+        types.sort_by_key(|(name, _)| *name);
+        types
+    }
+
+    /// Extensions for the type called `name`
+    pub fn extensions(&self, name: &str) -> Option<&[String]> {
+        self.all()
+            .into_iter()
+            .find(|(type_name, _)| *type_name == name)
+            .map(|(_, exts)| exts)
+    }
+
+    /// Extensions for all of `names`, or an error naming the first unknown type
+    pub fn resolve(&self, names: &[String]) -> RfgrepResult<Vec<String>> {
+        let mut resolved = Vec::new();
+        for name in names {
+            let exts = self.extensions(name).ok_or_else(|| {
+                RfgrepError::Other(format!(
+                    "Unknown file type '{}' (see 'rfgrep search --type-list')",
+                    name
+                ))
+            })?;
+            resolved.extend(exts.iter().cloned());
+        }
+        Ok(resolved)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchShortcut {
     pub pattern: String,
     pub mode: SearchMode,
@@ -351,7 +451,7 @@ pub struct SearchShortcut {
     pub case_sensitive: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct UIConfig {
     pub interactive: bool,
@@ -371,7 +471,7 @@ impl Default for UIConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct ExperimentalConfig {
     pub semantic_search: bool,
@@ -401,10 +501,36 @@ impl Config {
         })
     }
 
-    /// Auto-detect format and load
+    /// Load from YAML file
+    pub fn from_yaml_file(path: &Path) -> RfgrepResult<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            RfgrepError::Other(format!("Failed to read config file {:?}: {}", path, e))
+        })?;
+
+        serde_yaml::from_str(&content).map_err(|e| {
+            RfgrepError::Other(format!("Failed to parse YAML config {:?}: {}", path, e))
+        })
+    }
+
+    /// Load from JSON file
+    pub fn from_json_file(path: &Path) -> RfgrepResult<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            RfgrepError::Other(format!("Failed to read config file {:?}: {}", path, e))
+        })?;
+
+        serde_json::from_str(&content).map_err(|e| {
+            RfgrepError::Other(format!("Failed to parse JSON config {:?}: {}", path, e))
+        })
+    }
+
+    /// Auto-detect format from the extension and load. Files without a
+    /// recognised extension (e.g. `.rfgreprc`) are read as TOML.
     pub fn load_from_path(path: &Path) -> RfgrepResult<Self> {
-        // Only TOML supported for now
-        Self::from_toml_file(path)
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => Self::from_yaml_file(path),
+            Some("json") => Self::from_json_file(path),
+            _ => Self::from_toml_file(path),
+        }
     }
 
     /// Auto-detect format and load default config
@@ -412,16 +538,23 @@ impl Config {
         let config_path =
             Self::find_config_path().map_err(|e| RfgrepError::Other(e.to_string()))?;
         if let Some(path) = config_path {
-            Self::from_toml_file(&path)
+            Self::load_from_path(&path)
         } else {
             Ok(Self::default())
         }
     }
 
+    /// The first existing `config.{toml,yaml,yml,json}` in `dir`
+    fn find_config_in(dir: &Path) -> Option<PathBuf> {
+        ["config.toml", "config.yaml", "config.yml", "config.json"]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+    }
+
     fn find_config_path() -> RfgrepResult<Option<PathBuf>> {
         if let Some(xdg_config) = dirs::config_dir() {
-            let xdg_path = xdg_config.join("rfgrep/config.toml");
-            if xdg_path.exists() {
+            if let Some(xdg_path) = Self::find_config_in(&xdg_config.join("rfgrep")) {
                 return Ok(Some(xdg_path));
             }
         }
@@ -442,7 +575,7 @@ impl Config {
     }
 
     pub fn validate(&self) -> RfgrepResult<()> {
-        if self.search.threads > 1024 {
+        if self.search.threads > MAX_THREADS {
             return Err(RfgrepError::Other(format!(
                 "Thread count too high: {}",
                 self.search.threads
@@ -511,8 +644,7 @@ impl ConfigManager {
 
     fn load_user_config() -> RfgrepResult<Option<Config>> {
         if let Some(config_dir) = dirs::config_dir() {
-            let path = config_dir.join("rfgrep/config.toml");
-            if path.exists() {
+            if let Some(path) = Config::find_config_in(&config_dir.join("rfgrep")) {
                 return Ok(Some(Config::load_from_path(&path)?));
             }
         }
@@ -555,3 +687,65 @@ impl ConfigManager {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_every_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::default();
+
+        let toml_path = dir.path().join("config.toml");
+        fs::write(&toml_path, toml::to_string_pretty(&config).unwrap()).unwrap();
+        let yaml_path = dir.path().join("config.yaml");
+        fs::write(&yaml_path, serde_yaml::to_string(&config).unwrap()).unwrap();
+        let yml_path = dir.path().join("config.yml");
+        fs::write(&yml_path, serde_yaml::to_string(&config).unwrap()).unwrap();
+        let json_path = dir.path().join("config.json");
+        fs::write(&json_path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+
+        for path in [toml_path, yaml_path, yml_path, json_path] {
+            assert_eq!(Config::load_from_path(&path).unwrap(), config, "{path:?}");
+        }
+    }
+
+    #[test]
+    fn test_type_definitions_include_custom_types() {
+        let types: TypeDefinitions =
+            toml::from_str("rust = [\"rs\", \"rlib\"]\nproto = [\"proto\"]\n").unwrap();
+        assert_eq!(types.extensions("rust").unwrap(), ["rs", "rlib"]);
+        assert_eq!(types.extensions("proto").unwrap(), ["proto"]);
+        // Types the file doesn't mention keep their defaults
+        assert_eq!(types.extensions("sql").unwrap(), ["sql"]);
+
+        assert_eq!(
+            types.resolve(&["rust".into(), "sql".into()]).unwrap(),
+            ["rs", "rlib", "sql"]
+        );
+        assert!(types
+            .resolve(&["cobol".into()])
+            .unwrap_err()
+            .to_string()
+            .contains("Unknown file type 'cobol'"));
+    }
+
+    #[test]
+    fn test_find_config_prefers_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(Config::find_config_in(dir.path()), None);
+
+        fs::write(dir.path().join("config.json"), "{}").unwrap();
+        assert_eq!(
+            Config::find_config_in(dir.path()),
+            Some(dir.path().join("config.json"))
+        );
+
+        fs::write(dir.path().join("config.toml"), "").unwrap();
+        assert_eq!(
+            Config::find_config_in(dir.path()),
+            Some(dir.path().join("config.toml"))
+        );
+    }
+}
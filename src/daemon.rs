@@ -0,0 +1,261 @@
+//! Long-running search daemon that keeps compiled patterns warm
+//!
+//! A short-lived `rfgrep search` compiles its pattern on every run, which adds
+//! up for scripts calling rfgrep in a loop. `rfgrep daemon --socket PATH`
+//! serves queries from one process instead, so a repeated pattern hits the
+//! regex cache in `processor`.
+//!
+//! Each request is one line of JSON: `{"pattern": "...", "path": "..."}`.
+//! The reply is one JSON line per match followed by a summary line
+//! `{"done": true, "matches": N, "cache_hit": bool}`, or a single
+//! `{"error": "..."}` line. A connection may send any number of requests.
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use crate::processor::{get_or_compile_regex, is_binary, is_regex_cached, search_file};
+use crate::walker::walk_dir;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A single search query
+#[derive(Debug, Deserialize)]
+pub struct DaemonRequest {
+    pub pattern: String,
+    /// File to search, or a directory to search recursively
+    pub path: PathBuf,
+}
+
+/// Last line of a successful reply
+#[derive(Debug, Serialize)]
+pub struct DaemonSummary {
+    pub done: bool,
+    pub matches: usize,
+    /// Whether the pattern was already compiled by an earlier query
+    pub cache_hit: bool,
+}
+
+/// How often an idle daemon checks whether it should stop
+#[cfg(unix)]
+const SHUTDOWN_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Removes the socket file however the daemon stops, so a restart can bind it
+#[cfg(unix)]
+struct SocketFile<'a>(&'a Path);
+
+#[cfg(unix)]
+impl Drop for SocketFile<'_> {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(self.0) {
+            log::warn!("Failed to remove {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+/// Listen on `socket` until `shutdown` is set, then remove it
+#[cfg(unix)]
+pub fn serve(socket: &Path, shutdown: &AtomicBool) -> RfgrepResult<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if let Ok(metadata) = std::fs::symlink_metadata(socket) {
+        if !metadata.file_type().is_socket() {
+            return Err(RfgrepError::Other(format!(
+                "{} exists and is not a socket",
+                socket.display()
+            )));
+        }
+        if UnixStream::connect(socket).is_ok() {
+            return Err(RfgrepError::Other(format!(
+                "A daemon is already listening on {}",
+                socket.display()
+            )));
+        }
+        // Left behind by a daemon that didn't shut down cleanly
+        std::fs::remove_file(socket)?;
+    }
+
+    let listener = UnixListener::bind(socket)?;
+    let _socket_file = SocketFile(socket);
+    // Accept without blocking so the shutdown flag is noticed while idle
+    listener.set_nonblocking(true)?;
+    log::info!("Daemon listening on {}", socket.display());
+    while !shutdown.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(e) = stream.set_nonblocking(false) {
+                    log::warn!("Daemon failed to set up a connection: {}", e);
+                    continue;
+                }
+                std::thread::spawn(move || {
+                    let reader = io::BufReader::new(&stream);
+                    if let Err(e) = handle_connection(reader, &stream) {
+                        log::debug!("Daemon connection closed: {}", e);
+                    }
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                std::thread::sleep(SHUTDOWN_POLL_INTERVAL)
+            }
+            Err(e) => log::warn!("Daemon failed to accept a connection: {}", e),
+        }
+    }
+    log::info!("Daemon on {} shutting down", socket.display());
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(_socket: &Path, _shutdown: &AtomicBool) -> RfgrepResult<()> {
+    Err(RfgrepError::Other(
+        "Daemon mode requires Unix domain sockets".to_string(),
+    ))
+}
+
+/// Answer every request read from `reader` until it is closed
+pub fn handle_connection(reader: impl BufRead, mut out: impl Write) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<DaemonRequest>(&line) {
+            Ok(request) => answer(&request, &mut out)?,
+            Err(e) => write_error(&mut out, &format!("Invalid request: {}", e))?,
+        }
+        out.flush()?;
+    }
+    Ok(())
+}
+
+fn answer(request: &DaemonRequest, out: &mut impl Write) -> io::Result<()> {
+    let cache_hit = is_regex_cached(&request.pattern);
+    let regex = match get_or_compile_regex(&request.pattern) {
+        Ok(regex) => regex,
+        Err(e) => return write_error(out, &e.to_string()),
+    };
+
+    let files: Vec<PathBuf> = if request.path.is_dir() {
+        walk_dir(&request.path, true, false)
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file() && !is_binary(path))
+            .collect()
+    } else if request.path.is_file() {
+        vec![request.path.clone()]
+    } else {
+        return write_error(out, &format!("No such file: {}", request.path.display()));
+    };
+
+    let mut matches = 0;
+    for file in files {
+        match search_file(&file, &regex) {
+            Ok(found) => {
+                for m in found {
+                    write_line(out, &m)?;
+                    matches += 1;
+                }
+            }
+            Err(e) => log::debug!("Daemon skipped {}: {}", file.display(), e),
+        }
+    }
+
+    write_line(
+        out,
+        &DaemonSummary {
+            done: true,
+            matches,
+            cache_hit,
+        },
+    )
+}
+
+fn write_error(out: &mut impl Write, message: &str) -> io::Result<()> {
+    write_line(out, &serde_json::json!({ "error": message }))
+}
+
+fn write_line(out: &mut impl Write, value: &impl Serialize) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replies(input: &str) -> Vec<serde_json::Value> {
+        let mut out = Vec::new();
+        handle_connection(input.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn repeated_queries_reuse_the_compiled_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "daemon-warm-cache\nother\n").unwrap();
+
+        let request = serde_json::json!({
+            "pattern": "daemon-warm-(cache)",
+            "path": dir.path(),
+        })
+        .to_string();
+        let lines = replies(&format!("{request}\n{request}\n"));
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["line_number"], 1);
+        assert_eq!(lines[1]["done"], true);
+        assert_eq!(lines[1]["matches"], 1);
+        assert_eq!(lines[1]["cache_hit"], false);
+        assert_eq!(lines[3]["matches"], 1);
+        assert_eq!(lines[3]["cache_hit"], true);
+    }
+
+    #[test]
+    fn bad_requests_get_an_error_line() {
+        let lines = replies("not json\n{\"pattern\": \"(\", \"path\": \".\"}\n");
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0]["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid request"));
+        assert!(lines[1]["error"].as_str().unwrap().contains("Regex"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_is_removed_on_shutdown_and_stale_ones_replaced() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::{UnixListener, UnixStream};
+        use std::sync::Arc;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("rfgrep.sock");
+        // Left behind by a daemon that was killed: the file exists, nobody listens
+        drop(UnixListener::bind(&socket).unwrap());
+        assert!(socket.exists());
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let daemon = {
+            let (socket, shutdown) = (socket.clone(), shutdown.clone());
+            std::thread::spawn(move || serve(&socket, &shutdown))
+        };
+
+        let stream = loop {
+            match UnixStream::connect(&socket) {
+                Ok(stream) => break stream,
+                Err(_) => std::thread::sleep(std::time::Duration::from_millis(10)),
+            }
+        };
+        let request = serde_json::json!({ "pattern": "x", "path": dir.path().join("none") });
+        writeln!(&stream, "{request}").unwrap();
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply).unwrap();
+        assert!(reply.contains("error"), "{reply}");
+
+        shutdown.store(true, Ordering::Relaxed);
+        daemon.join().unwrap().unwrap();
+        assert!(!socket.exists());
+    }
+}
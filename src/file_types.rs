@@ -1,8 +1,24 @@
 // Removed unused imports
+use crate::config::TypeDefinitions;
 use std::collections::{HashMap, HashSet};
 use std::fs::Metadata;
 use std::path::Path;
 
+/// Whether `path`'s extension is one of `extensions`, ignoring ASCII case
+/// unless `case_sensitive`; files without an extension never match
+pub fn has_extension(path: &Path, extensions: &[String], case_sensitive: bool) -> bool {
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+    extensions.iter().any(|e| {
+        if case_sensitive {
+            e == ext
+        } else {
+            e.eq_ignore_ascii_case(ext)
+        }
+    })
+}
+
 /// Search decision for a file
 #[derive(Debug, Clone)]
 pub enum SearchDecision {
@@ -211,6 +227,59 @@ impl FileTypeClassifier {
         }
     }
 
+    /// Programming or markup language of a file: the `--type` definition
+    /// its extension belongs to ("rust", "python", ...), or else one of the
+    /// languages `--type` doesn't cover, from its extension or, for files
+    /// like `Makefile`, its name
+    pub fn language<'a>(path: &Path, types: &'a TypeDefinitions) -> Option<&'a str> {
+        // `web` and `config` group several languages rather than naming one
+        let defined = types
+            .all()
+            .into_iter()
+            .filter(|(name, _)| !matches!(*name, "web" | "config"))
+            .find(|(_, extensions)| has_extension(path, extensions, false));
+        if let Some((name, _)) = defined {
+            return Some(name);
+        }
+
+        let name = path.file_name()?.to_str()?;
+        match name {
+            "Makefile" | "makefile" | "GNUmakefile" => return Some("make"),
+            "Dockerfile" => return Some("dockerfile"),
+            "CMakeLists.txt" => return Some("cmake"),
+            _ => {}
+        }
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        Some(match ext.as_str() {
+            "go" => "go",
+            "java" => "java",
+            "kt" | "kts" => "kotlin",
+            "scala" => "scala",
+            "c" | "h" => "c",
+            "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => "cpp",
+            "cs" => "csharp",
+            "php" => "php",
+            "rb" => "ruby",
+            "swift" => "swift",
+            "dart" => "dart",
+            "r" => "r",
+            "lua" => "lua",
+            "sh" | "bash" | "zsh" | "fish" => "shell",
+            "ps1" => "powershell",
+            "bat" | "cmd" => "batch",
+            "html" | "htm" => "html",
+            "css" | "scss" | "sass" | "less" => "css",
+            "vue" => "vue",
+            "svelte" => "svelte",
+            "json" => "json",
+            "yaml" | "yml" => "yaml",
+            "toml" => "toml",
+            "xml" => "xml",
+            "tex" => "latex",
+            _ => return None,
+        })
+    }
+
     /// Determine if a file should be searched and how
     pub fn should_search(&self, path: &Path, metadata: &Metadata) -> SearchDecision {
         let ext = path
@@ -467,4 +536,32 @@ mod tests {
         let limit = classifier.get_size_limit("pdf").unwrap();
         assert_eq!(limit, 10 * 1024 * 1024);
     }
+
+    #[test]
+    fn test_has_extension_case() {
+        let rs = vec!["rs".to_string()];
+        assert!(has_extension(Path::new("main.RS"), &rs, false));
+        assert!(!has_extension(Path::new("main.RS"), &rs, true));
+        assert!(has_extension(Path::new("main.rs"), &rs, true));
+        assert!(!has_extension(Path::new("Makefile"), &rs, false));
+    }
+
+    #[test]
+    fn test_language_follows_type_definitions() {
+        let mut types = TypeDefinitions::default();
+        types
+            .custom
+            .insert("terraform".to_string(), vec!["tf".to_string()]);
+        let language = |name: &str| FileTypeClassifier::language(Path::new(name), &types);
+
+        assert_eq!(language("main.rs"), Some("rust"));
+        assert_eq!(language("infra/main.tf"), Some("terraform"));
+        // Aggregate types don't name a language, so the extension decides
+        assert_eq!(language("index.html"), Some("html"));
+        assert_eq!(language("package.json"), Some("json"));
+        assert_eq!(language("app.tsx"), Some("typescript"));
+        assert_eq!(language("main.go"), Some("go"));
+        assert_eq!(language("Makefile"), Some("make"));
+        assert_eq!(language("notes.unknown"), None);
+    }
 }
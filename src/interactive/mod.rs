@@ -1,6 +1,7 @@
 //! Interactive search engine with TUI interface
 use crate::cli::InteractiveAlgorithm;
 use crate::error::{Result as RfgrepResult, RfgrepError};
+use crate::file_types::has_extension;
 use crate::metrics::Metrics;
 use crate::search::algorithms::*;
 use crate::search::SearchEngine;
@@ -48,6 +49,7 @@ impl InteractiveEngine {
         algorithm: InteractiveAlgorithm,
         recursive: bool,
         extensions: Option<&[String]>,
+        case_sensitive_extensions: bool,
     ) -> RfgrepResult<()> {
         println!("{}", "Starting interactive search mode...".green().bold());
         println!("Pattern: {}", initial_pattern.yellow());
@@ -56,7 +58,7 @@ impl InteractiveEngine {
 
         // Discover files
         let files = self
-            .discover_files(root_path, recursive, extensions)
+            .discover_files(root_path, recursive, extensions, case_sensitive_extensions)
             .await?;
         println!("Files to search: {}", files.len());
 
@@ -85,6 +87,7 @@ impl InteractiveEngine {
         root_path: &Path,
         recursive: bool,
         extensions: Option<&[String]>,
+        case_sensitive_extensions: bool,
     ) -> RfgrepResult<Vec<std::path::PathBuf>> {
         use crate::walker::walk_dir;
 
@@ -97,13 +100,7 @@ impl InteractiveEngine {
         let filtered_files: Vec<_> = if let Some(extensions) = extensions {
             files
                 .into_iter()
-                .filter(|path| {
-                    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                        extensions.iter().any(|e| e.eq_ignore_ascii_case(ext))
-                    } else {
-                        false
-                    }
-                })
+                .filter(|path| has_extension(path, extensions, case_sensitive_extensions))
                 .collect()
         } else {
             files
@@ -408,7 +405,7 @@ impl InteractiveEngine {
             }
 
             let mut ext_vec: Vec<_> = ext_counts.into_iter().collect();
-            ext_vec.sort_by(|a, b| b.1.cmp(&a.1));
+            ext_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
 
             if !ext_vec.is_empty() {
                 println!("\nMatches by file type:");
@@ -502,6 +499,10 @@ impl SearchAlgorithmTrait for RegexSearch {
                     column_start: match_pos - text[..match_pos].rfind('\n').unwrap_or(0),
                     column_end: match_pos - text[..match_pos].rfind('\n').unwrap_or(0)
                         + matched_text.len(),
+                    byte_offset: match_pos,
+                    distance: None,
+                    binary: false,
+                    excerpt: None,
                 });
             }
         }
@@ -36,10 +36,11 @@
 //! use rfgrep::search_algorithms::{SearchAlgorithm, SearchAlgorithmFactory};
 //!
 //! // Create a search algorithm
-//! let algorithm = SearchAlgorithmFactory::create(SearchAlgorithm::BoyerMoore, "pattern");
+//! let algorithm = SearchAlgorithmFactory::create(SearchAlgorithm::BoyerMoore, "pattern")?;
 //!
 //! // Search in text
 //! let matches = algorithm.search("Hello, world!", "world");
+//! # Ok::<(), rfgrep::error::RfgrepError>(())
 //! ```
 //!
 //! ## Performance Features
@@ -105,6 +106,9 @@ pub mod cli_config;
 /// Compression support
 pub mod compression;
 
+/// Search daemon serving queries over a Unix socket
+mod daemon;
+
 /// Error types and result handling
 pub mod error;
 
@@ -148,6 +152,9 @@ pub mod plugin_system;
 /// Core file processing and search logic
 pub mod processor;
 
+/// `--pre` commands that turn files into searchable text
+pub mod preprocess;
+
 /// Progress tracking and reporting
 mod progress;
 
@@ -1,9 +1,13 @@
 //! File listing engine with advanced filtering and statistics
 use crate::error::{Result as RfgrepResult, RfgrepError};
+use crate::file_types::has_extension;
 use crate::metrics::Metrics;
-use crate::walker::walk_dir;
+use crate::walker::{walk_dir, FileEntry};
 use colored::*;
-use std::path::Path;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// File information for listing
@@ -16,6 +20,327 @@ pub struct FileInfo {
     pub modified: Option<std::time::SystemTime>,
 }
 
+impl FileInfo {
+    /// Gather the listing details of the file at `path`
+    pub fn from_path(path: &Path) -> RfgrepResult<Self> {
+        Ok(Self::from_entry(&FileEntry::from_path(path)?))
+    }
+
+    /// Like [`from_path`](Self::from_path), with the metadata already read
+    pub fn from_entry(file: &FileEntry) -> Self {
+        let extension = file
+            .path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("none")
+            .to_string();
+
+        Self {
+            path: file.path.clone(),
+            size: file.metadata.len(),
+            extension,
+            is_binary: file.is_binary(),
+            modified: file.metadata.modified().ok(),
+        }
+    }
+
+    /// Last modification time in seconds since the Unix epoch
+    pub fn modified_epoch(&self) -> Option<u64> {
+        self.modified
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs())
+    }
+
+    fn record(&self, long: bool) -> FileRecord<'_> {
+        FileRecord {
+            path: self.path.to_string_lossy().into_owned(),
+            size: self.size,
+            extension: (self.extension != "none").then_some(self.extension.as_str()),
+            modified: if long { self.modified_epoch() } else { None },
+            is_binary: long.then_some(self.is_binary),
+        }
+    }
+}
+
+/// One file of a structured listing; `--long` adds `modified` and `is_binary`
+#[derive(Serialize)]
+struct FileRecord<'a> {
+    path: String,
+    size: u64,
+    extension: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modified: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_binary: Option<bool>,
+}
+
+/// Totals over a listing, included in JSON output with `--stats`
+#[derive(Serialize)]
+struct ListingStats {
+    total_files: usize,
+    total_size: u64,
+    binary_files: usize,
+    extensions: BTreeMap<String, usize>,
+}
+
+impl ListingStats {
+    fn new(files: &[FileInfo]) -> Self {
+        let mut extensions = BTreeMap::new();
+        for file in files {
+            *extensions.entry(file.extension.clone()).or_insert(0) += 1;
+        }
+        Self {
+            total_files: files.len(),
+            total_size: files.iter().map(|f| f.size).sum(),
+            binary_files: files.iter().filter(|f| f.is_binary).count(),
+            extensions,
+        }
+    }
+}
+
+/// Render `files` as JSON, CSV or TSV, or `None` for formats without a
+/// structured listing
+///
+/// JSON is an array of records, or with `stats` an object holding the
+/// records under `files` and the totals under `stats`. CSV and TSV write one
+/// row per file after a header.
+pub fn format_listing(
+    files: &[FileInfo],
+    format: &crate::cli::OutputFormat,
+    long: bool,
+    stats: bool,
+) -> RfgrepResult<Option<String>> {
+    use crate::cli::OutputFormat;
+
+    let records: Vec<FileRecord> = files.iter().map(|file| file.record(long)).collect();
+    let json = |value: serde_json::Value| {
+        serde_json::to_string_pretty(&value).map_err(|e| RfgrepError::Other(e.to_string()))
+    };
+    let output = match format {
+        OutputFormat::Json if stats => {
+            json(serde_json::json!({
+                "files": records,
+                "stats": ListingStats::new(files),
+            }))? + "\n"
+        }
+        OutputFormat::Json => json(serde_json::json!(records))? + "\n",
+        OutputFormat::Csv => delimited(&records, long, ",", crate::output_formats::escape_csv),
+        OutputFormat::Tsv => delimited(&records, long, "\t", crate::output_formats::escape_tsv),
+        _ => return Ok(None),
+    };
+    Ok(Some(output))
+}
+
+/// Files that share the same content
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    /// SHA-256 of the content, in hex
+    pub digest: String,
+    /// Size of each file in bytes
+    pub size: u64,
+    pub paths: Vec<PathBuf>,
+}
+
+/// Group `files` with identical content, largest groups first
+///
+/// Only files sharing a size are hashed, and each is streamed through the
+/// hash rather than read into memory. Empty files are never reported, and
+/// files that can't be read are skipped.
+pub fn find_duplicates(files: &[FileEntry]) -> Vec<DuplicateGroup> {
+    use rayon::prelude::*;
+
+    let mut by_size: HashMap<u64, Vec<&PathBuf>> = HashMap::new();
+    for file in files {
+        let size = file.metadata.len();
+        if size > 0 {
+            by_size.entry(size).or_default().push(&file.path);
+        }
+    }
+
+    let candidates: Vec<(u64, &PathBuf)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map(|(size, paths)| paths.into_iter().map(move |path| (size, path)))
+        .collect();
+    let hashed: Vec<((u64, String), &PathBuf)> = candidates
+        .into_par_iter()
+        .filter_map(|(size, path)| Some(((size, hash_file(path).ok()?), path)))
+        .collect();
+
+    let mut by_content: HashMap<(u64, String), Vec<PathBuf>> = HashMap::new();
+    for (key, path) in hashed {
+        by_content.entry(key).or_default().push(path.clone());
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_content
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, digest), mut paths)| {
+            paths.sort();
+            DuplicateGroup {
+                digest,
+                size,
+                paths,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.paths
+            .len()
+            .cmp(&a.paths.len())
+            .then_with(|| a.paths.cmp(&b.paths))
+    });
+    groups
+}
+
+/// Hex SHA-256 of a file's content, read in chunks
+fn hash_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Print duplicate groups as JSON, or as indented text blocks
+pub fn print_duplicates(
+    groups: &[DuplicateGroup],
+    format: &crate::cli::OutputFormat,
+) -> RfgrepResult<()> {
+    if *format == crate::cli::OutputFormat::Json {
+        let json =
+            serde_json::to_string_pretty(groups).map_err(|e| RfgrepError::Other(e.to_string()))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    for group in groups {
+        println!(
+            "{} identical files ({} bytes each):",
+            group.paths.len(),
+            group.size
+        );
+        for path in &group.paths {
+            println!("  {}", path.display());
+        }
+        println!();
+    }
+    let wasted: u64 = groups
+        .iter()
+        .map(|group| group.size * (group.paths.len() as u64 - 1))
+        .sum();
+    println!(
+        "Summary: {} groups of duplicates, {} bytes in redundant copies",
+        groups.len(),
+        wasted
+    );
+    Ok(())
+}
+
+/// Directories and files of a tree listing, each ordered by name
+#[derive(Default)]
+struct TreeDir {
+    dirs: BTreeMap<String, TreeDir>,
+    files: Vec<String>,
+}
+
+/// Render `files` as an indented tree under `root`, like `tree`
+///
+/// Entries are ordered by name within each directory. A directory whose only
+/// entry is another directory shares its line (`a/b/c/`), so deep nesting
+/// doesn't push names off the screen.
+pub fn format_tree(root: &Path, files: &[PathBuf]) -> String {
+    let mut tree = TreeDir::default();
+    for path in files {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let mut components: Vec<String> = relative
+            .components()
+            .filter_map(|component| match component {
+                std::path::Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        // The root itself, when it's a file
+        let name = components
+            .pop()
+            .unwrap_or_else(|| path.display().to_string());
+        let mut dir = &mut tree;
+        for component in components {
+            dir = dir.dirs.entry(component).or_default();
+        }
+        dir.files.push(name);
+    }
+
+    let mut out = format!("{}\n", root.display());
+    render_tree(&tree, "", &mut out);
+    out
+}
+
+fn render_tree(dir: &TreeDir, prefix: &str, out: &mut String) {
+    let mut entries: Vec<(&str, Option<&TreeDir>)> = dir
+        .dirs
+        .iter()
+        .map(|(name, sub)| (name.as_str(), Some(sub)))
+        .chain(dir.files.iter().map(|name| (name.as_str(), None)))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (i, (name, sub)) in entries.iter().enumerate() {
+        let last = i + 1 == entries.len();
+        out.push_str(prefix);
+        out.push_str(if last { "└── " } else { "├── " });
+        out.push_str(name);
+        let Some(mut sub) = *sub else {
+            out.push('\n');
+            continue;
+        };
+        out.push('/');
+        while let Some((name, only)) = sub
+            .dirs
+            .first_key_value()
+            .filter(|_| sub.files.is_empty() && sub.dirs.len() == 1)
+        {
+            out.push_str(name);
+            out.push('/');
+            sub = only;
+        }
+        out.push('\n');
+        render_tree(
+            sub,
+            &format!("{prefix}{}", if last { "    " } else { "│   " }),
+            out,
+        );
+    }
+}
+
+fn delimited(
+    records: &[FileRecord],
+    long: bool,
+    separator: &str,
+    escape: fn(&str) -> String,
+) -> String {
+    let mut header = vec!["path", "size", "extension"];
+    if long {
+        header.extend(["modified", "is_binary"]);
+    }
+    let mut output = header.join(separator) + "\n";
+
+    for record in records {
+        let mut row = vec![
+            escape(&record.path),
+            record.size.to_string(),
+            escape(record.extension.unwrap_or_default()),
+        ];
+        if long {
+            row.push(record.modified.map(|m| m.to_string()).unwrap_or_default());
+            row.push(record.is_binary.unwrap_or_default().to_string());
+        }
+        output.push_str(&row.join(separator));
+        output.push('\n');
+    }
+
+    output
+}
+
 /// List engine for file operations
 pub struct ListEngine {
     metrics: Arc<Metrics>,
@@ -34,6 +359,7 @@ impl ListEngine {
         recursive: bool,
         show_hidden: bool,
         extensions: Option<&[String]>,
+        case_sensitive_extensions: bool,
         max_size: Option<usize>,
         min_size: Option<usize>,
         sort: crate::cli::SortCriteria,
@@ -55,7 +381,13 @@ impl ListEngine {
                 return;
             }
 
-            if !self.should_include_file(path, extensions, max_size, min_size) {
+            if !self.should_include_file(
+                path,
+                extensions,
+                case_sensitive_extensions,
+                max_size,
+                min_size,
+            ) {
                 return;
             }
 
@@ -98,16 +430,13 @@ impl ListEngine {
         &self,
         path: &Path,
         extensions: Option<&[String]>,
+        case_sensitive_extensions: bool,
         max_size: Option<usize>,
         min_size: Option<usize>,
     ) -> bool {
         // Extension filter
         if let Some(extensions) = extensions {
-            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                if !extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
-                    return false;
-                }
-            } else {
+            if !has_extension(path, extensions, case_sensitive_extensions) {
                 return false;
             }
         }
@@ -134,20 +463,7 @@ impl ListEngine {
 
     /// Get file information
     fn get_file_info(&self, path: &Path) -> RfgrepResult<FileInfo> {
-        let metadata = std::fs::metadata(path)?;
-        let extension = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("none")
-            .to_string();
-
-        Ok(FileInfo {
-            path: path.to_path_buf(),
-            size: metadata.len(),
-            extension,
-            is_binary: crate::processor::is_binary(path),
-            modified: metadata.modified().ok(),
-        })
+        FileInfo::from_path(path)
     }
 
     /// Sort files based on criteria
@@ -157,7 +473,7 @@ impl ListEngine {
                 files.sort_by(|a, b| a.path.cmp(&b.path));
             }
             crate::cli::SortCriteria::Size => {
-                files.sort_by(|a, b| a.size.cmp(&b.size));
+                files.sort_by_key(|a| a.size);
             }
             crate::cli::SortCriteria::Date => {
                 files.sort_by(|a, b| match (&a.modified, &b.modified) {
@@ -234,7 +550,7 @@ impl ListEngine {
         }
 
         let mut ext_vec: Vec<_> = ext_counts.into_iter().collect();
-        ext_vec.sort_by(|a, b| b.1.cmp(&a.1));
+        ext_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
 
         println!("\n{}", "Statistics:".green().bold());
         println!("{}: {}", "Total files".cyan(), files.len());
@@ -19,12 +19,16 @@ mod cli;
 mod cli_config;
 mod compression;
 mod config;
+mod daemon;
 mod error;
 mod file_types;
+mod list;
 mod memory;
+mod metrics;
 mod output_formats;
 mod plugin_cli;
 mod plugin_system;
+mod preprocess;
 mod processor;
 mod search_algorithms;
 mod simd;
@@ -39,33 +43,40 @@ use env_logger::{Builder, Env, Target};
 use std::fs;
 use std::time::Instant;
 
+use std::sync::atomic::AtomicBool;
 #[cfg(unix)]
-use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
-
-#[cfg(unix)]
-static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+use std::sync::atomic::Ordering as AtomicOrdering;
+use std::sync::Arc;
 
+/// Exit like grep: 0 when something was found, 1 when nothing was, 2 on error
 fn main() {
-    let result = main_inner();
-    if let Err(e) = result {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
+    match main_inner() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
     }
 }
 
-fn main_inner() -> RfgrepResult<()> {
+fn main_inner() -> RfgrepResult<bool> {
+    let shutdown = Arc::new(AtomicBool::new(false));
     #[cfg(unix)]
     {
-        let shutdown_flag = &SHUTDOWN_REQUESTED;
+        let shutdown_flag = shutdown.clone();
         ctrlc::set_handler(move || {
-            shutdown_flag.store(true, AtomicOrdering::SeqCst);
+            // A second Ctrl-C doesn't wait for the search to wind down
+            if shutdown_flag.swap(true, AtomicOrdering::SeqCst) {
+                std::process::exit(130);
+            }
             eprintln!("\nShutdown requested, finishing current operations...");
         })
         .expect("Failed to set Ctrl-C handler");
     }
 
     let cli = Cli::parse();
-    // setup_logging(&cli)?;
+    setup_logging(&cli)?;
 
     let start_time = Instant::now();
 
@@ -83,10 +94,16 @@ fn main_inner() -> RfgrepResult<()> {
     }
 
     let rt = tokio::runtime::Runtime::new()?;
-    rt.block_on(async {
-        let app = app_simple::RfgrepApp::new_async().await?;
+    let result = rt.block_on(async {
+        let app = app_simple::RfgrepApp::new_async()
+            .await?
+            .with_shutdown(shutdown);
         app.run(cli).await
-    })?;
+    });
+    // A scan abandoned by --timeout-per-file may still be stuck on one line;
+    // don't wait for it before exiting
+    rt.shutdown_background();
+    let found = result?;
 
     if !suppress_verbose && verbose {
         println!(
@@ -94,11 +111,18 @@ fn main_inner() -> RfgrepResult<()> {
             start_time.elapsed()
         );
     }
-    Ok(())
+    Ok(found)
 }
 
+/// Log warnings by default and rfgrep's own debug records with `--verbose`;
+/// `RUST_LOG` overrides both
 fn setup_logging(cli: &Cli) -> RfgrepResult<()> {
-    let mut builder = Builder::from_env(Env::default().default_filter_or("info"));
+    let level = if cli.verbose {
+        "warn,rfgrep=debug"
+    } else {
+        "warn"
+    };
+    let mut builder = Builder::from_env(Env::default().default_filter_or(level));
 
     builder.format(|buf, record| {
         use std::io::Write;
@@ -122,3 +122,82 @@ impl AdaptiveMemoryManager {
         }
     }
 }
+
+/// A cap on the file bytes that in-flight searches may buffer or map at once
+///
+/// Reservations are counted in KiB permits of a semaphore, so a search that
+/// doesn't fit waits (or streams instead) until others release theirs.
+#[derive(Debug)]
+pub struct MemoryBudget {
+    permits: tokio::sync::Semaphore,
+    limit_kib: u32,
+    in_use_kib: AtomicU64,
+    peak_kib: AtomicU64,
+}
+
+/// Bytes reserved from a [`MemoryBudget`], released on drop
+#[derive(Debug)]
+pub struct MemoryReservation {
+    budget: Arc<MemoryBudget>,
+    kib: u32,
+}
+
+impl MemoryBudget {
+    pub fn new(limit_bytes: u64) -> Self {
+        let limit_kib = (limit_bytes / 1024).clamp(1, u64::from(u32::MAX >> 3)) as u32;
+        Self {
+            permits: tokio::sync::Semaphore::new(limit_kib as usize),
+            limit_kib,
+            in_use_kib: AtomicU64::new(0),
+            peak_kib: AtomicU64::new(0),
+        }
+    }
+
+    /// Wait until `bytes` fit under the cap; anything larger than the whole
+    /// cap waits for all of it
+    pub async fn reserve(self: &Arc<Self>, bytes: u64) -> MemoryReservation {
+        let kib = self.kib_for(bytes);
+        if let Ok(permit) = self.permits.acquire_many(kib).await {
+            permit.forget();
+        }
+        self.track(kib)
+    }
+
+    /// Reserve `bytes` only if they fit right now
+    pub fn try_reserve(self: &Arc<Self>, bytes: u64) -> Option<MemoryReservation> {
+        if bytes > u64::from(self.limit_kib) * 1024 {
+            return None;
+        }
+        let kib = self.kib_for(bytes);
+        let permit = self.permits.try_acquire_many(kib).ok()?;
+        permit.forget();
+        Some(self.track(kib))
+    }
+
+    /// Highest number of bytes reserved at once so far
+    pub fn peak_bytes(&self) -> u64 {
+        self.peak_kib.load(Ordering::Relaxed) * 1024
+    }
+
+    fn kib_for(&self, bytes: u64) -> u32 {
+        bytes.div_ceil(1024).clamp(1, u64::from(self.limit_kib)) as u32
+    }
+
+    fn track(self: &Arc<Self>, kib: u32) -> MemoryReservation {
+        let in_use = self.in_use_kib.fetch_add(u64::from(kib), Ordering::Relaxed) + u64::from(kib);
+        self.peak_kib.fetch_max(in_use, Ordering::Relaxed);
+        MemoryReservation {
+            budget: self.clone(),
+            kib,
+        }
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.budget
+            .in_use_kib
+            .fetch_sub(u64::from(self.kib), Ordering::Relaxed);
+        self.budget.permits.add_permits(self.kib as usize);
+    }
+}
@@ -1,12 +1,18 @@
+use crate::processor::SearchCounters;
 use prometheus::{Encoder, IntCounter, Opts, Registry, TextEncoder};
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Metrics {
     pub files_scanned: IntCounter,
+    pub bytes_scanned: IntCounter,
     pub matches_found: IntCounter,
     pub files_skipped: IntCounter,
     pub worker_timeouts: IntCounter,
+    pub regex_cache_hits: IntCounter,
+    pub regex_cache_misses: IntCounter,
+    pub files_mmapped: IntCounter,
+    pub files_streamed: IntCounter,
     registry: Arc<Registry>,
 }
 
@@ -21,6 +27,8 @@ impl Metrics {
         let registry = Registry::new();
         let files_scanned =
             IntCounter::with_opts(Opts::new("files_scanned", "Number of files scanned")).unwrap();
+        let bytes_scanned =
+            IntCounter::with_opts(Opts::new("bytes_scanned", "Number of bytes scanned")).unwrap();
         let matches_found =
             IntCounter::with_opts(Opts::new("matches_found", "Number of matches found")).unwrap();
         let files_skipped = IntCounter::with_opts(Opts::new(
@@ -31,21 +39,56 @@ impl Metrics {
         let worker_timeouts =
             IntCounter::with_opts(Opts::new("worker_timeouts", "Number of worker timeouts"))
                 .unwrap();
+        let regex_cache_hits = IntCounter::with_opts(Opts::new(
+            "regex_cache_hits",
+            "Number of regex lookups served from the cache",
+        ))
+        .unwrap();
+        let regex_cache_misses = IntCounter::with_opts(Opts::new(
+            "regex_cache_misses",
+            "Number of regexes compiled on a cache miss",
+        ))
+        .unwrap();
+        let files_mmapped = IntCounter::with_opts(Opts::new(
+            "files_mmapped",
+            "Number of files searched through a memory map",
+        ))
+        .unwrap();
+        let files_streamed =
+            IntCounter::with_opts(Opts::new("files_streamed", "Number of files streamed")).unwrap();
 
         registry.register(Box::new(files_scanned.clone())).ok();
+        registry.register(Box::new(bytes_scanned.clone())).ok();
         registry.register(Box::new(matches_found.clone())).ok();
         registry.register(Box::new(files_skipped.clone())).ok();
         registry.register(Box::new(worker_timeouts.clone())).ok();
+        registry.register(Box::new(regex_cache_hits.clone())).ok();
+        registry.register(Box::new(regex_cache_misses.clone())).ok();
+        registry.register(Box::new(files_mmapped.clone())).ok();
+        registry.register(Box::new(files_streamed.clone())).ok();
 
         Metrics {
             files_scanned,
+            bytes_scanned,
             matches_found,
             files_skipped,
             worker_timeouts,
+            regex_cache_hits,
+            regex_cache_misses,
+            files_mmapped,
+            files_streamed,
             registry: Arc::new(registry),
         }
     }
 
+    /// Add the regex cache and read strategy counts of a search
+    pub fn record_counters(&self, counters: SearchCounters) {
+        self.regex_cache_hits.inc_by(counters.regex_cache_hits);
+        self.regex_cache_misses.inc_by(counters.regex_cache_misses);
+        self.files_mmapped.inc_by(counters.mmap_reads);
+        self.files_streamed.inc_by(counters.streamed_reads);
+    }
+
     pub fn gather(&self) -> String {
         let metric_families = self.registry.gather();
         let mut buffer = Vec::new();
@@ -1,7 +1,77 @@
+use crate::config::{ColorScheme, TypeDefinitions};
 use crate::processor::SearchMatch;
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
+/// ANSI SGR parameters for each part of colored text output; `None` leaves
+/// that part uncolored
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    matched: Option<String>,
+    line_number: Option<String>,
+    filename: Option<String>,
+    separator: Option<String>,
+}
+
+impl Palette {
+    /// The colors of a config `ColorScheme`; names that aren't recognized are
+    /// reported and left uncolored
+    pub fn from_scheme(scheme: &ColorScheme) -> Self {
+        let color = |name: &str| {
+            let sgr = ansi_color(name);
+            if sgr.is_none() && !matches!(name.trim(), "" | "none") {
+                log::warn!("Ignoring unknown color '{name}'");
+            }
+            sgr
+        };
+        Self {
+            matched: color(&scheme.r#match),
+            line_number: color(&scheme.line_number),
+            filename: color(&scheme.filename),
+            separator: color(&scheme.separator),
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::from_scheme(&ColorScheme::default())
+    }
+}
+
+/// SGR parameters of a foreground color given by name (`red`, `bright-red`)
+/// or as `#rrggbb`
+pub fn ansi_color(name: &str) -> Option<String> {
+    let name = name.trim().to_ascii_lowercase();
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(format!(
+            "38;2;{};{};{}",
+            channel(0)?,
+            channel(2)?,
+            channel(4)?
+        ));
+    }
+
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+    let (base, name) = match name
+        .strip_prefix("bright-")
+        .or_else(|| name.strip_prefix("bright_"))
+    {
+        Some(name) => (90, name),
+        None => (30, name.as_str()),
+    };
+    let index = NAMES.iter().position(|&known| known == name)?;
+    Some((base + index).to_string())
+}
+
 /// Output format types
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -13,6 +83,8 @@ pub enum OutputFormat {
     Markdown,
     Csv,
     Tsv,
+    Sarif,
+    GithubActions,
 }
 
 /// Default implementation for OutputFormat
@@ -22,6 +94,12 @@ impl Default for OutputFormat {
     }
 }
 
+/// A line of text output around matches: one or more matches, or context
+enum TextLine<'a> {
+    Context(&'a str),
+    Matches(Vec<&'a SearchMatch>),
+}
+
 /// Output formatter for different formats
 #[allow(dead_code)]
 pub struct OutputFormatter {
@@ -30,6 +108,18 @@ pub struct OutputFormatter {
     include_context: bool,
     use_color: bool,
     ndjson: bool,
+    null_separated: bool,
+    only_matching: bool,
+    vimgrep: bool,
+    /// Add each match's `language`, named by these `--type` definitions, to
+    /// JSON records
+    file_type: Option<TypeDefinitions>,
+    /// End NDJSON output with a summary record, reporting this as the time taken
+    summary: Option<std::time::Duration>,
+    show_column: bool,
+    heading: bool,
+    show_filename: bool,
+    palette: Palette,
 }
 
 impl Default for OutputFormatter {
@@ -40,6 +130,15 @@ impl Default for OutputFormatter {
             include_context: true,
             use_color: is_terminal::is_terminal(&std::io::stdout()),
             ndjson: false,
+            null_separated: false,
+            only_matching: false,
+            vimgrep: false,
+            file_type: None,
+            summary: None,
+            show_column: false,
+            heading: is_terminal::is_terminal(&std::io::stdout()),
+            show_filename: true,
+            palette: Palette::default(),
         }
     }
 }
@@ -53,9 +152,80 @@ impl OutputFormatter {
             include_context: true,
             use_color: is_terminal::is_terminal(&std::io::stdout()),
             ndjson: false,
+            null_separated: false,
+            only_matching: false,
+            vimgrep: false,
+            file_type: None,
+            summary: None,
+            show_column: false,
+            heading: is_terminal::is_terminal(&std::io::stdout()),
+            show_filename: true,
+            palette: Palette::default(),
         }
     }
 
+    /// Terminate each text record with `\0` instead of a newline (like `grep -Z`)
+    #[allow(dead_code)]
+    pub fn with_null_separator(mut self, null_separated: bool) -> Self {
+        self.null_separated = null_separated;
+        self
+    }
+
+    /// Print only the matched text of each match instead of the whole line (like `grep -o`)
+    #[allow(dead_code)]
+    pub fn with_only_matching(mut self, only_matching: bool) -> Self {
+        self.only_matching = only_matching;
+        self
+    }
+
+    /// Print every match as its own `path:line:col:line` record for editor
+    /// quickfix lists (like `rg --vimgrep`)
+    #[allow(dead_code)]
+    pub fn with_vimgrep(mut self, vimgrep: bool) -> Self {
+        self.vimgrep = vimgrep;
+        self
+    }
+
+    /// Add a `language` field ("rust", "python", or null if unknown) to
+    /// JSON and NDJSON records, using `types` for the names they define
+    #[allow(dead_code)]
+    pub fn with_file_type(mut self, types: Option<TypeDefinitions>) -> Self {
+        self.file_type = types;
+        self
+    }
+
+    /// Follow NDJSON records with a
+    /// `{"type":"summary","matched_lines":N,"files":M,"elapsed_ms":T}` line,
+    /// where `files` counts the files with matches
+    #[allow(dead_code)]
+    pub fn with_summary(mut self, elapsed: Option<std::time::Duration>) -> Self {
+        self.summary = elapsed;
+        self
+    }
+
+    /// Always include the 1-based column in text output, even when colored
+    #[allow(dead_code)]
+    pub fn with_column(mut self, show_column: bool) -> Self {
+        self.show_column = show_column;
+        self
+    }
+
+    /// Group text matches under one path heading per file instead of
+    /// prefixing every line with the path
+    #[allow(dead_code)]
+    pub fn with_heading(mut self, heading: bool) -> Self {
+        self.heading = heading;
+        self
+    }
+
+    /// Name the file of each text match; without it, records start at the
+    /// line number and there are no path headings
+    #[allow(dead_code)]
+    pub fn with_filename(mut self, show_filename: bool) -> Self {
+        self.show_filename = show_filename;
+        self
+    }
+
     #[allow(dead_code)]
     pub fn with_ndjson(mut self, ndjson: bool) -> Self {
         self.ndjson = ndjson;
@@ -68,6 +238,13 @@ impl OutputFormatter {
         self
     }
 
+    /// Colors used for matches, line numbers, paths and separators
+    #[allow(dead_code)]
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
     #[allow(dead_code)]
     pub fn with_metadata(mut self, include: bool) -> Self {
         self.include_metadata = include;
@@ -91,131 +268,162 @@ impl OutputFormatter {
             OutputFormat::Markdown => self.format_markdown(matches, query, path),
             OutputFormat::Csv => self.format_csv(matches, query, path),
             OutputFormat::Tsv => self.format_tsv(matches, query, path),
+            OutputFormat::Sarif => self.format_sarif(matches, query, path),
+            OutputFormat::GithubActions => self.format_github_actions(matches, query, path),
         }
     }
 
     /// Format as JSON
     #[allow(dead_code)]
     fn format_json(&self, matches: &[SearchMatch], query: &str, path: &Path) -> String {
+        let mut out = Vec::new();
+        match self.write_json(&mut out, matches, query, path) {
+            Ok(()) => String::from_utf8_lossy(&out).into_owned(),
+            Err(e) => format!(r#"{{"error":"json_serialization_failed","details":"{e}"}}"#),
+        }
+    }
+
+    /// Write search results to `w`
+    ///
+    /// JSON output is streamed one match object at a time, so memory use does
+    /// not grow with the size of the serialized result set. Other formats are
+    /// rendered with [`format_results`](Self::format_results) first.
+    pub fn write_results<W: Write>(
+        &self,
+        w: &mut W,
+        matches: &[SearchMatch],
+        query: &str,
+        path: &Path,
+    ) -> std::io::Result<()> {
+        match self.format {
+            OutputFormat::Json => self.write_json(w, matches, query, path),
+            _ => w.write_all(self.format_results(matches, query, path).as_bytes()),
+        }
+    }
+
+    fn write_json<W: Write>(
+        &self,
+        w: &mut W,
+        matches: &[SearchMatch],
+        query: &str,
+        path: &Path,
+    ) -> std::io::Result<()> {
         if self.ndjson {
-            let mut out = String::new();
             for m in matches {
-                let mut match_obj = json!({
-                    "query": query,
-                    "path": m.path.to_string_lossy(),
-                    "line_number": m.line_number,
-                    "line": m.line,
-                    "matched_text": m.matched_text,
-                    "column_start": m.column_start,
-                    "column_end": m.column_end,
+                let mut match_obj = self.json_match(m);
+                match_obj["type"] = json!("match");
+                match_obj["query"] = json!(query);
+                serde_json::to_writer(&mut *w, &match_obj)?;
+                w.write_all(b"\n")?;
+            }
+            if let Some(elapsed) = self.summary {
+                let lines: std::collections::HashSet<(&Path, usize)> = matches
+                    .iter()
+                    .map(|m| (m.path.as_path(), m.line_number))
+                    .collect();
+                let files: std::collections::HashSet<&Path> =
+                    lines.iter().map(|(path, _)| *path).collect();
+                let summary = json!({
+                    "type": "summary",
+                    "matched_lines": lines.len(),
+                    "files": files.len(),
+                    "elapsed_ms": elapsed.as_millis() as u64,
                 });
+                serde_json::to_writer(&mut *w, &summary)?;
+                w.write_all(b"\n")?;
+            }
+            return Ok(());
+        }
 
-                if self.include_context {
-                    let context_before: Vec<Value> = m
-                        .context_before
-                        .iter()
-                        .map(|(num, line)| {
-                            json!({
-                                "line_number": num,
-                                "content": line
-                            })
-                        })
-                        .collect();
-
-                    let context_after: Vec<Value> = m
-                        .context_after
-                        .iter()
-                        .map(|(num, line)| {
-                            json!({
-                                "line_number": num,
-                                "content": line
-                            })
-                        })
-                        .collect();
-
-                    match_obj["context_before"] = Value::Array(context_before);
-                    match_obj["context_after"] = Value::Array(context_after);
-                }
-
-                match serde_json::to_string(&match_obj) {
-                    Ok(s) => {
-                        out.push_str(&s);
-                        out.push('\n');
-                    }
-                    Err(e) => {
-                        let err_obj =
-                            json!({"error": "json_serialization_failed", "details": e.to_string()});
-                        if let Ok(s) = serde_json::to_string(&err_obj) {
-                            out.push_str(&s);
-                            out.push('\n');
-                        } else {
-                            out.push_str("{\"error\":\"json_serialization_failed\"}\n");
-                        }
-                    }
-                }
+        // Keys are written in the same (sorted) order serde_json uses for maps
+        w.write_all(b"{\"matches\":[")?;
+        for (i, m) in matches.iter().enumerate() {
+            if i > 0 {
+                w.write_all(b",")?;
             }
-            return out;
+            serde_json::to_writer(&mut *w, &self.json_match(m))?;
         }
+        w.write_all(b"],\"path\":")?;
+        serde_json::to_writer(&mut *w, &path.to_string_lossy())?;
+        w.write_all(b",\"query\":")?;
+        serde_json::to_writer(&mut *w, query)?;
+        write!(w, ",\"total_matches\":{}}}", matches.len())
+    }
 
-        let mut result = json!({
-            "query": query,
-            "path": path.to_string_lossy(),
-            "total_matches": matches.len(),
-            "matches": []
+    /// JSON object for a single match
+    fn json_match(&self, m: &SearchMatch) -> Value {
+        let mut match_obj = json!({
+            "path": m.path.to_string_lossy(),
+            "line_number": m.line_number,
+            "line": m.line,
+            "matched_text": m.matched_text,
+            "column_start": m.column_start,
+            "column_end": m.column_end,
+            "byte_offset": m.byte_offset,
         });
 
-        let matches_array = result["matches"].as_array_mut().unwrap();
+        if let Some(distance) = m.distance {
+            match_obj["distance"] = json!(distance);
+        }
+        // `line` starts this many bytes into a line too long to report whole
+        if let Some(offset) = m.excerpt {
+            match_obj["line_excerpt_offset"] = json!(offset);
+        }
 
-        for m in matches {
-            let mut match_obj = json!({
-                "path": m.path.to_string_lossy(),
-                "line_number": m.line_number,
-                "line": m.line,
-                "matched_text": m.matched_text,
-                "column_start": m.column_start,
-                "column_end": m.column_end,
-            });
-
-            if self.include_context {
-                let context_before: Vec<Value> = m
-                    .context_before
-                    .iter()
-                    .map(|(num, line)| {
-                        json!({
-                            "line_number": num,
-                            "content": line
-                        })
-                    })
-                    .collect();
+        if m.binary {
+            match_obj["binary"] = json!(true);
+        }
 
-                let context_after: Vec<Value> = m
-                    .context_after
-                    .iter()
-                    .map(|(num, line)| {
-                        json!({
-                            "line_number": num,
-                            "content": line
-                        })
+        if let Some(types) = &self.file_type {
+            match_obj["language"] = json!(crate::file_types::FileTypeClassifier::language(
+                &m.path, types
+            ));
+        }
+
+        if self.include_context {
+            let context_before: Vec<Value> = m
+                .context_before
+                .iter()
+                .map(|(num, line)| {
+                    json!({
+                        "line_number": num,
+                        "content": line
                     })
-                    .collect();
+                })
+                .collect();
 
-                match_obj["context_before"] = Value::Array(context_before);
-                match_obj["context_after"] = Value::Array(context_after);
-            }
+            let context_after: Vec<Value> = m
+                .context_after
+                .iter()
+                .map(|(num, line)| {
+                    json!({
+                        "line_number": num,
+                        "content": line
+                    })
+                })
+                .collect();
 
-            matches_array.push(match_obj);
+            match_obj["context_before"] = Value::Array(context_before);
+            match_obj["context_after"] = Value::Array(context_after);
         }
 
-        serde_json::to_string(&result).unwrap_or_else(|e| {
-            format!(r#"{{"error":"json_serialization_failed","details":"{e}"}}"#)
-        })
+        match_obj
     }
 
     /// Format as plain text (default)
     #[allow(dead_code)]
     fn format_text(&self, matches: &[SearchMatch], query: &str, path: &Path) -> String {
+        if self.vimgrep {
+            return self.format_text_vimgrep(matches);
+        }
+        if self.only_matching {
+            return self.format_text_only_matching(matches);
+        }
+        if self.null_separated {
+            return self.format_text_null(matches);
+        }
+
         let mut output = String::new();
-        let word_len = query.len();
         // metadata header
         if self.include_metadata {
             output.push_str(&format!("Query: {query}\n"));
@@ -223,77 +431,224 @@ impl OutputFormatter {
             output.push_str(&format!("Total matches: {}\n\n", matches.len()));
         }
 
-        if matches.is_empty() {
-            return output;
-        }
-        // default one-line-per-match: path:line:col: line-with-highlight
-        let mut path: &PathBuf = &matches[0].path;
-        output.push_str(&format!("\x1b[38;2;40;172;201m{}\x1b[0m", path.display()));
-        output.push('\n');
-        for m in matches {
-            let ind_match: Vec<usize> =
-                ::memchr::memmem::find_iter(m.matched_text.as_bytes(), query.as_bytes()).collect();
-            let match_indices = ind_match.as_slice();
-            let line_len = m.line.len();
-            let column_start = m.column_start.min(line_len);
-            let column_end = m.column_end.min(line_len);
-            let before = if column_start < line_len {
-                &m.line[..column_start]
-            } else {
-                ""
-            };
-            let matched = &m.matched_text;
-            let after = if column_end < line_len {
-                &m.line[column_end..]
-            } else {
-                ""
-            };
-
-            if self.use_color {
-                // ANSI yellow highlight for match
-                let highlighted = highlight(matched.as_str(), match_indices, word_len);
-                if path == &m.path {
-                    output.push_str(&format!(
-                        "\x1b[38;2;167;29;222m{}\x1b[0m: {before}{highlighted}\n",
-                        m.line_number,
-                    ));
-                } else {
-                    path = &m.path;
-                    output.push('\n');
-                    output.push_str(&format!("\x1b[38;2;40;172;201m{}\x1b[0m", path.display()));
+        // With headings, consecutive matches in a file share one path line and
+        // are listed as `line: content` beneath it; otherwise every match is a
+        // `path:line:col: content` record
+        let headings = self.heading && self.show_filename;
+        let with_context = self.include_context
+            && matches
+                .iter()
+                .any(|m| !m.context_before.is_empty() || !m.context_after.is_empty());
+        let separator = format!("{}\n", self.paint(&self.palette.separator, "--"));
+        let mut current_path: Option<&PathBuf> = None;
+        for run in matches.chunk_by(|a, b| a.path == b.path) {
+            let path = &run[0].path;
+            // Like grep, a binary file's lines aren't printed
+            if run[0].binary {
+                output.push_str(&format!("Binary file {} matches\n", path.display()));
+                continue;
+            }
+            if headings {
+                if current_path.is_some() {
                     output.push('\n');
-                    output.push_str(&format!(
-                        "\x1b[38;2;167;29;222m{}\x1b[0m: {before}{highlighted}\n",
-                        m.line_number,
-                    ));
                 }
-            } else {
-                output.push_str(&format!(
-                    "{}:{}:{}: {before}{matched}{after}\n",
-                    m.path.display(),
-                    m.line_number,
-                    column_start + 1
-                ));
+                output.push_str(&self.paint(&self.palette.filename, &path.display().to_string()));
+                output.push('\n');
+            } else if with_context && current_path.is_some() {
+                output.push_str(&separator);
+            }
+            current_path = Some(path);
+
+            if !with_context {
+                for m in run {
+                    output.push_str(&self.text_record(m));
+                }
+                continue;
             }
 
-            // if context requested, append a small block
-            if self.include_context && (!m.context_before.is_empty() || !m.context_after.is_empty())
-            {
-                output.push_str("-- context --\n");
-                for (num, line) in &m.context_before {
-                    output.push_str(&format!("  {num} │ {line}\n"));
+            // Like `grep -C`, overlapping and adjacent context is merged and
+            // `--` separates the groups of lines that aren't contiguous
+            let mut lines: BTreeMap<usize, TextLine> = BTreeMap::new();
+            for m in run {
+                for (number, line) in m.context_before.iter().chain(&m.context_after) {
+                    lines.entry(*number).or_insert(TextLine::Context(line));
                 }
-                output.push_str(&format!("→ {} │ {before}{matched}{after}\n", m.line_number));
-                for (num, line) in &m.context_after {
-                    output.push_str(&format!("  {num} │ {line}\n"));
+                match lines
+                    .entry(m.line_number)
+                    .or_insert(TextLine::Matches(Vec::new()))
+                {
+                    TextLine::Matches(on_line) => on_line.push(m),
+                    context => *context = TextLine::Matches(vec![m]),
                 }
-                output.push('\n');
             }
+            let mut previous: Option<usize> = None;
+            for (number, line) in lines {
+                if previous.is_some_and(|previous| number > previous + 1) {
+                    output.push_str(&separator);
+                }
+                previous = Some(number);
+                match line {
+                    TextLine::Context(text) => {
+                        output.push_str(&self.context_record(path, number, text))
+                    }
+                    TextLine::Matches(on_line) => {
+                        for m in on_line {
+                            output.push_str(&self.text_record(m));
+                        }
+                    }
+                }
+            }
+        }
+
+        output
+    }
+
+    /// A match's `path:line:col: content` record, or `line: content` under a
+    /// heading
+    fn text_record(&self, m: &SearchMatch) -> String {
+        let line_len = m.line.len();
+        let (span_start, span_end) = m.line_span();
+        let column_start = span_start.min(line_len);
+        let column_end = span_end.min(line_len);
+        if column_end != span_end {
+            log::debug!(
+                "match at {}:{} ends past the line ({} > {line_len}), clamping",
+                m.path.display(),
+                m.line_number,
+                m.column_end
+            );
+        }
+        let (before, after) = split_around_match(&m.line, column_start, column_end);
+        // Highlight the bytes the match covers on the line, which can differ
+        // from the query (case-insensitive or regex matches)
+        let matched = &m.line[before.len()..m.line.len() - after.len()];
+        let shown = self.highlight(matched);
+        let separator = self.paint(&self.palette.separator, ":");
+
+        if self.heading && self.show_filename {
+            let column = if self.show_column {
+                format!(":{}", m.column_start + 1)
+            } else {
+                String::new()
+            };
+            format!(
+                "  {}{separator} {before}{shown}{after}\n",
+                self.paint(
+                    &self.palette.line_number,
+                    &format!("{}{column}", m.line_number)
+                ),
+            )
+        } else {
+            let mut record = String::new();
+            if self.show_filename {
+                record.push_str(&self.paint(&self.palette.filename, &m.path.display().to_string()));
+                record.push_str(&separator);
+            }
+            record.push_str(&format!(
+                "{}{separator}{}{separator} {before}{shown}{after}\n",
+                self.paint(&self.palette.line_number, &m.line_number.to_string()),
+                m.column_start + 1
+            ));
+            record
+        }
+    }
+
+    /// A context line around matches: like grep, `-` takes the place of `:`
+    /// and there's no column
+    fn context_record(&self, path: &Path, line_number: usize, line: &str) -> String {
+        let separator = self.paint(&self.palette.separator, "-");
+        let number = self.paint(&self.palette.line_number, &line_number.to_string());
+        if self.heading && self.show_filename {
+            format!("  {number}{separator} {line}\n")
+        } else if self.show_filename {
+            let path = self.paint(&self.palette.filename, &path.display().to_string());
+            format!("{path}{separator}{number}{separator} {line}\n")
+        } else {
+            format!("{number}{separator} {line}\n")
+        }
+    }
+
+    /// One `--passthru` record. Every input line is echoed; like grep's context
+    /// lines, non-matching ones use `-` instead of `:` after the line number.
+    /// `spans` are the byte ranges of the matches on the line.
+    pub fn format_passthru_line(
+        &self,
+        path: Option<&Path>,
+        line_number: usize,
+        line: &str,
+        spans: &[(usize, usize)],
+    ) -> String {
+        let separator = self.paint(
+            &self.palette.separator,
+            if spans.is_empty() { "-" } else { ":" },
+        );
+        let mut output = String::new();
+        if let Some(path) = path {
+            output.push_str(&self.paint(&self.palette.filename, &path.display().to_string()));
+            output.push_str(&separator);
         }
+        output.push_str(&self.paint(&self.palette.line_number, &line_number.to_string()));
+        output.push_str(&separator);
 
+        let mut last = 0;
+        for &(start, end) in spans {
+            let (before, after) = split_around_match(line, start, end);
+            let (start, end) = (before.len(), line.len() - after.len());
+            // Spans come in line order; skip any that overlap an earlier one
+            if start < last {
+                continue;
+            }
+            output.push_str(&line[last..start]);
+            output.push_str(&self.highlight(&line[start..end]));
+            last = end;
+        }
+        output.push_str(&line[last..]);
+        output.push('\n');
         output
     }
 
+    /// Write one `--format` record per match: `template` filled in with the
+    /// match's capture groups, after the path when `show_path` is set
+    pub fn write_templated<W: Write>(
+        &self,
+        w: &mut W,
+        matches: &[SearchMatch],
+        regex: &crate::search_algorithms::RegexSearch,
+        template: &str,
+        show_path: bool,
+    ) -> std::io::Result<()> {
+        for m in matches {
+            let rendered = regex
+                .expand_match(&m.line, m.line_span().0, template)
+                .unwrap_or_default();
+            if show_path {
+                let path = self.paint(&self.palette.filename, &m.path.display().to_string());
+                let separator = self.paint(&self.palette.separator, ":");
+                writeln!(w, "{path}{separator}{rendered}")?;
+            } else {
+                writeln!(w, "{rendered}")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Wrap `text` in the ANSI color `sgr` when color is enabled
+    fn paint(&self, sgr: &Option<String>, text: &str) -> String {
+        match sgr {
+            Some(sgr) if self.use_color => format!("\x1b[{sgr}m{text}\x1b[0m"),
+            _ => text.to_string(),
+        }
+    }
+
+    /// `matched` in the match color; an empty match (e.g. an inverted one)
+    /// stays empty
+    fn highlight(&self, matched: &str) -> String {
+        if matched.is_empty() {
+            return String::new();
+        }
+        self.paint(&self.palette.matched, matched)
+    }
+
     /// Format as XML
     #[allow(dead_code)]
     fn format_xml(&self, matches: &[SearchMatch], query: &str, path: &Path) -> String {
@@ -389,20 +744,12 @@ impl OutputFormatter {
 
             // Highlight the match
             let line_len = m.line.len();
-            let column_start = m.column_start.min(line_len);
-            let column_end = m.column_end.min(line_len);
+            let (span_start, span_end) = m.line_span();
+            let column_start = span_start.min(line_len);
+            let column_end = span_end.min(line_len);
 
-            let before = if column_start < line_len {
-                &m.line[..column_start]
-            } else {
-                ""
-            };
+            let (before, after) = split_around_match(&m.line, column_start, column_end);
             let matched_text = &m.matched_text;
-            let after = if column_end < line_len {
-                &m.line[column_end..]
-            } else {
-                ""
-            };
 
             output.push_str("<div>");
             let matched_html = format!(
@@ -425,6 +772,65 @@ impl OutputFormatter {
         output
     }
 
+    /// Plain `path:line:col: line` records, each terminated by `\0`, with no
+    /// header or colors so the output can be fed to `xargs -0`
+    fn format_text_null(&self, matches: &[SearchMatch]) -> String {
+        let mut output = String::new();
+        for m in matches {
+            output.push_str(&format!(
+                "{}{}:{}: {}\0",
+                self.path_prefix(m),
+                m.line_number,
+                m.column_start + 1,
+                m.line
+            ));
+        }
+        output
+    }
+
+    /// `path:` before an uncolored record, or nothing without file names
+    fn path_prefix(&self, m: &SearchMatch) -> String {
+        if self.show_filename {
+            format!("{}:", m.path.display())
+        } else {
+            String::new()
+        }
+    }
+
+    /// `path:line:col:matched_text` records, one per match, with no header or
+    /// context. Matches without text (e.g. from `--invert-match`) are skipped.
+    fn format_text_only_matching(&self, matches: &[SearchMatch]) -> String {
+        let terminator = if self.null_separated { '\0' } else { '\n' };
+        let mut output = String::new();
+        for m in matches.iter().filter(|m| !m.matched_text.is_empty()) {
+            output.push_str(&format!(
+                "{}{}:{}:{}{terminator}",
+                self.path_prefix(m),
+                m.line_number,
+                m.column_start + 1,
+                m.matched_text
+            ));
+        }
+        output
+    }
+
+    /// `path:line:col:line` records, one per match so a line with several
+    /// matches is repeated, with no header, context or colors
+    fn format_text_vimgrep(&self, matches: &[SearchMatch]) -> String {
+        let terminator = if self.null_separated { '\0' } else { '\n' };
+        let mut output = String::new();
+        for m in matches {
+            output.push_str(&format!(
+                "{}:{}:{}:{}{terminator}",
+                m.path.display(),
+                m.line_number,
+                m.column_start + 1,
+                m.line
+            ));
+        }
+        output
+    }
+
     /// Format as Markdown
     #[allow(dead_code)]
     fn format_markdown(&self, matches: &[SearchMatch], query: &str, path: &Path) -> String {
@@ -442,20 +848,12 @@ impl OutputFormatter {
             output.push_str(&format!("## Match {}\n\n", i + 1));
 
             let line_len = m.line.len();
-            let column_start = m.column_start.min(line_len);
-            let column_end = m.column_end.min(line_len);
+            let (span_start, span_end) = m.line_span();
+            let column_start = span_start.min(line_len);
+            let column_end = span_end.min(line_len);
 
-            let before = if column_start < line_len {
-                &m.line[..column_start]
-            } else {
-                ""
-            };
+            let (before, after) = split_around_match(&m.line, column_start, column_end);
             let matched = &m.matched_text;
-            let after = if column_end < line_len {
-                &m.line[column_end..]
-            } else {
-                ""
-            };
 
             output.push_str("**Match:**\n");
             output.push_str("```\n");
@@ -509,11 +907,97 @@ impl OutputFormatter {
 
         output
     }
+
+    /// Format as a SARIF 2.1.0 log with one result per match
+    ///
+    /// All matches share a single rule derived from the query. Columns are
+    /// 1-based code points, with `endColumn` pointing just past the match.
+    #[allow(dead_code)]
+    fn format_sarif(&self, matches: &[SearchMatch], query: &str, _path: &Path) -> String {
+        let rule_id = format!("rfgrep/{query}");
+        let results: Vec<Value> = matches
+            .iter()
+            .map(|m| {
+                let (span_start, span_end) = m.line_span();
+                let (before, after) = split_around_match(&m.line, span_start, span_end);
+                let start_column = before.chars().count() + 1;
+                let end_column = m.line.chars().count() - after.chars().count() + 1;
+                json!({
+                    "ruleId": rule_id,
+                    "ruleIndex": 0,
+                    "level": "note",
+                    "message": {
+                        "text": format!("Match for `{query}`: {}", m.matched_text)
+                    },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": m.path.to_string_lossy().replace('\\', "/")
+                            },
+                            "region": {
+                                "startLine": m.line_number,
+                                "startColumn": start_column,
+                                "endColumn": end_column,
+                                "snippet": { "text": m.line }
+                            }
+                        }
+                    }]
+                })
+            })
+            .collect();
+
+        let log = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "rfgrep",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                        "rules": [{
+                            "id": rule_id,
+                            "name": "PatternMatch",
+                            "shortDescription": { "text": format!("Matches for `{query}`") }
+                        }]
+                    }
+                },
+                "columnKind": "unicodeCodePoints",
+                "results": results
+            }]
+        });
+
+        serde_json::to_string_pretty(&log).unwrap_or_else(|e| {
+            format!(r#"{{"error":"sarif_serialization_failed","details":"{e}"}}"#)
+        })
+    }
+
+    /// Format as GitHub Actions workflow commands, one `::warning` per match,
+    /// so matches show up as annotations on the changed lines of a PR
+    ///
+    /// `line` and `col` are 1-based; `col` counts code points like SARIF.
+    #[allow(dead_code)]
+    fn format_github_actions(&self, matches: &[SearchMatch], query: &str, _path: &Path) -> String {
+        let mut output = String::new();
+        for m in matches {
+            let (span_start, span_end) = m.line_span();
+            let (before, _) = split_around_match(&m.line, span_start, span_end);
+            let file = m.path.to_string_lossy().replace('\\', "/");
+            output.push_str(&format!(
+                "::warning file={},line={},col={}::{}\n",
+                escape_workflow_property(&file),
+                m.line_number,
+                before.chars().count() + 1,
+                escape_workflow_data(&format!("Match for `{query}`: {}", m.matched_text)),
+            ));
+        }
+        output
+    }
 }
 
 /// Escape CSV special characters
 #[allow(dead_code)]
-fn escape_csv(s: &str) -> String {
+pub(crate) fn escape_csv(s: &str) -> String {
     if s.contains(',') || s.contains('"') || s.contains('\n') || s.contains('\r') {
         format!("\"{}\"", s.replace("\"", "\"\""))
     } else {
@@ -523,12 +1007,27 @@ fn escape_csv(s: &str) -> String {
 
 /// Escape TSV special characters
 #[allow(dead_code)]
-fn escape_tsv(s: &str) -> String {
+pub(crate) fn escape_tsv(s: &str) -> String {
     s.replace('\t', "\\t")
         .replace('\n', "\\n")
         .replace('\r', "\\r")
 }
 
+/// Escape a workflow command message; a raw newline would end the command
+fn escape_workflow_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value, which also can't contain the
+/// `:` and `,` that delimit properties
+fn escape_workflow_property(s: &str) -> String {
+    escape_workflow_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
 /// Escape XML special characters
 #[allow(dead_code)]
 fn escape_xml(s: &str) -> String {
@@ -549,17 +1048,429 @@ fn escape_html(s: &str) -> String {
         .replace("'", "&#39;")
 }
 
-fn highlight(text: &str, starts: &[usize], word_len: usize) -> String {
-    let mut result = String::new();
-    let mut last = 0;
-    for &start in starts {
-        // push text before match -- This is not bound to always run but it is important
-        result.push_str(&text[last..start]);
-        let highlighted = &text[start..start + word_len];
-        result.push_str(format!("\x1b[38;2;194;93;21m{highlighted}\x1b[0m").as_str());
-        last = start + word_len;
-    }
-    // push remaining tail
-    result.push_str(&text[last..]);
-    result
+/// Split `line` into the text before and after a match, moving the byte
+/// offsets outward to the nearest char boundaries so multibyte content never
+/// causes a slicing panic
+fn split_around_match(line: &str, column_start: usize, column_end: usize) -> (&str, &str) {
+    let mut start = column_start.min(line.len());
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = column_end.clamp(start, line.len());
+    while !line.is_char_boundary(end) {
+        end += 1;
+    }
+    (&line[..start], &line[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multibyte_match() -> SearchMatch {
+        let line = "日本語 match 日本語".to_string();
+        let column_start = line.find("match").unwrap();
+        SearchMatch {
+            path: PathBuf::from("cjk.txt"),
+            line_number: 1,
+            matched_text: "match".to_string(),
+            column_start,
+            column_end: column_start + "match".len(),
+            line,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split_around_match_multibyte() {
+        let m = multibyte_match();
+        assert_eq!(
+            split_around_match(&m.line, m.column_start, m.column_end),
+            ("日本語 ", " 日本語")
+        );
+        // Offsets inside a multibyte char are widened to its boundaries
+        assert_eq!(split_around_match(&m.line, 1, 4), ("", "語 match 日本語"));
+    }
+
+    #[test]
+    fn test_formatters_handle_multibyte_lines() {
+        let matches = vec![multibyte_match()];
+        let path = Path::new(".");
+
+        let text = OutputFormatter::new(OutputFormat::Text)
+            .with_color(false)
+            .format_results(&matches, "match", path);
+        assert!(text.contains("cjk.txt:1:11: 日本語 match 日本語"));
+
+        let html = OutputFormatter::new(OutputFormat::Html).format_results(&matches, "match", path);
+        assert!(html.contains("日本語 <span class=\"matched-text\">match</span> 日本語"));
+
+        let markdown =
+            OutputFormatter::new(OutputFormat::Markdown).format_results(&matches, "match", path);
+        assert!(markdown.contains("日本語 match 日本語"));
+
+        // Misaligned offsets must not panic
+        let mut broken = multibyte_match();
+        broken.column_start = 1;
+        broken.column_end = 2;
+        for format in [
+            OutputFormat::Text,
+            OutputFormat::Html,
+            OutputFormat::Markdown,
+        ] {
+            OutputFormatter::new(format).format_results(
+                std::slice::from_ref(&broken),
+                "match",
+                path,
+            );
+        }
+    }
+
+    #[test]
+    fn test_only_matching_emits_one_record_per_match() {
+        let line = "foo bar foo".to_string();
+        let matches: Vec<SearchMatch> = [0, 8]
+            .into_iter()
+            .map(|start| SearchMatch {
+                path: PathBuf::from("a.txt"),
+                line_number: 3,
+                line: line.clone(),
+                matched_text: "foo".to_string(),
+                column_start: start,
+                column_end: start + 3,
+                ..Default::default()
+            })
+            .collect();
+
+        let text = OutputFormatter::new(OutputFormat::Text)
+            .with_only_matching(true)
+            .format_results(&matches, "foo", Path::new("."));
+        assert_eq!(text, "a.txt:3:1:foo\na.txt:3:9:foo\n");
+    }
+
+    #[test]
+    fn test_vimgrep_repeats_the_line_for_every_match() {
+        let line = "foo bar foo".to_string();
+        let matches: Vec<SearchMatch> = [0, 8]
+            .into_iter()
+            .map(|start| SearchMatch {
+                path: PathBuf::from("a.txt"),
+                line_number: 3,
+                line: line.clone(),
+                matched_text: "foo".to_string(),
+                column_start: start,
+                column_end: start + 3,
+                context_before: vec![(2, "before".to_string())],
+                ..Default::default()
+            })
+            .collect();
+
+        let text = OutputFormatter::new(OutputFormat::Text)
+            .with_vimgrep(true)
+            .with_heading(true)
+            .with_filename(false)
+            .format_results(&matches, "foo", Path::new("."));
+        assert_eq!(text, "a.txt:3:1:foo bar foo\na.txt:3:9:foo bar foo\n");
+    }
+
+    #[test]
+    fn test_context_groups_are_merged_and_separated() {
+        let line = |n: usize| (n, format!("line {n}"));
+        let with_context = |path: &str, n: usize| SearchMatch {
+            path: PathBuf::from(path),
+            line_number: n,
+            line: format!("line {n}"),
+            matched_text: "line".to_string(),
+            column_start: 0,
+            column_end: 4,
+            context_before: vec![line(n - 1)],
+            context_after: vec![line(n + 1)],
+            ..Default::default()
+        };
+        // 3 and 5 share line 4, 6 and 14 are too far apart, 15 is another file
+        let matches = [
+            with_context("a.txt", 3),
+            with_context("a.txt", 5),
+            with_context("a.txt", 15),
+            with_context("b.txt", 2),
+        ];
+
+        let text = OutputFormatter::new(OutputFormat::Text)
+            .with_metadata(false)
+            .format_results(&matches, "line", Path::new("."));
+        assert_eq!(
+            text,
+            "a.txt-2- line 2\n\
+             a.txt:3:1: line 3\n\
+             a.txt-4- line 4\n\
+             a.txt:5:1: line 5\n\
+             a.txt-6- line 6\n\
+             --\n\
+             a.txt-14- line 14\n\
+             a.txt:15:1: line 15\n\
+             a.txt-16- line 16\n\
+             --\n\
+             b.txt-1- line 1\n\
+             b.txt:2:1: line 2\n\
+             b.txt-3- line 3\n"
+        );
+
+        let headed = OutputFormatter::new(OutputFormat::Text)
+            .with_metadata(false)
+            .with_heading(true)
+            .format_results(&matches[2..], "line", Path::new("."));
+        assert_eq!(
+            headed,
+            "a.txt\n  14- line 14\n  15: line 15\n  16- line 16\n\n\
+             b.txt\n  1- line 1\n  2: line 2\n  3- line 3\n"
+        );
+    }
+
+    /// Records the size of every write so tests can check output is streamed
+    #[derive(Default)]
+    struct WriteRecorder {
+        bytes: Vec<u8>,
+        largest_write: usize,
+    }
+
+    impl Write for WriteRecorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.largest_write = self.largest_write.max(buf.len());
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_results_streams_json() {
+        let matches: Vec<SearchMatch> = (1..=10_000)
+            .map(|line_number| SearchMatch {
+                path: PathBuf::from("big.log"),
+                line_number,
+                line: format!("entry {line_number} \"quoted\""),
+                matched_text: "entry".to_string(),
+                column_end: 5,
+                ..Default::default()
+            })
+            .collect();
+
+        let mut recorder = WriteRecorder::default();
+        OutputFormatter::new(OutputFormat::Json)
+            .write_results(&mut recorder, &matches, "entry", Path::new("."))
+            .unwrap();
+        // No single write carries more than a fragment of one match
+        assert!(recorder.largest_write < 256);
+
+        let parsed: Value = serde_json::from_slice(&recorder.bytes).unwrap();
+        assert_eq!(parsed["total_matches"], 10_000);
+        assert_eq!(parsed["matches"].as_array().unwrap().len(), 10_000);
+        assert_eq!(parsed["matches"][9_999]["line"], "entry 10000 \"quoted\"");
+
+        let mut recorder = WriteRecorder::default();
+        OutputFormatter::new(OutputFormat::Json)
+            .with_ndjson(true)
+            .write_results(&mut recorder, &matches, "entry", Path::new("."))
+            .unwrap();
+        let lines: Vec<&[u8]> = recorder.bytes.split(|&b| b == b'\n').collect();
+        assert_eq!(lines.len(), 10_001);
+        for line in &lines[..10_000] {
+            let record: Value = serde_json::from_slice(line).unwrap();
+            assert_eq!(record["query"], "entry");
+            assert_eq!(record["type"], "match");
+        }
+    }
+
+    #[test]
+    fn test_github_actions_annotations() {
+        let matches = vec![
+            SearchMatch {
+                path: PathBuf::from("src/lib.rs"),
+                line_number: 7,
+                line: "    let needle = 1;".to_string(),
+                matched_text: "needle".to_string(),
+                column_start: 8,
+                column_end: 14,
+                ..Default::default()
+            },
+            SearchMatch {
+                path: PathBuf::from("a,b:c.txt"),
+                line_number: 1,
+                line: "100% needle".to_string(),
+                matched_text: "100% needle".to_string(),
+                column_start: 0,
+                column_end: 11,
+                ..Default::default()
+            },
+        ];
+
+        let out = OutputFormatter::new(OutputFormat::GithubActions).format_results(
+            &matches,
+            "needle",
+            Path::new("."),
+        );
+        assert_eq!(
+            out,
+            "::warning file=src/lib.rs,line=7,col=9::Match for `needle`: needle\n\
+             ::warning file=a%2Cb%3Ac.txt,line=1,col=1::Match for `needle`: 100%25 needle\n"
+        );
+        assert_eq!(escape_workflow_data("a\r\nb"), "a%0D%0Ab");
+    }
+
+    #[test]
+    fn test_sarif_output() {
+        let matches = vec![SearchMatch {
+            path: PathBuf::from("src/lib.rs"),
+            line_number: 7,
+            line: "    let needle = 1;".to_string(),
+            matched_text: "needle".to_string(),
+            column_start: 8,
+            column_end: 14,
+            ..Default::default()
+        }];
+
+        let sarif = OutputFormatter::new(OutputFormat::Sarif).format_results(
+            &matches,
+            "needle",
+            Path::new("."),
+        );
+        let log: Value = serde_json::from_str(&sarif).unwrap();
+
+        assert!(log["$schema"].as_str().unwrap().contains("sarif-2.1.0"));
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "rfgrep");
+        assert_eq!(run["tool"]["driver"]["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 1);
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0]["ruleId"],
+            run["tool"]["driver"]["rules"][0]["id"]
+        );
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/lib.rs");
+        assert_eq!(location["region"]["startLine"], 7);
+        assert_eq!(location["region"]["startColumn"], 9);
+        assert_eq!(location["region"]["endColumn"], 15);
+    }
+
+    #[test]
+    fn test_passthru_line_highlights_every_match() {
+        let formatter = OutputFormatter::new(OutputFormat::Text).with_color(true);
+        let line = formatter.format_passthru_line(None, 2, "a cat, a cat", &[(2, 5), (9, 12)]);
+        let cat = "\x1b[38;2;194;93;21mcat\x1b[0m";
+        assert!(line.ends_with(&format!(":a {cat}, a {cat}\n")), "{line:?}");
+
+        let plain = OutputFormatter::new(OutputFormat::Text).with_color(false);
+        assert_eq!(
+            plain.format_passthru_line(Some(Path::new("a.txt")), 1, "no match", &[]),
+            "a.txt-1-no match\n"
+        );
+        assert_eq!(
+            plain.format_passthru_line(None, 3, "日本 cat", &[(1, 8)]),
+            "3:日本 cat\n"
+        );
+    }
+
+    #[test]
+    fn test_ansi_color_names_and_hex() {
+        assert_eq!(ansi_color("red").as_deref(), Some("31"));
+        assert_eq!(ansi_color("Bright-Blue").as_deref(), Some("94"));
+        assert_eq!(ansi_color("#ff8000").as_deref(), Some("38;2;255;128;0"));
+        assert_eq!(ansi_color("#ff80"), None);
+        assert_eq!(ansi_color("none"), None);
+        assert_eq!(ansi_color("mauve"), None);
+
+        let scheme = ColorScheme {
+            r#match: "green".to_string(),
+            separator: "cyan".to_string(),
+            ..Default::default()
+        };
+        let line = OutputFormatter::new(OutputFormat::Text)
+            .with_color(true)
+            .with_palette(Palette::from_scheme(&scheme))
+            .format_passthru_line(None, 1, "a cat", &[(2, 5)]);
+        assert!(
+            line.ends_with("\x1b[36m:\x1b[0ma \x1b[32mcat\x1b[0m\n"),
+            "{line:?}"
+        );
+    }
+
+    #[test]
+    fn test_highlight_covers_the_matched_columns() {
+        let palette = Palette {
+            matched: Some("32".to_string()),
+            ..Default::default()
+        };
+        let record = |line: &str, matched: &str, query: &str, start: usize, end: usize| {
+            let m = SearchMatch {
+                path: PathBuf::from("a.txt"),
+                line_number: 1,
+                line: line.to_string(),
+                matched_text: matched.to_string(),
+                column_start: start,
+                column_end: end,
+                ..Default::default()
+            };
+            OutputFormatter::new(OutputFormat::Text)
+                .with_color(true)
+                .with_palette(palette.clone())
+                .format_results(&[m], query, Path::new("."))
+        };
+
+        // Case-insensitive: the line's text is highlighted, not the query
+        let text = record("a FOO b", "FOO", "foo", 2, 5);
+        assert!(text.contains("a \x1b[32mFOO\x1b[0m b"), "{text:?}");
+
+        // Regex matches are longer than the pattern
+        let text = record("x foooo y", "foooo", "f.+o", 2, 7);
+        assert!(text.contains("x \x1b[32mfoooo\x1b[0m y"), "{text:?}");
+
+        // A match running to the end of the line, or past it
+        let text = record("end foo", "foo", "foo", 4, 7);
+        assert!(text.ends_with("end \x1b[32mfoo\x1b[0m\n"), "{text:?}");
+        let text = record("end foo", "foo", "foo", 4, 40);
+        assert!(text.ends_with("end \x1b[32mfoo\x1b[0m\n"), "{text:?}");
+    }
+
+    #[test]
+    fn test_column_shown_with_color() {
+        let line = "    let needle = 1;".to_string();
+        let column_start = line.find("needle").unwrap();
+        let matches = vec![SearchMatch {
+            path: PathBuf::from("a.rs"),
+            line_number: 3,
+            matched_text: "needle".to_string(),
+            column_start,
+            column_end: column_start + "needle".len(),
+            line,
+            ..Default::default()
+        }];
+        let path = Path::new(".");
+
+        let colored = OutputFormatter::new(OutputFormat::Text)
+            .with_color(true)
+            .with_heading(true)
+            .with_metadata(false)
+            .with_column(true)
+            .format_results(&matches, "needle", path);
+        assert!(colored.contains("3:9\x1b[0m: "), "{colored:?}");
+
+        let plain = OutputFormatter::new(OutputFormat::Text)
+            .with_color(false)
+            .with_column(true)
+            .format_results(&matches, "needle", path);
+        assert!(plain.contains("a.rs:3:9: "));
+
+        let without = OutputFormatter::new(OutputFormat::Text)
+            .with_color(true)
+            .with_heading(true)
+            .format_results(&matches, "needle", path);
+        assert!(without.contains("3\x1b[0m: "));
+    }
 }
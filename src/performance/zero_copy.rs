@@ -10,6 +10,8 @@ pub struct ZeroCopyMatch<'a> {
     pub path: PathBuf,
     pub line_number: usize,
     pub column: usize,
+    /// Where the match starts, in bytes from the start of the content
+    pub byte_offset: usize,
     pub matched_text: &'a str,
     pub context_before: Vec<&'a str>,
     pub context_after: Vec<&'a str>,
@@ -36,6 +38,7 @@ impl<'a> ZeroCopySearchResult<'a> {
         path: PathBuf,
         line_number: usize,
         column: usize,
+        byte_offset: usize,
         matched_text: &'a str,
         context_before: Vec<&'a str>,
         context_after: Vec<&'a str>,
@@ -44,6 +47,7 @@ impl<'a> ZeroCopySearchResult<'a> {
             path,
             line_number,
             column,
+            byte_offset,
             matched_text,
             context_before,
             context_after,
@@ -88,6 +92,9 @@ impl<'a> ZeroCopyProcessor<'a> {
                 let line_number = line_idx + 1;
                 let column = mat.start() + 1;
                 let matched_text = &line[mat.start()..mat.end()];
+                // Every line borrows from `content`, so its offset is the pointer distance
+                let byte_offset =
+                    line.as_ptr() as usize - self.content.as_ptr() as usize + mat.start();
 
                 // Get context lines
                 let context_before = self.get_context_before(line_idx, context_lines);
@@ -97,6 +104,7 @@ impl<'a> ZeroCopyProcessor<'a> {
                     path.clone(),
                     line_number,
                     column,
+                    byte_offset,
                     matched_text,
                     context_before,
                     context_after,
@@ -261,6 +269,7 @@ pub fn to_owned_matches<'a>(
             line: m.matched_text.to_string(), // This should be the full line content
             column_start: m.column,
             column_end: m.column + m.matched_text.len(),
+            byte_offset: m.byte_offset,
             matched_text: m.matched_text.to_string(),
             context_before: m
                 .context_before
@@ -274,6 +283,9 @@ pub fn to_owned_matches<'a>(
                 .enumerate()
                 .map(|(i, s)| (i + 1, s.to_string()))
                 .collect(),
+            distance: None,
+            binary: false,
+            excerpt: None,
         })
         .collect()
 }
@@ -1,6 +1,6 @@
 //! Enhanced plugin system for rfgrep with dynamic loading and better integration
 use crate::error::Result as RfgrepResult;
-use crate::processor::SearchMatch;
+use crate::processor::{lines_with_offsets, SearchMatch};
 use crate::search_algorithms::SearchAlgorithm;
 use crate::streaming_search::{StreamingConfig, StreamingSearchPipeline};
 use libloading::{Library, Symbol};
@@ -85,6 +85,7 @@ pub enum ConfigValueType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginConfig {
     pub enabled: bool,
+    /// Lower values are tried first; starts out as the plugin's own `priority()`
     pub priority: u32,
     pub settings: HashMap<String, serde_json::Value>,
     pub streaming_enabled: bool,
@@ -108,6 +109,10 @@ pub struct EnhancedPluginManager {
     plugins: Arc<RwLock<HashMap<String, Box<dyn EnhancedSearchPlugin>>>>,
     plugin_configs: Arc<RwLock<HashMap<String, PluginConfig>>>,
     streaming_pipeline: Option<StreamingSearchPipeline>,
+    /// Where configs changed with `update_plugin_config` are saved, if anywhere
+    config_file: Option<PathBuf>,
+    /// Configs read from `config_file`, applied as plugins register
+    saved_configs: HashMap<String, PluginConfig>,
 }
 
 impl EnhancedPluginManager {
@@ -116,16 +121,51 @@ impl EnhancedPluginManager {
             plugins: Arc::new(RwLock::new(HashMap::new())),
             plugin_configs: Arc::new(RwLock::new(HashMap::new())),
             streaming_pipeline: None,
+            config_file: None,
+            saved_configs: HashMap::new(),
         }
     }
 
+    /// A manager whose plugin configs persist in `default_config_file()`
+    pub fn load_saved() -> Self {
+        match Self::default_config_file() {
+            Some(path) => Self::new().with_config_file(path),
+            None => Self::new(),
+        }
+    }
+
+    /// `plugins.json` in rfgrep's config directory
+    pub fn default_config_file() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rfgrep").join("plugins.json"))
+    }
+
+    /// Apply the plugin configs saved in `path` and save changes back to it
+    pub fn with_config_file(mut self, path: PathBuf) -> Self {
+        self.saved_configs = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("Ignoring invalid plugin config {}: {}", path.display(), e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        self.config_file = Some(path);
+        self
+    }
+
     /// Register a plugin
     pub async fn register_plugin(
         &self,
         mut plugin: Box<dyn EnhancedSearchPlugin>,
     ) -> RfgrepResult<()> {
         let name = plugin.name().to_string();
-        let config = PluginConfig::default();
+        let config = self
+            .saved_configs
+            .get(&name)
+            .cloned()
+            .unwrap_or_else(|| PluginConfig {
+                priority: plugin.priority(),
+                ..PluginConfig::default()
+            });
 
         plugin.initialize(config.clone())?;
 
@@ -144,15 +184,35 @@ impl EnhancedPluginManager {
         Ok(())
     }
 
-    /// Update plugin configuration
+    /// Update plugin configuration, saving it to the config file if there is one
     pub async fn update_plugin_config(&self, name: &str, config: PluginConfig) -> RfgrepResult<()> {
         if let Some(plugin) = self.plugins.write().await.get_mut(name) {
             plugin.update_config(config.settings.clone())?;
         }
-        self.plugin_configs
-            .write()
-            .await
-            .insert(name.to_string(), config);
+        let mut configs = self.plugin_configs.write().await;
+        configs.insert(name.to_string(), config);
+        self.save_configs(&configs)
+    }
+
+    /// Write the configs of registered plugins to the config file, keeping
+    /// those saved for plugins that aren't loaded right now
+    fn save_configs(&self, configs: &HashMap<String, PluginConfig>) -> RfgrepResult<()> {
+        let Some(path) = &self.config_file else {
+            return Ok(());
+        };
+        let mut saved = self.saved_configs.clone();
+        saved.extend(
+            configs
+                .iter()
+                .map(|(name, config)| (name.clone(), config.clone())),
+        );
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(&saved).map_err(|e| {
+            crate::error::RfgrepError::Other(format!("Failed to save plugin config: {e}"))
+        })?;
+        std::fs::write(path, content)?;
         Ok(())
     }
 
@@ -184,6 +244,10 @@ impl EnhancedPluginManager {
     }
 
     /// Search a file using the best available plugin
+    ///
+    /// Of the enabled plugins that can handle the file, the one with the lowest
+    /// configured priority wins; ties go to the plugin whose name sorts first,
+    /// so the choice never depends on registration order.
     pub async fn search_file(&self, file: &Path, pattern: &str) -> RfgrepResult<Vec<SearchMatch>> {
         let plugins = self.plugins.read().await;
         let configs = self.plugin_configs.read().await;
@@ -200,8 +264,7 @@ impl EnhancedPluginManager {
             })
             .collect();
 
-        // Sort by priority
-        candidates.sort_by_key(|(name, _)| configs.get(*name).map(|c| c.priority).unwrap_or(100));
+        candidates.sort_by_key(|(name, _)| (configs.get(*name).map(|c| c.priority), *name));
 
         if let Some((_, plugin)) = candidates.first() {
             // Check if plugin supports streaming and we have a streaming pipeline
@@ -365,7 +428,7 @@ impl EnhancedSearchPlugin for EnhancedTextSearchPlugin {
         let regex = regex::Regex::new(&regex_pattern)?;
 
         let mut matches = Vec::new();
-        for (line_num, line) in content.lines().enumerate() {
+        for (line_num, (line_start, line)) in lines_with_offsets(&content).enumerate() {
             for mat in regex.find_iter(line) {
                 matches.push(SearchMatch {
                     path: file.to_path_buf(),
@@ -376,6 +439,10 @@ impl EnhancedSearchPlugin for EnhancedTextSearchPlugin {
                     matched_text: mat.as_str().to_string(),
                     column_start: mat.start(),
                     column_end: mat.end(),
+                    byte_offset: line_start + mat.start(),
+                    distance: None,
+                    binary: false,
+                    excerpt: None,
                 });
             }
         }
@@ -514,6 +581,10 @@ impl EnhancedSearchPlugin for EnhancedBinarySearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                byte_offset: absolute_pos,
+                distance: None,
+                binary: false,
+                excerpt: None,
             });
             pos = absolute_pos + 1;
         }
@@ -715,6 +786,100 @@ mod tests {
         assert!(stats.total_plugins > 0);
     }
 
+    /// Claims every file and reports its own name as the match
+    struct ClaimAll(&'static str);
+
+    impl EnhancedSearchPlugin for ClaimAll {
+        fn name(&self) -> &str {
+            self.0
+        }
+        fn version(&self) -> &str {
+            "0.0.0"
+        }
+        fn description(&self) -> &str {
+            "test plugin"
+        }
+        fn can_handle(&self, _file: &Path) -> bool {
+            true
+        }
+        fn priority(&self) -> u32 {
+            50
+        }
+        fn search(&self, _file: &Path, _pattern: &str) -> RfgrepResult<Vec<SearchMatch>> {
+            Ok(vec![SearchMatch {
+                line: self.0.to_string(),
+                ..Default::default()
+            }])
+        }
+        fn supported_extensions(&self) -> Vec<String> {
+            Vec::new()
+        }
+        fn get_config_options(&self) -> HashMap<String, PluginConfigOption> {
+            HashMap::new()
+        }
+        fn update_config(
+            &mut self,
+            _config: HashMap<String, serde_json::Value>,
+        ) -> RfgrepResult<()> {
+            Ok(())
+        }
+    }
+
+    async fn handled_by(manager: &EnhancedPluginManager) -> String {
+        let matches = manager
+            .search_file(Path::new("any.txt"), "x")
+            .await
+            .unwrap();
+        matches[0].line.clone()
+    }
+
+    #[tokio::test]
+    async fn test_priority_picks_the_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("plugins.json");
+        let manager = EnhancedPluginManager::new().with_config_file(config_file.clone());
+        manager
+            .register_plugin(Box::new(ClaimAll("first")))
+            .await
+            .unwrap();
+        manager
+            .register_plugin(Box::new(ClaimAll("second")))
+            .await
+            .unwrap();
+
+        // Equal priorities fall back to the name
+        assert_eq!(handled_by(&manager).await, "first");
+
+        let mut config = manager.get_plugin_config("second").await.unwrap();
+        assert_eq!(config.priority, 50);
+        config.priority = 5;
+        manager
+            .update_plugin_config("second", config)
+            .await
+            .unwrap();
+        assert_eq!(handled_by(&manager).await, "second");
+
+        // The priority survives a restart
+        let restarted = EnhancedPluginManager::new().with_config_file(config_file);
+        restarted
+            .register_plugin(Box::new(ClaimAll("first")))
+            .await
+            .unwrap();
+        restarted
+            .register_plugin(Box::new(ClaimAll("second")))
+            .await
+            .unwrap();
+        assert_eq!(
+            restarted
+                .get_plugin_config("second")
+                .await
+                .unwrap()
+                .priority,
+            5
+        );
+        assert_eq!(handled_by(&restarted).await, "second");
+    }
+
     #[tokio::test]
     async fn test_text_plugin() {
         let _plugin = EnhancedTextSearchPlugin::new();
@@ -0,0 +1,216 @@
+/// `--pre` preprocessors: a command that turns a file (a PDF, say) into the
+/// text that's searched in its place
+///
+/// The command runs in the shell with the file on stdin and its path as `$1`
+/// (and in `RFGREP_PRE_PATH`), so both `tr a-z A-Z` and `pdftotext "$1" -`
+/// work. Whatever it prints is searched, and matches report the original path.
+use crate::error::{Result as RfgrepResult, RfgrepError};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// How long a preprocessor may run on one file unless told otherwise
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A preprocessor command and the files it applies to
+#[derive(Debug, Clone)]
+pub struct Preprocessor {
+    command: String,
+    /// Globs without a `/`, matched against the file name
+    name_globs: Option<GlobSet>,
+    /// Globs with a `/`, matched against the whole path
+    path_globs: Option<GlobSet>,
+    timeout: Duration,
+    max_output_bytes: u64,
+}
+
+impl Preprocessor {
+    /// Run `command` on every file, for up to `DEFAULT_TIMEOUT` each, with
+    /// no cap on its output
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            name_globs: None,
+            path_globs: None,
+            timeout: DEFAULT_TIMEOUT,
+            max_output_bytes: u64::MAX,
+        }
+    }
+
+    /// Only run on files matching one of `globs` (all files if empty)
+    pub fn with_globs(mut self, globs: &[String]) -> RfgrepResult<Self> {
+        let build = |patterns: Vec<&String>| -> RfgrepResult<Option<GlobSet>> {
+            if patterns.is_empty() {
+                return Ok(None);
+            }
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                builder.add(Glob::new(pattern).map_err(|e| {
+                    RfgrepError::Other(format!("Invalid --pre-glob '{pattern}': {e}"))
+                })?);
+            }
+            builder
+                .build()
+                .map(Some)
+                .map_err(|e| RfgrepError::Other(format!("Invalid --pre-glob: {e}")))
+        };
+        let (path_globs, name_globs): (Vec<_>, Vec<_>) =
+            globs.iter().partition(|glob| glob.contains('/'));
+        self.name_globs = build(name_globs)?;
+        self.path_globs = build(path_globs)?;
+        Ok(self)
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Fail a file whose preprocessed text is longer than this
+    pub fn with_max_output_bytes(mut self, max_output_bytes: u64) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    /// Whether `path` should be searched through the preprocessor
+    pub fn applies_to(&self, path: &Path) -> bool {
+        if self.name_globs.is_none() && self.path_globs.is_none() {
+            return true;
+        }
+        let name_matches = |globs: &GlobSet| {
+            path.file_name()
+                .is_some_and(|name| globs.is_match(Path::new(name)))
+        };
+        self.name_globs.as_ref().is_some_and(name_matches)
+            || self
+                .path_globs
+                .as_ref()
+                .is_some_and(|globs| globs.is_match(path))
+    }
+
+    /// Run the command on `path` and return what it printed
+    pub fn run(&self, path: &Path) -> RfgrepResult<Vec<u8>> {
+        let input = File::open(path).map_err(RfgrepError::Io)?;
+        let mut child = self
+            .shell_command(path)
+            .stdin(input)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| {
+                RfgrepError::Other(format!(
+                    "Failed to run preprocessor '{}': {e}",
+                    self.command
+                ))
+            })?;
+
+        // Read on another thread so a command that hangs can be killed
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let limit = self.max_output_bytes.saturating_add(1);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let result = stdout.take(limit).read_to_end(&mut output).map(|_| output);
+            let _ = sender.send(result);
+        });
+
+        let stop = |child: &mut std::process::Child, reason: String| {
+            let _ = child.kill();
+            let _ = child.wait();
+            Err(RfgrepError::Other(format!(
+                "Preprocessor '{}' {reason} on {}",
+                self.command,
+                path.display()
+            )))
+        };
+        let output = match receiver.recv_timeout(self.timeout) {
+            Ok(result) => result.map_err(RfgrepError::Io)?,
+            Err(_) => return stop(&mut child, format!("timed out after {:?}", self.timeout)),
+        };
+        if output.len() as u64 > self.max_output_bytes {
+            return stop(
+                &mut child,
+                format!("printed more than {} bytes", self.max_output_bytes),
+            );
+        }
+
+        let status = child.wait().map_err(RfgrepError::Io)?;
+        if !status.success() {
+            return Err(RfgrepError::Other(format!(
+                "Preprocessor '{}' failed on {}: {status}",
+                self.command,
+                path.display()
+            )));
+        }
+        Ok(output)
+    }
+
+    #[cfg(unix)]
+    fn shell_command(&self, path: &Path) -> Command {
+        let mut command = Command::new("sh");
+        // `$0` names the script in error messages, `$1` is the file
+        command
+            .arg("-c")
+            .arg(&self.command)
+            .arg("rfgrep-pre")
+            .arg(path)
+            .env("RFGREP_PRE_PATH", path);
+        command
+    }
+
+    #[cfg(not(unix))]
+    fn shell_command(&self, path: &Path) -> Command {
+        let mut command = Command::new("cmd");
+        command
+            .arg("/C")
+            .arg(&self.command)
+            .env("RFGREP_PRE_PATH", path);
+        command
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_globs_pick_the_files() {
+        let pre = Preprocessor::new("cat")
+            .with_globs(&["*.pdf".to_string(), "docs/**/*.txt".to_string()])
+            .unwrap();
+        assert!(pre.applies_to(Path::new("/tmp/report.pdf")));
+        assert!(pre.applies_to(Path::new("docs/a/b.txt")));
+        assert!(!pre.applies_to(Path::new("notes.txt")));
+        assert!(Preprocessor::new("cat").applies_to(Path::new("notes.txt")));
+    }
+
+    #[test]
+    fn test_output_limit_and_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "hello\n").unwrap();
+
+        assert_eq!(
+            Preprocessor::new("tr a-z A-Z").run(&file).unwrap(),
+            b"HELLO\n"
+        );
+        assert_eq!(
+            Preprocessor::new("echo \"$1\"").run(&file).unwrap(),
+            format!("{}\n", file.display()).into_bytes()
+        );
+        let error = Preprocessor::new("cat")
+            .with_max_output_bytes(3)
+            .run(&file)
+            .unwrap_err();
+        assert!(error.to_string().contains("more than 3 bytes"), "{error}");
+        let error = Preprocessor::new("sleep 5")
+            .with_timeout(Duration::from_millis(100))
+            .run(&file)
+            .unwrap_err();
+        assert!(error.to_string().contains("timed out"), "{error}");
+        assert!(Preprocessor::new("exit 3").run(&file).is_err());
+    }
+}
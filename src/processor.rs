@@ -12,6 +12,7 @@ use std::fs::File;
 use std::fs::Metadata;
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 use std::time::Instant;
 
@@ -68,12 +69,89 @@ pub struct SearchMatch {
     pub matched_text: String,
     pub column_start: usize,
     pub column_end: usize,
+    /// Where the match starts, in bytes from the start of the file (of the
+    /// decompressed or transcoded text, for files that are converted first)
+    #[serde(default)]
+    pub byte_offset: usize,
+    /// Edit distance between the match and the pattern, for approximate searches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<usize>,
+    /// Found in a binary file under `--binary-mode binary`; only that the file
+    /// matches is reported, so `line` is empty
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub binary: bool,
+    /// Set when `line` is only part of an over-long line: how many bytes into
+    /// the line it starts. The columns are still those of the whole line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excerpt: Option<usize>,
+}
+
+impl SearchMatch {
+    /// Where the match is in `line`, which is further left than its columns
+    /// when `line` is an excerpt
+    pub fn line_span(&self) -> (usize, usize) {
+        let offset = self.excerpt.unwrap_or(0);
+        (
+            self.column_start.saturating_sub(offset),
+            self.column_end.saturating_sub(offset),
+        )
+    }
 }
 
 lazy_static! {
     static ref REGEX_CACHE: Mutex<HashMap<String, Regex>> = Mutex::new(HashMap::new());
 }
 
+static REGEX_CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static REGEX_CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static MMAP_READS: AtomicU64 = AtomicU64::new(0);
+static STREAMED_READS: AtomicU64 = AtomicU64::new(0);
+
+/// Process-wide counts of regex cache lookups and of how files were read
+///
+/// The counters only ever grow; take a snapshot before and after a search
+/// and use `since` to get the counts for that search alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchCounters {
+    pub regex_cache_hits: u64,
+    pub regex_cache_misses: u64,
+    /// Files searched through a memory map
+    pub mmap_reads: u64,
+    /// Files searched by streaming them through a buffer
+    pub streamed_reads: u64,
+}
+
+impl SearchCounters {
+    pub fn snapshot() -> Self {
+        Self {
+            regex_cache_hits: REGEX_CACHE_HITS.load(Ordering::Relaxed),
+            regex_cache_misses: REGEX_CACHE_MISSES.load(Ordering::Relaxed),
+            mmap_reads: MMAP_READS.load(Ordering::Relaxed),
+            streamed_reads: STREAMED_READS.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The counts recorded after `earlier` was taken
+    pub fn since(self, earlier: Self) -> Self {
+        Self {
+            regex_cache_hits: self
+                .regex_cache_hits
+                .saturating_sub(earlier.regex_cache_hits),
+            regex_cache_misses: self
+                .regex_cache_misses
+                .saturating_sub(earlier.regex_cache_misses),
+            mmap_reads: self.mmap_reads.saturating_sub(earlier.mmap_reads),
+            streamed_reads: self.streamed_reads.saturating_sub(earlier.streamed_reads),
+        }
+    }
+}
+
+/// Count a file as read through a memory map or streamed
+pub fn record_read(mapped: bool) {
+    let counter = if mapped { &MMAP_READS } else { &STREAMED_READS };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
 pub fn is_binary(file: &Path) -> bool {
     // If it's a supported compressed file,... treat it as searchable (non-binary skip)
     if crate::compression::is_compressed(file) {
@@ -138,7 +216,7 @@ pub fn is_binary(file: &Path) -> bool {
                 if (null_bytes as f64) > binary_threshold {
                     debug!(
                         "Null byte heuristic detected binary file: {}",
-                        f.metadata().map(|m| m.len()).unwrap_or(0)
+                        file.display()
                     );
                     return true;
                 }
@@ -168,27 +246,18 @@ pub fn should_skip(path: &Path, metadata: &Metadata) -> bool {
         }
     }
 
-    let ftype = metadata.file_type();
+    // Symlinks are not rejected here: whether they are followed is decided
+    // by the walker (`--follow-symlinks`)
     #[cfg(unix)]
     {
         use std::os::unix::fs::FileTypeExt;
-        if ftype.is_socket()
-            || ftype.is_fifo()
-            || ftype.is_block_device()
-            || ftype.is_char_device()
-            || ftype.is_symlink()
+        let ftype = metadata.file_type();
+        if ftype.is_socket() || ftype.is_fifo() || ftype.is_block_device() || ftype.is_char_device()
         {
             debug!("Skipping special unix file type: {}", path.display());
             return true;
         }
     }
-    #[cfg(windows)]
-    {
-        if ftype.is_symlink() {
-            debug!("Skipping symlink on windows: {}", path.display());
-            return true;
-        }
-    }
 
     let classifier = FileTypeClassifier::new();
     match classifier.should_search(path, metadata) {
@@ -225,15 +294,22 @@ pub fn get_or_compile_regex(pattern: &str) -> RfgrepResult<Regex> {
     let mut cache = REGEX_CACHE.lock().unwrap();
     if let Some(regex) = cache.get(pattern) {
         debug!("Regex cache hit for pattern: {pattern}");
+        REGEX_CACHE_HITS.fetch_add(1, Ordering::Relaxed);
         Ok(regex.clone())
     } else {
         debug!("Regex cache miss for pattern: {pattern}. Compiling.");
+        REGEX_CACHE_MISSES.fetch_add(1, Ordering::Relaxed);
         let regex = Regex::new(pattern).map_err(RfgrepError::Regex)?;
         cache.insert(pattern.to_string(), regex.clone());
         Ok(regex)
     }
 }
 
+/// Whether `pattern` has already been compiled by `get_or_compile_regex`
+pub fn is_regex_cached(pattern: &str) -> bool {
+    REGEX_CACHE.lock().unwrap().contains_key(pattern)
+}
+
 pub fn search_file(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch>> {
     let _start = Instant::now();
     let file_display = path.display();
@@ -276,11 +352,15 @@ pub fn search_file(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch
                     return Ok(vec![]);
                 }
                 match std::str::from_utf8(&mmap) {
-                    Ok(content) => find_matches_with_context(content.to_string(), pattern, path)?,
+                    Ok(content) => {
+                        record_read(true);
+                        find_matches_with_context(content.to_string(), pattern, path)?
+                    }
                     Err(e) => {
                         warn!(
                             "Invalid UTF-8 in file {file_display}, falling back to streaming: {e}"
                         );
+                        record_read(false);
                         let reader = BufReader::new(file);
                         find_matches_streaming(reader, pattern, path)?
                     }
@@ -288,11 +368,13 @@ pub fn search_file(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch
             }
             Err(_) => {
                 warn!("Failed to memory map, falling back to streaming: {file_display}");
+                record_read(false);
                 let reader = BufReader::new(file);
                 find_matches_streaming(reader, pattern, path)?
             }
         }
     } else {
+        record_read(false);
         let reader = BufReader::new(file);
         find_matches_streaming(reader, pattern, path)?
     };
@@ -305,23 +387,125 @@ pub fn search_file(path: &Path, pattern: &Regex) -> RfgrepResult<Vec<SearchMatch
     Ok(matches_found)
 }
 
+/// `line` without the `\n` or `\r\n` that ends it, like `str::lines` yields
+pub fn trim_line_ending(line: &str) -> &str {
+    match line.strip_suffix('\n') {
+        Some(line) => line.strip_suffix('\r').unwrap_or(line),
+        None => line,
+    }
+}
+
+/// The lines of `content`, each with the byte offset it starts at
+pub fn lines_with_offsets(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    content.split_inclusive('\n').scan(0, |offset, raw| {
+        let start = *offset;
+        *offset += raw.len();
+        Some((start, trim_line_ending(raw)))
+    })
+}
+
+/// Error from [`OffsetLines`] for a line longer than its `max_line_len`
+#[derive(Debug)]
+pub struct LineTooLong;
+
+impl std::fmt::Display for LineTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line too long to read whole")
+    }
+}
+
+impl std::error::Error for LineTooLong {}
+
+/// Whether `e` is a [`LineTooLong`] error
+pub fn is_line_too_long(e: &std::io::Error) -> bool {
+    e.get_ref().is_some_and(|inner| inner.is::<LineTooLong>())
+}
+
+/// Like `BufRead::lines`, but each line comes with the byte offset it starts
+/// at. A line that isn't UTF-8 is an `InvalidData` error, after which reading
+/// can carry on with the next line.
+pub struct OffsetLines<R> {
+    reader: R,
+    offset: usize,
+    buf: Vec<u8>,
+    lossy: bool,
+    max_line_len: Option<usize>,
+}
+
+impl<R: BufRead> OffsetLines<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            offset: 0,
+            buf: Vec::new(),
+            lossy: false,
+            max_line_len: None,
+        }
+    }
+
+    /// Fail with [`LineTooLong`] instead of buffering a line longer than
+    /// `max` bytes; reading can't carry on after that
+    pub fn max_line_len(mut self, max: usize) -> Self {
+        self.max_line_len = Some(max);
+        self
+    }
+
+    /// Replace invalid UTF-8 with U+FFFD instead of failing the line
+    pub fn lossy(mut self, lossy: bool) -> Self {
+        self.lossy = lossy;
+        self
+    }
+}
+
+impl<R: BufRead> Iterator for OffsetLines<R> {
+    type Item = std::io::Result<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buf.clear();
+        let read = match self.max_line_len {
+            Some(max) => {
+                let mut limited = Read::take(&mut self.reader, max as u64 + 1);
+                match limited.read_until(b'\n', &mut self.buf) {
+                    Ok(read) if read > max && self.buf.last() != Some(&b'\n') => {
+                        return Some(Err(std::io::Error::other(LineTooLong)));
+                    }
+                    result => result,
+                }
+            }
+            None => self.reader.read_until(b'\n', &mut self.buf),
+        };
+        match read {
+            Ok(0) => None,
+            Ok(read) => {
+                let start = self.offset;
+                self.offset += read;
+                Some(match std::str::from_utf8(&self.buf) {
+                    Ok(line) => Ok((start, trim_line_ending(line).to_string())),
+                    Err(_) if self.lossy => Ok((
+                        start,
+                        trim_line_ending(&String::from_utf8_lossy(&self.buf)).to_string(),
+                    )),
+                    Err(e) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+                })
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
 pub fn find_matches_with_context(
     content: String,
     pattern: &Regex,
     path: &Path,
 ) -> RfgrepResult<Vec<SearchMatch>> {
     let mut matches = Vec::new();
-    let lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let (starts, lines): (Vec<usize>, Vec<String>) = lines_with_offsets(&content)
+        .map(|(start, line)| (start, line.to_string()))
+        .unzip();
     for (i, line) in lines.iter().enumerate() {
         if let Some(m) = pattern.find(line) {
-            let start_idx = i.saturating_sub(CONTEXT_LINES);
-            let context_before: Vec<(usize, String)> = (start_idx..i)
-                .map(|idx| (idx + 1, lines[idx].clone()))
-                .collect();
-            let end_idx = (i + CONTEXT_LINES + 1).min(lines.len());
-            let context_after: Vec<(usize, String)> = ((i + 1)..end_idx)
-                .map(|idx| (idx + 1, lines[idx].to_string()))
-                .collect();
+            let (context_before, context_after) =
+                context_around(&lines, i, i, CONTEXT_LINES, CONTEXT_LINES);
             matches.push(SearchMatch {
                 path: path.to_path_buf(),
                 line_number: i + 1,
@@ -331,48 +515,223 @@ pub fn find_matches_with_context(
                 matched_text: m.as_str().to_string(),
                 column_start: m.start(),
                 column_end: m.end(),
+                byte_offset: starts[i] + m.start(),
+                distance: None,
+                binary: false,
+                excerpt: None,
             });
         }
     }
     Ok(matches)
 }
 
-pub fn find_matches_streaming<R: Read>(
-    reader: BufReader<R>,
+/// Letters, digits and `_`, the characters `-w` won't let a match touch
+pub fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether `text[start..end]` has no word character on either side, like
+/// grep's `-w`. Unlike `\b`, this also holds for matches that begin or end
+/// with a non-word character.
+pub fn is_whole_word(text: &str, start: usize, end: usize) -> bool {
+    let before = text.get(..start).and_then(|s| s.chars().next_back());
+    let after = text.get(end..).and_then(|s| s.chars().next());
+    !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+}
+
+/// Find matches that may span several lines
+///
+/// The regex runs over the whole content rather than line by line, so it can
+/// match across `\n`. `line_number` is the line the match starts on, `line`
+/// holds every line the match touches, and the columns are byte offsets into
+/// that `line`.
+pub fn find_matches_multiline(
+    content: &str,
     pattern: &Regex,
     path: &Path,
+    context_before: usize,
+    context_after: usize,
 ) -> RfgrepResult<Vec<SearchMatch>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+
     let mut matches = Vec::new();
-    let mut buffer: VecDeque<(usize, String)> = VecDeque::with_capacity(2 * CONTEXT_LINES + 1);
-    let mut lines_iter = reader.lines();
-    let mut line_no = 0usize;
-    while let Some(line_res) = lines_iter.next() {
-        line_no += 1;
-        let line = line_res.map_err(RfgrepError::Io)?;
-        buffer.push_back((line_no, line.clone()));
-        if buffer.len() > 2 * CONTEXT_LINES + 1 {
-            buffer.pop_front();
+    for m in pattern.find_iter(content) {
+        if m.is_empty() {
+            continue;
         }
+        let first = line_of(m.start());
+        let last = line_of(m.end() - 1);
+        let block_start = line_starts[first];
+        let block_end = line_starts
+            .get(last + 1)
+            .map_or(content.len(), |next| next - 1);
+        let line = content[block_start..block_end].trim_end_matches('\r');
+
+        let (context_before, context_after) =
+            context_around(&lines, first, last, context_before, context_after);
+        matches.push(SearchMatch {
+            path: path.to_path_buf(),
+            line_number: first + 1,
+            line: line.to_string(),
+            context_before,
+            context_after,
+            matched_text: m.as_str().to_string(),
+            column_start: m.start() - block_start,
+            column_end: (m.end() - block_start).min(line.len()),
+            byte_offset: m.start(),
+            distance: None,
+            binary: false,
+            excerpt: None,
+        });
+    }
+    Ok(matches)
+}
+
+/// `(line_number, line)` pairs
+type NumberedLines = Vec<(usize, String)>;
+
+/// Numbered lines surrounding `lines[first..=last]`
+fn context_around<S: AsRef<str>>(
+    lines: &[S],
+    first: usize,
+    last: usize,
+    context_before: usize,
+    context_after: usize,
+) -> (NumberedLines, NumberedLines) {
+    let numbered = |idx: usize| (idx + 1, lines[idx].as_ref().to_string());
+    let before = (first.saturating_sub(context_before)..first)
+        .map(numbered)
+        .collect();
+    let end = (last + context_after + 1).min(lines.len());
+    let after = ((last + 1)..end).map(numbered).collect();
+    (before, after)
+}
+
+pub fn find_matches_streaming<R: Read>(
+    reader: BufReader<R>,
+    pattern: &Regex,
+    path: &Path,
+) -> RfgrepResult<Vec<SearchMatch>> {
+    let mut matches: Vec<SearchMatch> = Vec::new();
+    let mut before: VecDeque<(usize, String)> = VecDeque::with_capacity(CONTEXT_LINES + 1);
+    // Matches still collecting trailing context: (index into `matches`, lines wanted).
+    // Context lines are not consumed here, so they are searched like any other line.
+    let mut pending_after: Vec<(usize, usize)> = Vec::new();
+    for (index, line_res) in OffsetLines::new(reader).enumerate() {
+        let line_no = index + 1;
+        let (line_start, line) = line_res.map_err(RfgrepError::Io)?;
+
+        pending_after.retain_mut(|(match_index, remaining)| {
+            matches[*match_index]
+                .context_after
+                .push((line_no, line.clone()));
+            *remaining -= 1;
+            *remaining > 0
+        });
+
         if let Some(m) = pattern.find(&line) {
-            let context_before: Vec<(usize, String)> =
-                buffer.iter().take(CONTEXT_LINES).cloned().collect();
-            let mut context_after = Vec::new();
-            for next in lines_iter.by_ref().take(CONTEXT_LINES) {
-                let nline = next.map_err(RfgrepError::Io)?;
-                line_no += 1;
-                context_after.push((line_no, nline));
-            }
             matches.push(SearchMatch {
                 path: path.to_path_buf(),
                 line_number: line_no,
                 line: line.clone(),
-                context_before,
-                context_after,
+                context_before: before.iter().cloned().collect(),
+                context_after: Vec::new(),
                 matched_text: m.as_str().to_string(),
                 column_start: m.start(),
                 column_end: m.end(),
+                byte_offset: line_start + m.start(),
+                distance: None,
+                binary: false,
+                excerpt: None,
             });
+            if CONTEXT_LINES > 0 {
+                pending_after.push((matches.len() - 1, CONTEXT_LINES));
+            }
+        }
+
+        before.push_back((line_no, line));
+        if before.len() > CONTEXT_LINES {
+            before.pop_front();
         }
     }
     Ok(matches)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_pattern_records_a_cache_hit() {
+        // Other tests share the cache, so only the counts' growth is checked
+        let pattern = "cache-counter-test-[0-9]+";
+        let before = SearchCounters::snapshot();
+        get_or_compile_regex(pattern).unwrap();
+        let first = SearchCounters::snapshot().since(before);
+        assert!(first.regex_cache_misses >= 1);
+
+        let before = SearchCounters::snapshot();
+        get_or_compile_regex(pattern).unwrap();
+        let second = SearchCounters::snapshot().since(before);
+        assert!(second.regex_cache_hits >= 1);
+    }
+
+    #[test]
+    fn test_find_matches_streaming_adjacent_matches() {
+        let text = "hit one\nhit two\nplain\nhit three\n";
+        let pattern = Regex::new("hit").unwrap();
+        let matches =
+            find_matches_streaming(BufReader::new(text.as_bytes()), &pattern, Path::new("t"))
+                .unwrap();
+
+        let line_numbers: Vec<usize> = matches.iter().map(|m| m.line_number).collect();
+        assert_eq!(line_numbers, vec![1, 2, 4]);
+        assert_eq!(
+            matches[0].context_after,
+            vec![(2, "hit two".to_string()), (3, "plain".to_string())]
+        );
+        assert_eq!(
+            matches[2].context_before,
+            vec![(2, "hit two".to_string()), (3, "plain".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_byte_offsets_count_line_endings() {
+        let text = "first line\r\nsecond\n\nthe needle is here\n";
+        let pattern = Regex::new("needle").unwrap();
+        let expected = text.find("needle").unwrap();
+
+        let streamed =
+            find_matches_streaming(BufReader::new(text.as_bytes()), &pattern, Path::new("t"))
+                .unwrap();
+        let buffered =
+            find_matches_with_context(text.to_string(), &pattern, Path::new("t")).unwrap();
+
+        for matches in [streamed, buffered] {
+            assert_eq!(matches.len(), 1);
+            assert_eq!(matches[0].line_number, 4);
+            assert_eq!(matches[0].byte_offset, expected);
+        }
+    }
+
+    #[test]
+    fn test_find_matches_multiline_spans_lines() {
+        let text = "// header\nfn foo(\n    bar: u32,\n) {}\n";
+        let pattern = Regex::new(r"(?s)fn foo\(\s*\n\s*bar").unwrap();
+        let matches = find_matches_multiline(text, &pattern, Path::new("t.rs"), 1, 1).unwrap();
+
+        assert_eq!(matches.len(), 1);
+        let m = &matches[0];
+        assert_eq!(m.line_number, 2);
+        assert_eq!(m.matched_text, "fn foo(\n    bar");
+        assert_eq!(m.line, "fn foo(\n    bar: u32,");
+        assert_eq!(&m.line[m.column_start..m.column_end], m.matched_text);
+        assert_eq!(m.context_before, vec![(1, "// header".to_string())]);
+        assert_eq!(m.context_after, vec![(4, ") {}".to_string())]);
+    }
+}
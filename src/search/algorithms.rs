@@ -90,6 +90,10 @@ impl SearchAlgorithmTrait for SimdSearch {
                     matched_text,
                     column_start,
                     column_end,
+                    byte_offset: match_pos,
+                    distance: None,
+                    binary: false,
+                    excerpt: None,
                 });
             }
         }
@@ -190,6 +194,10 @@ impl SearchAlgorithmTrait for AhoCorasickSearch {
                     column_start: match_pos - text[..match_pos].rfind('\n').unwrap_or(0),
                     column_end: match_pos - text[..match_pos].rfind('\n').unwrap_or(0)
                         + matched_text.len(),
+                    byte_offset: match_pos,
+                    distance: None,
+                    binary: false,
+                    excerpt: None,
                 });
             }
         }
@@ -333,6 +341,10 @@ impl SearchAlgorithmTrait for FuzzySearch {
                     matched_text,
                     column_start,
                     column_end,
+                    byte_offset: match_pos,
+                    distance: None,
+                    binary: false,
+                    excerpt: None,
                 });
             }
         }
@@ -494,6 +506,10 @@ impl SearchAlgorithmTrait for RabinKarpSearch {
                     matched_text,
                     column_start,
                     column_end,
+                    byte_offset: match_pos,
+                    distance: None,
+                    binary: false,
+                    excerpt: None,
                 });
             }
         }
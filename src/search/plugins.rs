@@ -1,6 +1,6 @@
 //! Plugin system for extensible search capabilities
 use crate::error::Result as RfgrepResult;
-use crate::processor::SearchMatch;
+use crate::processor::{lines_with_offsets, SearchMatch};
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -132,7 +132,7 @@ impl SearchPlugin for TextSearchPlugin {
         let regex = regex::Regex::new(pattern)?;
 
         let mut matches = Vec::new();
-        for (line_num, line) in content.lines().enumerate() {
+        for (line_num, (line_start, line)) in lines_with_offsets(&content).enumerate() {
             for mat in regex.find_iter(line) {
                 matches.push(SearchMatch {
                     path: file.to_path_buf(),
@@ -143,6 +143,10 @@ impl SearchPlugin for TextSearchPlugin {
                     matched_text: mat.as_str().to_string(),
                     column_start: mat.start(),
                     column_end: mat.end(),
+                    byte_offset: line_start + mat.start(),
+                    distance: None,
+                    binary: false,
+                    excerpt: None,
                 });
             }
         }
@@ -216,6 +220,10 @@ impl SearchPlugin for BinarySearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                byte_offset: absolute_pos,
+                distance: None,
+                binary: false,
+                excerpt: None,
             });
             pos = absolute_pos + 1;
         }
@@ -286,6 +294,10 @@ impl SearchPlugin for ArchiveSearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                byte_offset: absolute_pos,
+                distance: None,
+                binary: false,
+                excerpt: None,
             });
             pos = absolute_pos + 1;
         }
@@ -352,6 +364,10 @@ impl SearchPlugin for DatabaseSearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                byte_offset: absolute_pos,
+                distance: None,
+                binary: false,
+                excerpt: None,
             });
             pos = absolute_pos + 1;
         }
@@ -421,6 +437,10 @@ impl SearchPlugin for ImageSearchPlugin {
                 matched_text: pattern.to_string(),
                 column_start: absolute_pos,
                 column_end: absolute_pos + pattern_bytes.len(),
+                byte_offset: absolute_pos,
+                distance: None,
+                binary: false,
+                excerpt: None,
             });
             pos = absolute_pos + 1;
         }
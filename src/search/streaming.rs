@@ -1,5 +1,5 @@
 //! Streaming search implementation for memory-efficient processing
-use crate::processor::SearchMatch;
+use crate::processor::{trim_line_ending, SearchMatch};
 use crate::search::algorithms::SearchAlgorithmTrait;
 use std::collections::VecDeque;
 use std::fs::File;
@@ -66,6 +66,7 @@ impl StreamingSearch {
         let mut matches = Vec::new();
         let mut _line_buffer: VecDeque<String> = VecDeque::new();
         let mut line_number = 0;
+        let mut line_start = 0;
         let mut context_before = VecDeque::new();
 
         // Read file in chunks
@@ -79,8 +80,8 @@ impl StreamingSearch {
                     let chunk = String::from_utf8_lossy(&buffer[..n]);
                     let full_text = remaining + &chunk;
 
-                    // Process complete lines
-                    let lines: Vec<&str> = full_text.lines().collect();
+                    // Process complete lines, keeping their endings to count bytes
+                    let lines: Vec<&str> = full_text.split_inclusive('\n').collect();
                     let last_line_incomplete = !full_text.ends_with('\n');
 
                     let lines_to_process = if last_line_incomplete {
@@ -91,7 +92,7 @@ impl StreamingSearch {
 
                     for i in 0..lines_to_process {
                         line_number += 1;
-                        let line = lines[i];
+                        let line = trim_line_ending(lines[i]);
 
                         // Add to context buffer
                         context_before.push_back((line_number, line.to_string()));
@@ -114,8 +115,13 @@ impl StreamingSearch {
                                 matched_text: pattern.to_string(),
                                 column_start: match_pos,
                                 column_end: match_pos + pattern.len(),
+                                byte_offset: line_start + match_pos,
+                                distance: None,
+                                binary: false,
+                                excerpt: None,
                             });
                         }
+                        line_start += lines[i].len();
                     }
 
                     // Keep incomplete line for next iteration
@@ -146,6 +152,10 @@ impl StreamingSearch {
                     matched_text: pattern.to_string(),
                     column_start: match_pos,
                     column_end: match_pos + pattern.len(),
+                    byte_offset: line_start + match_pos,
+                    distance: None,
+                    binary: false,
+                    excerpt: None,
                 });
             }
         }
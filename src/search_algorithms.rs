@@ -1,3 +1,4 @@
+use crate::error::{Result as RfgrepResult, RfgrepError};
 use std::collections::HashMap;
 
 /// SIMD-optimized string search using CPU-specific intrinsics (AVX2/SSE4.2)
@@ -58,6 +59,9 @@ impl SimdSearch {
                     matched_text,
                     column_start,
                     column_end,
+                    byte_offset: match_pos,
+                    distance: None,
+                    excerpt: None,
                 });
             }
         }
@@ -123,12 +127,43 @@ impl BoyerMoore {
     }
 
     /// Build the good suffix table for Boyer-Moore algorithm
+    ///
+    /// Uses the strong good-suffix rule: `table[j]` is the shift to apply when a
+    /// mismatch occurs at pattern index `j - 1` (so the suffix `pattern[j..]`
+    /// matched), and `table[0]` is the shift that would find overlapping
+    /// matches (unused, since matches don't overlap). The table is derived
+    /// from the border array of each pattern suffix.
     fn build_good_suffix_table(pattern: &[u8]) -> Vec<usize> {
         let pattern_len = pattern.len();
-        let mut table = vec![1; pattern_len];
+        let mut table = vec![0; pattern_len + 1];
+        let mut border = vec![0; pattern_len + 1];
+
+        // Case 1: the matched suffix occurs elsewhere in the pattern, preceded
+        // by a different character than the one that mismatched
+        let mut i = pattern_len;
+        let mut j = pattern_len + 1;
+        border[i] = j;
+        while i > 0 {
+            while j <= pattern_len && pattern[i - 1] != pattern[j - 1] {
+                if table[j] == 0 {
+                    table[j] = j - i;
+                }
+                j = border[j];
+            }
+            i -= 1;
+            j -= 1;
+            border[i] = j;
+        }
 
-        if pattern_len > 1 {
-            table[pattern_len - 2] = pattern_len;
+        // Case 2: only a prefix of the pattern matches a part of the suffix
+        let mut j = border[0];
+        for (i, shift) in table.iter_mut().enumerate() {
+            if *shift == 0 {
+                *shift = j;
+            }
+            if i == j {
+                j = border[j];
+            }
         }
 
         table
@@ -136,44 +171,50 @@ impl BoyerMoore {
 
     /// Search for the pattern in the given text
     pub fn search(&self, text: &str, _pattern: &str) -> Vec<usize> {
-        let text_bytes = text.as_bytes();
+        self.search_bytes(text.as_bytes()).0
+    }
+
+    /// Core search loop; also returns the number of byte comparisons performed
+    fn search_bytes(&self, text_bytes: &[u8]) -> (Vec<usize>, usize) {
         let pattern_len = self.pattern.len();
         let text_len = text_bytes.len();
         let mut matches = Vec::new();
+        let mut comparisons = 0;
 
         if pattern_len == 0 || text_len < pattern_len {
-            return matches;
+            return (matches, comparisons);
         }
 
-        let mut i = pattern_len - 1;
-        while i < text_len {
-            let mut j = pattern_len - 1;
-            let mut k = i;
-
-            while j > 0 && text_bytes[k] == self.pattern[j] {
-                k -= 1;
+        let mut s = 0;
+        while s <= text_len - pattern_len {
+            // Compare right-to-left; `j` is one past the next index to compare
+            let mut j = pattern_len;
+            while j > 0 {
+                comparisons += 1;
+                if self.pattern[j - 1] != text_bytes[s + j - 1] {
+                    break;
+                }
                 j -= 1;
             }
 
-            if j == 0 && text_bytes[k] == self.pattern[0] {
-                matches.push(k);
-            }
-
-            let bad_char_shift = self
-                .bad_char_table
-                .get(&text_bytes[i])
-                .unwrap_or(&pattern_len);
-            let good_suffix_shift = if j < pattern_len - 1 {
-                self.good_suffix_table[j + 1]
+            if j == 0 {
+                matches.push(s);
+                // Matches don't overlap; resume after this one
+                s += pattern_len;
             } else {
-                1
-            };
-
-            let shift = bad_char_shift.max(&good_suffix_shift);
-            i += shift;
+                let mismatch = j - 1;
+                let bad_char_shift = match self.bad_char_table.get(&text_bytes[s + mismatch]) {
+                    Some(&from_end) => {
+                        let last = pattern_len - 1 - from_end;
+                        mismatch.saturating_sub(last).max(1)
+                    }
+                    None => mismatch + 1,
+                };
+                s += bad_char_shift.max(self.good_suffix_table[j]);
+            }
         }
 
-        matches
+        (matches, comparisons)
     }
 
     /// Search for all occurrences with context
@@ -214,6 +255,9 @@ impl BoyerMoore {
                     matched_text,
                     column_start,
                     column_end,
+                    byte_offset: match_pos,
+                    distance: None,
+                    excerpt: None,
                 });
             }
         }
@@ -256,6 +300,13 @@ pub struct SearchMatch {
     pub matched_text: String,
     pub column_start: usize,
     pub column_end: usize,
+    /// Where the match starts, in bytes from the start of the searched text
+    pub byte_offset: usize,
+    /// Edit distance to the pattern; `None` for exact algorithms
+    pub distance: Option<usize>,
+    /// Set when `line` is only part of an over-long line: how many bytes
+    /// into the line it starts
+    pub excerpt: Option<usize>,
 }
 
 /// Search algorithm types
@@ -266,27 +317,55 @@ pub enum SearchAlgorithm {
     BoyerMoore,
     Regex,
     Simple,
+    AhoCorasick, // Multi-pattern literal search
+    Fuzzy,       // Approximate search within an edit distance
 }
 
 /// Search algorithm factory
 pub struct SearchAlgorithmFactory;
 
 impl SearchAlgorithmFactory {
-    pub fn create(algorithm: SearchAlgorithm, pattern: &str) -> Box<dyn SearchAlgorithmTrait> {
-        match algorithm {
+    pub fn create(
+        algorithm: SearchAlgorithm,
+        pattern: &str,
+    ) -> RfgrepResult<Box<dyn SearchAlgorithmTrait>> {
+        Ok(match algorithm {
             SearchAlgorithm::Simd => Box::new(SimdSearch::new(pattern)),
             SearchAlgorithm::BoyerMoore => Box::new(BoyerMoore::new(pattern)),
             SearchAlgorithm::Regex => Box::new(RegexSearch::new(pattern)),
-            SearchAlgorithm::Simple => Box::new(SimpleSearch::new(pattern)),
-        }
+            SearchAlgorithm::Simple => Box::new(SimpleSearch::new_case_sensitive(pattern)),
+            SearchAlgorithm::AhoCorasick => {
+                Box::new(AhoCorasickSearch::new(&[pattern.to_string()])?)
+            }
+            SearchAlgorithm::Fuzzy => Box::new(FuzzySearch::new(pattern)),
+        })
+    }
+
+    /// Create a multi-pattern searcher that matches any of `patterns` in a single pass
+    pub fn create_multi(patterns: &[String]) -> RfgrepResult<Box<dyn SearchAlgorithmTrait>> {
+        Ok(Box::new(AhoCorasickSearch::new(patterns)?))
+    }
+
+    pub fn create_multi_with_case_sensitivity(
+        patterns: &[String],
+        case_sensitive: bool,
+    ) -> RfgrepResult<Box<dyn SearchAlgorithmTrait>> {
+        Ok(Box::new(AhoCorasickSearch::with_case_sensitivity(
+            patterns,
+            case_sensitive,
+        )?))
     }
 
     pub fn create_with_case_sensitivity(
         algorithm: SearchAlgorithm,
         pattern: &str,
         case_sensitive: bool,
-    ) -> Box<dyn SearchAlgorithmTrait> {
-        match algorithm {
+    ) -> RfgrepResult<Box<dyn SearchAlgorithmTrait>> {
+        Ok(match algorithm {
+            // The byte-oriented searchers can't fold case, so fall back to SimpleSearch
+            SearchAlgorithm::Simd | SearchAlgorithm::BoyerMoore if !case_sensitive => {
+                Box::new(SimpleSearch::new(pattern))
+            }
             SearchAlgorithm::Simd => Box::new(SimdSearch::new(pattern)),
             SearchAlgorithm::BoyerMoore => Box::new(BoyerMoore::new(pattern)),
             SearchAlgorithm::Regex => Box::new(RegexSearch::new(pattern)),
@@ -297,14 +376,53 @@ impl SearchAlgorithmFactory {
                     Box::new(SimpleSearch::new(pattern))
                 }
             }
-        }
+            SearchAlgorithm::AhoCorasick => Box::new(AhoCorasickSearch::with_case_sensitivity(
+                &[pattern.to_string()],
+                case_sensitive,
+            )?),
+            SearchAlgorithm::Fuzzy => Box::new(FuzzySearch::with_options(
+                pattern,
+                FuzzySearch::DEFAULT_MAX_DISTANCE,
+                case_sensitive,
+            )),
+        })
     }
 }
 
 /// Trait for search algorithms
+///
+/// Every implementation reports non-overlapping matches, scanning left to right
+/// like grep: after a match, the search resumes at its end. `aa` is found twice
+/// in `aaaaa`, at 0 and 2.
 pub trait SearchAlgorithmTrait: Send + Sync {
+    /// Start offsets of all non-overlapping matches in `text`
     #[allow(dead_code)]
     fn search(&self, text: &str, pattern: &str) -> Vec<usize>;
+
+    /// Byte ranges `(start, end)` of every match in `text`
+    ///
+    /// The default assumes each match spans exactly `pattern.len()` bytes;
+    /// algorithms whose matches can vary in length override this.
+    fn search_ranges(&self, text: &str, pattern: &str) -> Vec<(usize, usize)> {
+        self.search(text, pattern)
+            .into_iter()
+            .map(|start| (start, start + pattern.len()))
+            .collect()
+    }
+
+    /// Like `search_ranges`, with the edit distance of each match for
+    /// approximate algorithms (`None` for exact ones)
+    fn search_ranges_with_distance(
+        &self,
+        text: &str,
+        pattern: &str,
+    ) -> Vec<(usize, usize, Option<usize>)> {
+        self.search_ranges(text, pattern)
+            .into_iter()
+            .map(|(start, end)| (start, end, None))
+            .collect()
+    }
+
     fn search_with_context(
         &self,
         text: &str,
@@ -343,6 +461,13 @@ impl SearchAlgorithmTrait for SimdSearch {
         SimdSearch::search(self, text, "")
     }
 
+    fn search_ranges(&self, text: &str, _pattern: &str) -> Vec<(usize, usize)> {
+        SimdSearch::search(self, text, "")
+            .into_iter()
+            .map(|start| (start, start + self.pattern.len()))
+            .collect()
+    }
+
     fn search_with_context(
         &self,
         text: &str,
@@ -359,6 +484,13 @@ impl SearchAlgorithmTrait for BoyerMoore {
         self.search(text, pattern)
     }
 
+    fn search_ranges(&self, text: &str, pattern: &str) -> Vec<(usize, usize)> {
+        self.search(text, pattern)
+            .into_iter()
+            .map(|start| (start, start + self.pattern.len()))
+            .collect()
+    }
+
     fn search_with_context(
         &self,
         text: &str,
@@ -372,45 +504,97 @@ impl SearchAlgorithmTrait for BoyerMoore {
 /// Simple text search implementation
 pub struct SimpleSearch {
     pattern: String,
+    /// Lowercased copy of `pattern`, used when matching case-insensitively
+    folded_pattern: String,
     case_sensitive: bool,
 }
 
 impl SimpleSearch {
+    /// Create a case-insensitive searcher
     pub fn new(pattern: &str) -> Self {
         Self {
             pattern: pattern.to_string(),
-            case_sensitive: true,
+            folded_pattern: pattern.to_lowercase(),
+            case_sensitive: false,
         }
     }
 
     pub fn new_case_sensitive(pattern: &str) -> Self {
         Self {
             pattern: pattern.to_string(),
+            folded_pattern: pattern.to_string(),
             case_sensitive: true,
         }
     }
 }
 
+/// Lowercase `text`, returning the folded string together with a table mapping
+/// each byte offset of the folded string back to the byte offset of the
+/// originating char in `text` (lowercasing may change byte lengths).
+fn fold_case_with_offsets(text: &str) -> (String, Vec<usize>) {
+    let mut folded = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+
+    for (orig_idx, ch) in text.char_indices() {
+        for lower in ch.to_lowercase() {
+            folded.push(lower);
+            offsets.resize(folded.len(), orig_idx);
+        }
+    }
+    offsets.push(text.len());
+
+    (folded, offsets)
+}
+
 impl SimpleSearch {
     pub fn search(&self, text: &str, _pattern: &str) -> Vec<usize> {
+        self.search_ranges(text)
+            .into_iter()
+            .map(|(start, _)| start)
+            .collect()
+    }
+
+    /// Byte ranges of all matches in `text`; case folding can make a match
+    /// span a different number of bytes than the pattern
+    pub fn search_ranges(&self, text: &str) -> Vec<(usize, usize)> {
         let mut matches = Vec::new();
         let mut pos = 0;
 
-        let search_text = if self.case_sensitive {
-            text.to_string()
+        if self.folded_pattern.is_empty() {
+            return matches;
+        }
+
+        let (search_text, offsets) = if self.case_sensitive {
+            (std::borrow::Cow::Borrowed(text), None)
+        } else if text.is_ascii() {
+            // ASCII folding preserves byte offsets, so no remapping is needed
+            (std::borrow::Cow::Owned(text.to_ascii_lowercase()), None)
         } else {
-            text.to_lowercase()
+            let (folded, offsets) = fold_case_with_offsets(text);
+            (std::borrow::Cow::Owned(folded), Some(offsets))
         };
 
-        while let Some(found_pos) = search_text[pos..].find(&self.pattern) {
-            matches.push(pos + found_pos);
-            pos += found_pos + 1;
+        while let Some(found_pos) = search_text[pos..].find(&self.folded_pattern) {
+            let match_pos = pos + found_pos;
+            let match_end = match_pos + self.folded_pattern.len();
+            matches.push(match offsets.as_ref() {
+                Some(o) => (o[match_pos], o[match_end]),
+                None => (match_pos, match_end),
+            });
+
+            // Matches don't overlap; resume after this one
+            pos = match_end;
 
             if pos >= search_text.len() {
                 break;
             }
         }
 
+        // A multi-char lowercase expansion can map several hits onto one original char
+        if offsets.is_some() {
+            matches.dedup_by_key(|(start, _)| *start);
+        }
+
         matches
     }
 
@@ -451,6 +635,9 @@ impl SimpleSearch {
                     matched_text,
                     column_start,
                     column_end,
+                    byte_offset: match_pos,
+                    distance: None,
+                    excerpt: None,
                 });
             }
         }
@@ -464,6 +651,10 @@ impl SearchAlgorithmTrait for SimpleSearch {
         self.search(text, pattern)
     }
 
+    fn search_ranges(&self, text: &str, _pattern: &str) -> Vec<(usize, usize)> {
+        SimpleSearch::search_ranges(self, text)
+    }
+
     fn search_with_context(
         &self,
         text: &str,
@@ -500,37 +691,355 @@ impl RegexSearch {
         _pattern: &str,
         context_lines: usize,
     ) -> Vec<SearchMatch> {
-        let matches = self.search(text, "");
+        let lines: Vec<&str> = text.lines().collect();
+
+        // Take each span from the match itself; re-running the regex on the rest
+        // of the text loses the context that anchors like `^` and `\b` look at
+        self.regex
+            .find_iter(text)
+            .filter_map(|m| self.match_with_context(text, &lines, m, context_lines))
+            .collect()
+    }
+
+    /// Like `search_with_context`, but with each match's capture groups
+    pub fn captures_with_context<'t>(
+        &self,
+        text: &'t str,
+        context_lines: usize,
+    ) -> Vec<(SearchMatch, regex::Captures<'t>)> {
+        let lines: Vec<&str> = text.lines().collect();
+        self.regex
+            .captures_iter(text)
+            .filter_map(|caps| {
+                let m = caps.get(0)?;
+                let found = self.match_with_context(text, &lines, m, context_lines)?;
+                Some((found, caps))
+            })
+            .collect()
+    }
+
+    /// Fill `template` with the groups of the match starting at byte
+    /// `column_start` of `line`: `$1` or `${1}` by number, `${name}` by name,
+    /// and `$$` for a literal `$`
+    pub fn expand_match(&self, line: &str, column_start: usize, template: &str) -> Option<String> {
+        let (_, caps) = self
+            .captures_with_context(line, 0)
+            .into_iter()
+            .find(|(found, _)| found.column_start == column_start)?;
+        let mut expanded = String::new();
+        caps.expand(template, &mut expanded);
+        Some(expanded)
+    }
+
+    fn match_with_context(
+        &self,
+        text: &str,
+        lines: &[&str],
+        m: regex::Match,
+        context_lines: usize,
+    ) -> Option<SearchMatch> {
+        let line_start = text[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+        let line_index = text[..line_start].matches('\n').count();
+        let line = lines.get(line_index)?;
+
+        Some(SearchMatch {
+            line_number: line_index + 1,
+            line: line.to_string(),
+            context_before: self.get_context_before(lines, line_index, context_lines),
+            context_after: self.get_context_after(lines, line_index, context_lines),
+            matched_text: m.as_str().to_string(),
+            column_start: m.start() - line_start,
+            column_end: m.end() - line_start,
+            byte_offset: m.start(),
+            distance: None,
+            excerpt: None,
+        })
+    }
+}
+
+impl SearchAlgorithmTrait for RegexSearch {
+    fn search(&self, text: &str, pattern: &str) -> Vec<usize> {
+        self.search(text, pattern)
+    }
+
+    fn search_ranges(&self, text: &str, _pattern: &str) -> Vec<(usize, usize)> {
+        self.regex
+            .find_iter(text)
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+
+    fn search_with_context(
+        &self,
+        text: &str,
+        pattern: &str,
+        context_lines: usize,
+    ) -> Vec<SearchMatch> {
+        self.search_with_context(text, pattern, context_lines)
+    }
+}
+
+/// Aho-Corasick multi-pattern search over a single automaton
+///
+/// Matches are reported leftmost-longest and non-overlapping, so when one
+/// pattern is a prefix of another (e.g. `ERR` and `ERROR`) the longer one wins.
+pub struct AhoCorasickSearch {
+    patterns: Vec<String>,
+    automaton: aho_corasick::AhoCorasick,
+}
+
+impl AhoCorasickSearch {
+    pub fn new(patterns: &[String]) -> RfgrepResult<Self> {
+        Self::with_case_sensitivity(patterns, true)
+    }
+
+    /// Fails when the pattern set is too large for the automaton
+    pub fn with_case_sensitivity(patterns: &[String], case_sensitive: bool) -> RfgrepResult<Self> {
+        // An empty pattern would match at every offset, so drop it up front
+        let patterns: Vec<String> = patterns.iter().filter(|p| !p.is_empty()).cloned().collect();
+        let automaton = aho_corasick::AhoCorasick::builder()
+            .match_kind(aho_corasick::MatchKind::LeftmostLongest)
+            .ascii_case_insensitive(!case_sensitive)
+            .build(&patterns)
+            .map_err(|e| RfgrepError::Other(format!("Aho-Corasick error: {e}")))?;
+
+        Ok(Self {
+            patterns,
+            automaton,
+        })
+    }
+
+    /// Patterns compiled into the automaton
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn search(&self, text: &str, _pattern: &str) -> Vec<usize> {
+        self.automaton.find_iter(text).map(|m| m.start()).collect()
+    }
+
+    pub fn search_with_context(
+        &self,
+        text: &str,
+        _pattern: &str,
+        context_lines: usize,
+    ) -> Vec<SearchMatch> {
         let lines: Vec<&str> = text.lines().collect();
         let mut results = Vec::new();
 
-        for &match_pos in &matches {
-            let pre_lines = text[..match_pos].lines().count();
-            let line_number = pre_lines.max(1);
-            let line_index = line_number - 1;
+        for m in self.automaton.find_iter(text) {
+            let match_pos = m.start();
+            let line_index = memchr::memchr_iter(b'\n', &text.as_bytes()[..match_pos]).count();
 
             if line_index < lines.len() {
                 let line = lines[line_index];
                 let context_before = self.get_context_before(&lines, line_index, context_lines);
                 let context_after = self.get_context_after(&lines, line_index, context_lines);
 
-                let matched_text = self
-                    .regex
-                    .find(&text[match_pos..])
-                    .map(|m| m.as_str().to_string())
-                    .unwrap_or_default();
+                let line_start = text[..match_pos].rfind('\n').map_or(0, |i| i + 1);
+                let column_start = match_pos - line_start;
 
-                let matched_len = matched_text.len();
+                results.push(SearchMatch {
+                    line_number: line_index + 1,
+                    line: line.to_string(),
+                    context_before,
+                    context_after,
+                    matched_text: self.patterns[m.pattern().as_usize()].clone(),
+                    column_start,
+                    column_end: column_start + m.len(),
+                    byte_offset: match_pos,
+                    distance: None,
+                    excerpt: None,
+                });
+            }
+        }
+
+        results
+    }
+}
+
+impl SearchAlgorithmTrait for AhoCorasickSearch {
+    fn search(&self, text: &str, pattern: &str) -> Vec<usize> {
+        self.search(text, pattern)
+    }
+
+    fn search_ranges(&self, text: &str, _pattern: &str) -> Vec<(usize, usize)> {
+        self.automaton
+            .find_iter(text)
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+
+    fn search_with_context(
+        &self,
+        text: &str,
+        pattern: &str,
+        context_lines: usize,
+    ) -> Vec<SearchMatch> {
+        self.search_with_context(text, pattern, context_lines)
+    }
+}
+
+/// Approximate search: finds substrings within a Levenshtein distance of the pattern
+///
+/// Uses Sellers' dynamic programming over the text with Ukkonen's cut-off, so
+/// only the band of pattern rows that can still be within `max_distance` is
+/// computed for each text character (roughly `O(n * k)` on typical input).
+/// Overlapping candidates are collapsed to the closest one.
+pub struct FuzzySearch {
+    pattern: Vec<char>,
+    max_distance: usize,
+    case_sensitive: bool,
+}
+
+/// A fuzzy match as byte offsets into the searched text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub start: usize,
+    pub end: usize,
+    pub distance: usize,
+}
+
+impl FuzzySearch {
+    pub const DEFAULT_MAX_DISTANCE: usize = 1;
+
+    pub fn new(pattern: &str) -> Self {
+        Self::with_options(pattern, Self::DEFAULT_MAX_DISTANCE, true)
+    }
+
+    pub fn with_options(pattern: &str, max_distance: usize, case_sensitive: bool) -> Self {
+        let pattern = if case_sensitive {
+            pattern.chars().collect()
+        } else {
+            pattern.chars().map(fold_char).collect()
+        };
+        Self {
+            pattern,
+            max_distance,
+            case_sensitive,
+        }
+    }
+
+    pub fn max_distance(&self) -> usize {
+        self.max_distance
+    }
+
+    /// Find the best non-overlapping approximate matches in `text`
+    pub fn find_matches(&self, text: &str) -> Vec<FuzzyMatch> {
+        let m = self.pattern.len();
+        let k = self.max_distance;
+        // A distance of the full pattern length would match everywhere
+        if m == 0 || k >= m {
+            return Vec::new();
+        }
+
+        // (cost, start byte offset) per pattern row; rows past the active band
+        // hold `k + 1`, which is all the cut-off needs to know about them
+        let mut column: Vec<(usize, usize)> = (0..=m).map(|i| (i.min(k + 1), 0)).collect();
+        let mut last_active = k;
+        let mut candidates: Vec<FuzzyMatch> = Vec::new();
+
+        for (byte_idx, original) in text.char_indices() {
+            let next_byte = byte_idx + original.len_utf8();
+            let ch = if self.case_sensitive {
+                original
+            } else {
+                fold_char(original)
+            };
+
+            let top = (last_active + 1).min(m);
+            // Row 0: an empty prefix of the pattern matches before any char
+            let mut diag = column[0];
+            column[0] = (0, next_byte);
+            for i in 1..=top {
+                let above = column[i];
+                let substitute = (diag.0 + usize::from(self.pattern[i - 1] != ch), diag.1);
+                let skip_pattern = (column[i - 1].0 + 1, column[i - 1].1);
+                let skip_text = (above.0 + 1, above.1);
+                let best = [substitute, skip_pattern, skip_text]
+                    .into_iter()
+                    .min_by_key(|&(cost, start)| (cost, std::cmp::Reverse(start)))
+                    .unwrap();
+                column[i] = (best.0.min(k + 1), best.1);
+                diag = above;
+            }
+
+            last_active = top;
+            while last_active > 0 && column[last_active].0 > k {
+                last_active -= 1;
+            }
+            for cell in column.iter_mut().skip(last_active + 1) {
+                cell.0 = k + 1;
+            }
+
+            if last_active == m {
+                let (distance, start) = column[m];
+                candidates.push(FuzzyMatch {
+                    start,
+                    end: next_byte,
+                    distance,
+                });
+            }
+        }
+
+        Self::select_best(candidates)
+    }
+
+    /// Collapse overlapping candidates to the one with the smallest distance
+    /// (ties go to the earliest match)
+    fn select_best(candidates: Vec<FuzzyMatch>) -> Vec<FuzzyMatch> {
+        let mut selected: Vec<FuzzyMatch> = Vec::new();
+        for candidate in candidates {
+            match selected.last_mut() {
+                Some(last) if candidate.start < last.end => {
+                    if candidate.distance < last.distance {
+                        *last = candidate;
+                    }
+                }
+                _ => selected.push(candidate),
+            }
+        }
+        selected
+    }
+
+    pub fn search(&self, text: &str, _pattern: &str) -> Vec<usize> {
+        self.find_matches(text)
+            .into_iter()
+            .map(|m| m.start)
+            .collect()
+    }
+
+    pub fn search_with_context(
+        &self,
+        text: &str,
+        _pattern: &str,
+        context_lines: usize,
+    ) -> Vec<SearchMatch> {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut results = Vec::new();
+
+        for m in self.find_matches(text) {
+            let line_index = memchr::memchr_iter(b'\n', &text.as_bytes()[..m.start]).count();
+
+            if line_index < lines.len() {
+                let line = lines[line_index];
+                let context_before = self.get_context_before(&lines, line_index, context_lines);
+                let context_after = self.get_context_after(&lines, line_index, context_lines);
+
+                let line_start = text[..m.start].rfind('\n').map_or(0, |i| i + 1);
+                let column_start = m.start - line_start;
 
                 results.push(SearchMatch {
-                    line_number,
+                    line_number: line_index + 1,
                     line: line.to_string(),
                     context_before,
                     context_after,
-                    matched_text,
-                    column_start: match_pos - text[..match_pos].rfind('\n').unwrap_or(0),
-                    column_end: match_pos - text[..match_pos].rfind('\n').unwrap_or(0)
-                        + matched_len,
+                    matched_text: text[m.start..m.end].to_string(),
+                    column_start,
+                    column_end: column_start + (m.end - m.start),
+                    byte_offset: m.start,
+                    distance: Some(m.distance),
+                    excerpt: None,
                 });
             }
         }
@@ -539,11 +1048,34 @@ impl RegexSearch {
     }
 }
 
-impl SearchAlgorithmTrait for RegexSearch {
+/// Single-char lowercase used for case-insensitive fuzzy comparison
+fn fold_char(ch: char) -> char {
+    ch.to_lowercase().next().unwrap_or(ch)
+}
+
+impl SearchAlgorithmTrait for FuzzySearch {
     fn search(&self, text: &str, pattern: &str) -> Vec<usize> {
         self.search(text, pattern)
     }
 
+    fn search_ranges(&self, text: &str, _pattern: &str) -> Vec<(usize, usize)> {
+        self.find_matches(text)
+            .into_iter()
+            .map(|m| (m.start, m.end))
+            .collect()
+    }
+
+    fn search_ranges_with_distance(
+        &self,
+        text: &str,
+        _pattern: &str,
+    ) -> Vec<(usize, usize, Option<usize>)> {
+        self.find_matches(text)
+            .into_iter()
+            .map(|m| (m.start, m.end, Some(m.distance)))
+            .collect()
+    }
+
     fn search_with_context(
         &self,
         text: &str,
@@ -553,3 +1085,227 @@ impl SearchAlgorithmTrait for RegexSearch {
         self.search_with_context(text, pattern, context_lines)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_good_suffix_table() {
+        // Classic textbook example: shifts for "ABBABAB"
+        assert_eq!(
+            BoyerMoore::build_good_suffix_table(b"ABBABAB"),
+            vec![5, 5, 5, 5, 2, 5, 4, 1]
+        );
+        assert_eq!(
+            BoyerMoore::build_good_suffix_table(b"aaaa"),
+            vec![1, 1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_boyer_moore_matches_simple_search() {
+        let cases = [
+            ("abab", "abababab ab abab"),
+            ("aa", "aaaaa"),
+            ("baaaaa", "aaaaaabaaaaaabaaaaa"),
+            ("needle", "haystack without it"),
+            ("x", "x"),
+        ];
+        for (pattern, text) in cases {
+            let bm = BoyerMoore::new(pattern);
+            let simple = SimpleSearch::new_case_sensitive(pattern);
+            assert_eq!(
+                bm.search(text, pattern),
+                simple.search(text, pattern),
+                "pattern {pattern:?} in {text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_boyer_moore_good_suffix_limits_comparisons() {
+        // With only the bad-character rule every alignment re-scans the whole
+        // run of 'a's before shifting by one; the good-suffix rule skips ahead.
+        let text = "a".repeat(10_000);
+        let bm = BoyerMoore::new("baaaaa");
+        let (matches, comparisons) = bm.search_bytes(text.as_bytes());
+        assert!(matches.is_empty());
+        assert!(
+            comparisons <= 2 * text.len(),
+            "too many comparisons: {comparisons}"
+        );
+    }
+
+    #[test]
+    fn test_simple_search_case_insensitive() {
+        let search = SimpleSearch::new("Error");
+        assert_eq!(search.search("error line", ""), vec![0]);
+        assert_eq!(search.search("an ERROR and an eRrOr", ""), vec![3, 16]);
+
+        let search = SimpleSearch::new_case_sensitive("Error");
+        assert!(search.search("error line", "").is_empty());
+        assert_eq!(search.search("error Error", ""), vec![6]);
+    }
+
+    #[test]
+    fn test_simple_search_case_insensitive_non_ascii_offsets() {
+        // 'İ' (2 bytes) lowercases to "i\u{307}" (3 bytes), shifting later offsets
+        let text = "İİ Straße STRASSE straße";
+        let search = SimpleSearch::new("STRAßE");
+        let matches = search.search(text, "");
+
+        assert_eq!(matches.len(), 2);
+        for &pos in &matches {
+            assert!(text.is_char_boundary(pos));
+            assert!(text[pos..].to_lowercase().starts_with("straße"));
+        }
+        assert_eq!(&text[matches[0]..matches[0] + "Straße".len()], "Straße");
+        assert_eq!(&text[matches[1]..], "straße");
+    }
+
+    #[test]
+    fn test_aho_corasick_finds_any_pattern() {
+        let search = AhoCorasickSearch::new(&patterns(&["ERROR", "WARN", "FATAL"])).unwrap();
+        let text = "INFO ok\nWARN disk\nERROR boom\nFATAL crash";
+        let matches = search.search_with_context(text, "", 0);
+
+        let found: Vec<(usize, &str)> = matches
+            .iter()
+            .map(|m| (m.line_number, m.matched_text.as_str()))
+            .collect();
+        assert_eq!(found, vec![(2, "WARN"), (3, "ERROR"), (4, "FATAL")]);
+        assert_eq!(matches[1].column_start, 0);
+        assert_eq!(matches[1].column_end, 5);
+    }
+
+    #[test]
+    fn test_aho_corasick_empty_pattern_list() {
+        let search = AhoCorasickSearch::new(&[]).unwrap();
+        assert!(search.search("anything at all", "").is_empty());
+
+        let search = AhoCorasickSearch::new(&patterns(&[""])).unwrap();
+        assert!(search.search("anything at all", "").is_empty());
+    }
+
+    #[test]
+    fn test_aho_corasick_substring_patterns_prefer_longest() {
+        let search = AhoCorasickSearch::new(&patterns(&["ERR", "ERROR"])).unwrap();
+        let matches = search.search_with_context("an ERROR and an ERR", "", 0);
+
+        let found: Vec<&str> = matches.iter().map(|m| m.matched_text.as_str()).collect();
+        assert_eq!(found, vec!["ERROR", "ERR"]);
+        assert_eq!(search.search("an ERROR and an ERR", ""), vec![3, 16]);
+    }
+
+    #[test]
+    fn test_aho_corasick_overlapping_literals() {
+        // "abc" and "bcd" overlap in "abcd"; only the leftmost is reported
+        let search = AhoCorasickSearch::new(&patterns(&["bcd", "abc"])).unwrap();
+        assert_eq!(search.search("abcd abcd", ""), vec![0, 5]);
+    }
+
+    #[test]
+    fn test_aho_corasick_case_insensitive() {
+        let search =
+            AhoCorasickSearch::with_case_sensitivity(&patterns(&["error"]), false).unwrap();
+        assert_eq!(search.search("Error ERROR error", ""), vec![0, 6, 12]);
+    }
+
+    #[test]
+    fn test_fuzzy_finds_typos_within_distance() {
+        let fuzzy = FuzzySearch::with_options("receive", 1, true);
+        // "recieve" is a transposition, which costs two edits
+        let text = "we recieve data, then receive more, recive less, and rcv nothing";
+
+        let found: Vec<(&str, usize)> = fuzzy
+            .find_matches(text)
+            .iter()
+            .map(|m| (&text[m.start..m.end], m.distance))
+            .collect();
+        assert_eq!(found, vec![("receive", 0), ("recive", 1)]);
+    }
+
+    #[test]
+    fn test_fuzzy_case_and_multibyte_offsets() {
+        let fuzzy = FuzzySearch::with_options("STRASSE", 1, false);
+        let text = "日本 strasse straße";
+        let found: Vec<(&str, usize)> = fuzzy
+            .find_matches(text)
+            .iter()
+            .map(|m| (&text[m.start..m.end], m.distance))
+            .collect();
+        assert_eq!(found, vec![("strasse", 0)]);
+    }
+
+    #[test]
+    fn test_fuzzy_distance_not_below_pattern_length() {
+        // A threshold covering the whole pattern would match everywhere
+        assert!(FuzzySearch::with_options("ab", 2, true)
+            .find_matches("xyz")
+            .is_empty());
+        assert!(FuzzySearch::new("").find_matches("xyz").is_empty());
+    }
+
+    #[test]
+    fn test_regex_reports_variable_length_spans() {
+        let regex = RegexSearch::new(r"\w+");
+        let algo: &dyn SearchAlgorithmTrait = &regex;
+        assert_eq!(
+            algo.search_ranges("a bb  cccc", r"\w+"),
+            vec![(0, 1), (2, 4), (6, 10)]
+        );
+
+        let matches = regex.search_with_context("one\ntwo three", r"\w+", 0);
+        let spans: Vec<_> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m.line_number,
+                    m.column_start,
+                    m.column_end,
+                    m.matched_text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![(1, 0, 3, "one"), (2, 0, 3, "two"), (2, 4, 9, "three")]
+        );
+    }
+
+    #[test]
+    fn test_regex_captures_fill_templates() {
+        let regex = RegexSearch::new(r"(?P<key>\w+)=(\d+)");
+        let captured = regex.captures_with_context("x\na=1 bb=22\n", 0);
+        let found: Vec<_> = captured
+            .iter()
+            .map(|(m, caps)| (m.line_number, m.column_start, caps[2].to_string()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(2, 0, "1".to_string()), (2, 4, "22".to_string())]
+        );
+
+        let line = "a=1 bb=22";
+        assert_eq!(
+            regex.expand_match(line, 4, "${key} -> $2 ($$)").as_deref(),
+            Some("bb -> 22 ($)")
+        );
+        assert_eq!(
+            regex.expand_match(line, 0, "${1}${2}").as_deref(),
+            Some("a1")
+        );
+        assert_eq!(regex.expand_match(line, 1, "$1"), None);
+    }
+
+    #[test]
+    fn test_factory_create_multi() {
+        let search = SearchAlgorithmFactory::create_multi(&patterns(&["foo", "bar"])).unwrap();
+        assert_eq!(search.search("foo bar baz", ""), vec![0, 4]);
+    }
+}
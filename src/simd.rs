@@ -1,11 +1,14 @@
 #![allow(dead_code)]
 
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use std::arch::x86_64::*;
 
 /// Interface for SIMD search backends
 pub trait SimdBackend: Send + Sync {
-    /// Search for pattern in text, returning all start indices
+    /// Search for pattern in text, returning all start indices in order,
+    /// including overlapping ones
     fn search(&self, text: &[u8]) -> Vec<usize>;
 }
 
@@ -166,10 +169,7 @@ impl SimdBackend for Sse42Backend {
     }
 }
 
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    target_feature = "avx512f"
-))]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 /// AVX-512 implementation using 512-bit registers
 pub struct Avx512Backend {
     pattern: Vec<u8>,
@@ -177,10 +177,7 @@ pub struct Avx512Backend {
     last_byte: u8,
 }
 
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    target_feature = "avx512f"
-))]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 impl Avx512Backend {
     pub fn new(pattern: &[u8]) -> Self {
         Self {
@@ -189,14 +186,79 @@ impl Avx512Backend {
             last_byte: *pattern.last().unwrap(),
         }
     }
+
+    /// Whether this CPU can run the backend; byte compares need AVX-512BW
+    pub fn is_supported() -> bool {
+        is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx512bw")
+    }
+
+    #[target_feature(enable = "avx512f,avx512bw")]
+    unsafe fn search_avx512(&self, text: &[u8]) -> Vec<usize> {
+        let mut matches = Vec::new();
+        let pat_len = self.pattern.len();
+        let text_len = text.len();
+
+        if text_len < pat_len {
+            return matches;
+        }
+
+        let first = _mm512_set1_epi8(self.first_byte as i8);
+        let mut i = 0;
+        // Process 64 bytes at a time
+        while i + 64 <= text_len {
+            let chunk = _mm512_loadu_si512(text.as_ptr().add(i) as *const __m512i);
+            let mut mask = _mm512_cmpeq_epi8_mask(chunk, first);
+            while mask != 0 {
+                let potential_match_idx = i + mask.trailing_zeros() as usize;
+                if potential_match_idx + pat_len <= text_len
+                    && text[potential_match_idx + pat_len - 1] == self.last_byte
+                    && &text[potential_match_idx..potential_match_idx + pat_len]
+                        == self.pattern.as_slice()
+                {
+                    matches.push(potential_match_idx);
+                }
+                mask &= mask - 1;
+            }
+            i += 64;
+        }
+
+        // Fallback for remaining bytes
+        while i <= text_len.saturating_sub(pat_len) {
+            if text[i] == self.first_byte && &text[i..i + pat_len] == self.pattern.as_slice() {
+                matches.push(i);
+            }
+            i += 1;
+        }
+        matches
+    }
 }
 
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    target_feature = "avx512f"
-))]
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 impl SimdBackend for Avx512Backend {
     fn search(&self, text: &[u8]) -> Vec<usize> {
+        // Only constructed after AVX-512 was detected at runtime
+        unsafe { self.search_avx512(text) }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+/// NEON implementation using 128-bit registers
+pub struct NeonBackend {
+    pattern: Vec<u8>,
+    first_byte: u8,
+}
+
+#[cfg(target_arch = "aarch64")]
+impl NeonBackend {
+    pub fn new(pattern: &[u8]) -> Self {
+        Self {
+            pattern: pattern.to_vec(),
+            first_byte: pattern[0],
+        }
+    }
+
+    #[target_feature(enable = "neon")]
+    unsafe fn search_neon(&self, text: &[u8]) -> Vec<usize> {
         let mut matches = Vec::new();
         let pat_len = self.pattern.len();
         let text_len = text.len();
@@ -205,45 +267,51 @@ impl SimdBackend for Avx512Backend {
             return matches;
         }
 
-        unsafe {
-            let first = std::arch::x86_64::_mm512_set1_epi8(self.first_byte as i8);
-            let mut i = 0;
-            while i + 64 <= text_len {
-                let chunk = std::arch::x86_64::_mm512_loadu_si512(
-                    text.as_ptr().add(i) as *const std::arch::x86_64::__m512i
-                );
-                let eq_first = std::arch::x86_64::_mm512_cmpeq_epi8_mask(chunk, first);
-                if eq_first != 0 {
-                    for bit in 0..64 {
-                        if (eq_first & (1u64 << bit)) != 0 {
-                            let potential_match_idx = i + bit;
-                            if potential_match_idx + pat_len <= text_len {
-                                if text[potential_match_idx + pat_len - 1] == self.last_byte {
-                                    if &text[potential_match_idx..potential_match_idx + pat_len]
-                                        == self.pattern.as_slice()
-                                    {
-                                        matches.push(potential_match_idx);
-                                    }
-                                }
-                            }
-                        }
-                    }
+        let first = vdupq_n_u8(self.first_byte);
+
+        let mut i = 0;
+        // Process 16 bytes at a time
+        while i + 16 <= text_len {
+            let chunk = vld1q_u8(text.as_ptr().add(i));
+            let eq = vceqq_u8(chunk, first);
+            // NEON has no movemask; narrowing leaves 4 bits per byte in a u64
+            let narrowed = vshrn_n_u16::<4>(vreinterpretq_u16_u8(eq));
+            let mut mask = vget_lane_u64::<0>(vreinterpret_u64_u8(narrowed));
+
+            while mask != 0 {
+                let lane = mask.trailing_zeros() as usize / 4;
+                let potential_match_idx = i + lane;
+
+                if potential_match_idx + pat_len <= text_len
+                    && &text[potential_match_idx..potential_match_idx + pat_len]
+                        == self.pattern.as_slice()
+                {
+                    matches.push(potential_match_idx);
                 }
-                i += 64;
+                mask &= !(0xf << (lane * 4));
             }
-            while i <= text_len.saturating_sub(pat_len) {
-                if text[i] == self.first_byte {
-                    if &text[i..i + pat_len] == self.pattern.as_slice() {
-                        matches.push(i);
-                    }
-                }
-                i += 1;
+            i += 16;
+        }
+
+        // Fallback
+        while i <= text_len.saturating_sub(pat_len) {
+            if text[i] == self.first_byte && &text[i..i + pat_len] == self.pattern.as_slice() {
+                matches.push(i);
             }
+            i += 1;
         }
         matches
     }
 }
 
+#[cfg(target_arch = "aarch64")]
+impl SimdBackend for NeonBackend {
+    fn search(&self, text: &[u8]) -> Vec<usize> {
+        // Only constructed after NEON was detected at runtime
+        unsafe { self.search_neon(text) }
+    }
+}
+
 /// Fallback backend using standard library
 pub struct FallbackBackend {
     pattern: Vec<u8>,
@@ -278,40 +346,122 @@ impl SimdBackend for FallbackBackend {
     }
 }
 
+/// Instruction set a `SimdSearchEngine` searches with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimdBackendKind {
+    Avx512,
+    Avx2,
+    Sse42,
+    Neon,
+    Fallback,
+}
+
+impl SimdBackendKind {
+    /// Short name used in reports: `avx512`, `avx2`, `sse4.2`, `neon` or `fallback`
+    pub fn name(self) -> &'static str {
+        match self {
+            SimdBackendKind::Avx512 => "avx512",
+            SimdBackendKind::Avx2 => "avx2",
+            SimdBackendKind::Sse42 => "sse4.2",
+            SimdBackendKind::Neon => "neon",
+            SimdBackendKind::Fallback => "fallback",
+        }
+    }
+}
+
+/// Backend `SimdSearchEngine` picks on this CPU for a non-empty pattern
+pub fn detected_backend() -> SimdBackendKind {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if Avx512Backend::is_supported() {
+            return SimdBackendKind::Avx512;
+        } else if is_x86_feature_detected!("avx2") {
+            return SimdBackendKind::Avx2;
+        } else if is_x86_feature_detected!("sse4.2") {
+            return SimdBackendKind::Sse42;
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return SimdBackendKind::Neon;
+        }
+    }
+    SimdBackendKind::Fallback
+}
+
 /// Main Engine that selects best backend
 pub struct SimdSearchEngine {
     backend: Box<dyn SimdBackend>,
+    pattern_len: usize,
 }
 
 impl SimdSearchEngine {
     pub fn new(pattern: &str) -> Self {
         let bytes = pattern.as_bytes();
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            #[cfg(target_feature = "avx512f")]
-            {
-                if is_x86_feature_detected!("avx512f") && !bytes.is_empty() {
-                    return Self {
-                        backend: Box::new(Avx512Backend::new(bytes)),
-                    };
-                }
-            }
-            if is_x86_feature_detected!("avx2") && !bytes.is_empty() {
-                return Self {
-                    backend: Box::new(Avx2Backend::new(bytes)),
-                };
-            } else if is_x86_feature_detected!("sse4.2") && !bytes.is_empty() {
-                return Self {
-                    backend: Box::new(Sse42Backend::new(bytes)),
-                };
-            }
-        }
+        let backend: Box<dyn SimdBackend> = match detected_backend() {
+            _ if bytes.is_empty() => Box::new(FallbackBackend::new(bytes)),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SimdBackendKind::Avx512 => Box::new(Avx512Backend::new(bytes)),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SimdBackendKind::Avx2 => Box::new(Avx2Backend::new(bytes)),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            SimdBackendKind::Sse42 => Box::new(Sse42Backend::new(bytes)),
+            #[cfg(target_arch = "aarch64")]
+            SimdBackendKind::Neon => Box::new(NeonBackend::new(bytes)),
+            _ => Box::new(FallbackBackend::new(bytes)),
+        };
         Self {
-            backend: Box::new(FallbackBackend::new(bytes)),
+            backend,
+            pattern_len: bytes.len(),
         }
     }
 
+    /// Start indices of the non-overlapping matches in `text`
     pub fn search(&self, text: &str) -> Vec<usize> {
-        self.backend.search(text.as_bytes())
+        let mut next_allowed = 0;
+        let mut matches = self.backend.search(text.as_bytes());
+        matches.retain(|&start| {
+            let keep = start >= next_allowed;
+            if keep {
+                next_allowed = start + self.pattern_len;
+            }
+            keep
+        });
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Byte strings dense in the pattern alphabet, so matches are common
+    fn text_and_pattern() -> impl Strategy<Value = (Vec<u8>, Vec<u8>)> {
+        let byte = prop_oneof![Just(b'a'), Just(b'b'), any::<u8>()];
+        (
+            proptest::collection::vec(byte, 0..300),
+            proptest::collection::vec(prop_oneof![Just(b'a'), Just(b'b')], 1..5),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn selected_backend_agrees_with_fallback((text, pattern) in text_and_pattern()) {
+            let pattern_str = String::from_utf8(pattern.clone()).unwrap();
+            let engine = SimdSearchEngine::new(&pattern_str);
+            let expected = FallbackBackend::new(&pattern).search(&text);
+            prop_assert_eq!(engine.backend.search(&text), expected);
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        #[test]
+        fn neon_agrees_with_fallback((text, pattern) in text_and_pattern()) {
+            prop_assume!(std::arch::is_aarch64_feature_detected!("neon"));
+            let neon = NeonBackend::new(&pattern).search(&text);
+            let fallback = FallbackBackend::new(&pattern).search(&text);
+            prop_assert_eq!(neon, fallback);
+        }
     }
 }
@@ -1,11 +1,14 @@
 //! Streaming search pipeline for efficient file processing
+use crate::cli::{BinaryMode, Encoding, LineRange};
 use crate::error::{Result as RfgrepResult, RfgrepError};
-use crate::processor::SearchMatch as ProcessorSearchMatch;
+use crate::memory::MemoryBudget;
+use crate::processor::{is_whole_word, is_word_char, SearchMatch as ProcessorSearchMatch};
 use crate::search_algorithms::{SearchAlgorithm, SearchAlgorithmTrait, SearchMatch};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
-use std::path::Path;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use tokio::task;
 
@@ -13,63 +16,293 @@ use tokio::task;
 #[derive(Debug, Clone)]
 pub struct StreamingConfig {
     pub algorithm: SearchAlgorithm,
-    pub context_lines: usize,
+    /// Full pattern list for multi-pattern searches; with more than one entry,
+    /// literal algorithms are replaced by a single Aho-Corasick automaton
+    pub patterns: Vec<String>,
+    /// Lines of context kept before each match (`-B`)
+    pub context_before: usize,
+    /// Lines of context kept after each match (`-A`)
+    pub context_after: usize,
     pub case_sensitive: bool,
+    /// Only accept hits that aren't touching word characters on either side (`-w`)
+    pub word_regexp: bool,
     pub invert_match: bool,
     pub max_matches: Option<usize>,
+    /// Stop searching a file after this many matches (`-m`)
+    pub max_count: Option<usize>,
+    /// Only lines in this window are searched or shown as context
+    pub line_range: Option<LineRange>,
+    /// How file bytes are decoded before being split into lines
+    pub encoding: Encoding,
+    /// Whether files that look binary are skipped, searched as text, or
+    /// reported with a single notice
+    pub binary_mode: BinaryMode,
+    /// Cap on the file bytes buffered or mapped by in-flight searches
+    pub max_memory_bytes: Option<u64>,
+    /// Nesting depth inside archives and decompressed size allowed for
+    /// archives and compressed files
+    pub archive_limits: crate::archive::ArchiveLimits,
     pub timeout_per_file: Option<u64>,
+    /// Maximum edit distance for the fuzzy algorithm
+    pub max_distance: usize,
+    /// Bytes read per window when a line is too long to read whole
     pub chunk_size: usize,
     pub buffer_size: usize,
+    /// Lines longer than this aren't buffered; the file is searched in
+    /// overlapping `chunk_size` windows instead
+    pub max_line_bytes: usize,
+    /// Plain files at least this large are searched through a memory map
+    /// rather than a buffered reader
+    pub mmap_threshold: u64,
+    /// Command whose output is searched instead of the files it applies to (`--pre`)
+    pub preprocessor: Option<crate::preprocess::Preprocessor>,
+    /// Log files that fail to search at debug level instead of printing
+    /// them (`--no-messages`)
+    pub no_messages: bool,
 }
 
 impl Default for StreamingConfig {
     fn default() -> Self {
         Self {
             algorithm: SearchAlgorithm::BoyerMoore,
-            context_lines: 2,
+            patterns: Vec::new(),
+            context_before: 2,
+            context_after: 2,
             case_sensitive: true,
+            word_regexp: false,
             invert_match: false,
             max_matches: None,
+            max_count: None,
+            line_range: None,
+            encoding: Encoding::Auto,
+            binary_mode: BinaryMode::Skip,
+            max_memory_bytes: None,
+            archive_limits: crate::archive::ArchiveLimits::default(),
             timeout_per_file: None,
+            max_distance: crate::search_algorithms::FuzzySearch::DEFAULT_MAX_DISTANCE,
             chunk_size: 8192,   // 8KB chunks
             buffer_size: 65536, // 64KB buffer
+            max_line_bytes: DEFAULT_MAX_LINE_BYTES,
+            mmap_threshold: crate::processor::get_adaptive_mmap_threshold(),
+            preprocessor: None,
+            no_messages: false,
         }
     }
 }
 
+/// Files finished so far, shared with whoever is reporting progress
+#[derive(Debug, Default)]
+pub struct SearchProgress {
+    files_done: AtomicUsize,
+    bytes_done: AtomicU64,
+    current_file: Mutex<Option<PathBuf>>,
+}
+
+impl SearchProgress {
+    /// Record that `path` has been searched
+    pub fn file_done(&self, path: &Path) {
+        self.files_done.fetch_add(1, Ordering::Relaxed);
+        let size = std::fs::metadata(path).map_or(0, |m| m.len());
+        self.bytes_done.fetch_add(size, Ordering::Relaxed);
+        if let Ok(mut current) = self.current_file.lock() {
+            *current = Some(path.to_path_buf());
+        }
+    }
+
+    /// Number of files searched so far
+    pub fn files_done(&self) -> usize {
+        self.files_done.load(Ordering::Relaxed)
+    }
+
+    /// Size on disk of the files searched so far
+    pub fn bytes_done(&self) -> u64 {
+        self.bytes_done.load(Ordering::Relaxed)
+    }
+
+    /// The most recently finished file
+    pub fn current_file(&self) -> Option<PathBuf> {
+        self.current_file
+            .lock()
+            .ok()
+            .and_then(|current| current.clone())
+    }
+}
+
+/// Whether `contains` finds a hit anywhere in `reader`, checking one buffer
+/// at a time with the last `overlap` bytes of the previous one kept in front
+/// so hits across a buffer boundary aren't missed
+fn scan_stream(
+    mut reader: impl BufRead,
+    overlap: usize,
+    contains: impl Fn(&[u8]) -> bool,
+) -> RfgrepResult<bool> {
+    let mut window = Vec::new();
+    loop {
+        let chunk = reader.fill_buf().map_err(RfgrepError::Io)?;
+        if chunk.is_empty() {
+            return Ok(false);
+        }
+        let keep = window.len().min(overlap);
+        window.drain(..window.len() - keep);
+        window.extend_from_slice(chunk);
+        let read = chunk.len();
+        reader.consume(read);
+        if contains(&window) {
+            return Ok(true);
+        }
+    }
+}
+
+/// Lines read between checks for cancellation within a file
+const CANCEL_CHECK_INTERVAL: usize = 4096;
+
+/// Default for [`StreamingConfig::max_line_bytes`]
+pub const DEFAULT_MAX_LINE_BYTES: usize = 16 * 1024 * 1024;
+
 /// Streaming search pipeline
 #[derive(Clone)]
 pub struct StreamingSearchPipeline {
     config: StreamingConfig,
+    progress: Option<Arc<SearchProgress>>,
+    memory: Option<Arc<MemoryBudget>>,
+    cancelled: Option<Arc<AtomicBool>>,
+    /// Set once `timeout_per_file` gave up on the search this copy is running
+    abandoned: Option<Arc<AtomicBool>>,
 }
 
 impl StreamingSearchPipeline {
     /// Fast-exit search: returns true if any match is found, exits early
     pub async fn search_file_fast_exit(&self, path: &Path, pattern: &str) -> RfgrepResult<bool> {
-        if crate::processor::is_binary(path) {
+        if self.preprocesses(path) {
+            return Ok(!self.search_file(path, pattern).await?.is_empty());
+        }
+        if self.config.binary_mode == BinaryMode::Skip && crate::processor::is_binary(path) {
             return Ok(false);
         }
+        // Inverted, approximate, regex and non-ASCII case-insensitive matches
+        // can't be found with a plain byte scan, and archive entries are
+        // searched one by one
+        let is_archive = matches!(
+            crate::compression::CompressionType::from_extension(path),
+            Some(
+                crate::compression::CompressionType::Zip | crate::compression::CompressionType::Tar
+            )
+        );
+        let needs_full_search = match self.config.algorithm {
+            _ if self.config.invert_match || self.config.line_range.is_some() => true,
+            _ if self.config.word_regexp || is_archive => true,
+            SearchAlgorithm::Fuzzy | SearchAlgorithm::Regex => true,
+            _ => {
+                let all_ascii =
+                    pattern.is_ascii() && self.config.patterns.iter().all(|p| p.is_ascii());
+                !self.config.case_sensitive && !all_ascii
+            }
+        };
+        if needs_full_search {
+            return Ok(!self.search_file(path, pattern).await?.is_empty());
+        }
         use memchr::memmem;
         use memmap2::Mmap;
+        let finder = memmem::Finder::new(pattern.as_bytes());
+        let patterns: Vec<&str> = if self.uses_multi_pattern() {
+            self.config.patterns.iter().map(String::as_str).collect()
+        } else {
+            vec![pattern]
+        };
+        let longest = patterns.iter().map(|p| p.len()).max().unwrap_or(0);
+        let multi = if self.uses_multi_pattern() || !self.config.case_sensitive {
+            Some(
+                aho_corasick::AhoCorasick::builder()
+                    .ascii_case_insensitive(!self.config.case_sensitive)
+                    .build(patterns.into_iter().filter(|p| !p.is_empty()))
+                    .map_err(|e| RfgrepError::Other(format!("Aho-Corasick error: {e}")))?,
+            )
+        } else {
+            None
+        };
+        let contains = |haystack: &[u8]| match &multi {
+            Some(ac) => ac.is_match(haystack),
+            // skipcq: RS-W1033 - Finder::find() only returns Option<usize>, .is_some() is correct
+            None => finder.find(haystack).is_some(),
+        };
+
+        // Compressed and transcoded files are scanned as they're decoded,
+        // stopping at the first hit
+        if crate::compression::is_compressed(path) || self.needs_decoding(path) {
+            let reader = self.open_text(path)?;
+            return scan_stream(reader, longest.saturating_sub(1), contains);
+        }
+
         let file = std::fs::File::open(path).map_err(crate::error::RfgrepError::Io)?;
         let metadata = file.metadata().map_err(crate::error::RfgrepError::Io)?;
-        let mmap_threshold = crate::processor::get_adaptive_mmap_threshold();
-        let finder = memmem::Finder::new(pattern.as_bytes());
+        let mmap_threshold = self.config.mmap_threshold;
+        // Reading or mapping the whole file must fit the memory cap; otherwise
+        // stream it line by line instead
+        let _reservation = match &self.memory {
+            Some(memory) => match memory.try_reserve(metadata.len()) {
+                Some(reservation) => Some(reservation),
+                None => return Ok(!self.search_file(path, pattern).await?.is_empty()),
+            },
+            None => None,
+        };
         let found = if metadata.len() >= mmap_threshold {
             // Use mmap for large files
             let mmap = unsafe { Mmap::map(&file).map_err(crate::error::RfgrepError::Io)? };
-            // skipcq: RS-W1033 - Finder::find() only returns Option<usize>, .is_some() is correct
-            finder.find(&mmap).is_some()
+            contains(&mmap)
         } else {
             // Zero-copy: read file into buffer, avoid extra allocations
             let buf = std::fs::read(path).map_err(crate::error::RfgrepError::Io)?;
-            // skipcq: RS-W1033 - Finder::find() only returns Option<usize>, .is_some() is correct
-            finder.find(&buf).is_some()
+            contains(&buf)
         };
         Ok(found)
     }
     pub fn new(config: StreamingConfig) -> Self {
-        Self { config }
+        let memory = config
+            .max_memory_bytes
+            .map(|limit| Arc::new(MemoryBudget::new(limit)));
+        Self {
+            config,
+            progress: None,
+            memory,
+            cancelled: None,
+            abandoned: None,
+        }
+    }
+
+    /// Most file bytes reserved at once under `max_memory_bytes`, if capped
+    pub fn peak_memory_bytes(&self) -> Option<u64> {
+        self.memory.as_ref().map(|memory| memory.peak_bytes())
+    }
+
+    /// Rough bytes of file data a streaming search of `path` holds in memory
+    fn estimated_memory(&self, path: &Path) -> u64 {
+        // Archive entries are read whole; everything else streams through buffers
+        match crate::compression::CompressionType::from_extension(path) {
+            Some(
+                crate::compression::CompressionType::Zip | crate::compression::CompressionType::Tar,
+            ) => std::fs::metadata(path).map_or(0, |m| m.len()),
+            _ => 2 * self.config.buffer_size as u64,
+        }
+    }
+
+    /// Count files in `progress` as `search_files_parallel` finishes them
+    pub fn with_progress(mut self, progress: Arc<SearchProgress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Stop early once `cancelled` is set: files not yet started are skipped
+    /// and a file being read returns the matches found so far
+    pub fn with_cancellation(mut self, cancelled: Arc<AtomicBool>) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        [&self.cancelled, &self.abandoned]
+            .into_iter()
+            .flatten()
+            .any(|flag| flag.load(Ordering::Relaxed))
     }
 
     /// Search a single file using streaming approach
@@ -78,100 +311,234 @@ impl StreamingSearchPipeline {
         path: &Path,
         pattern: &str,
     ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
-        // Early binary check
-        if crate::processor::is_binary(path) {
-            return Ok(vec![]);
+        // Early binary check; a preprocessor's input is expected not to be text
+        let binary = !self.preprocesses(path) && crate::processor::is_binary(path);
+        match self.config.binary_mode {
+            _ if !binary => self.search_file_timed(path, pattern, false).await,
+            BinaryMode::Skip => Ok(vec![]),
+            BinaryMode::Text => self.search_file_timed(path, pattern, true).await,
+            BinaryMode::Binary => {
+                // Only whether the file matches is reported, so stop at the first hit
+                let mut pipeline = self.clone();
+                pipeline.config.max_count = Some(1);
+                pipeline.config.context_before = 0;
+                pipeline.config.context_after = 0;
+                let matches = pipeline.search_file_timed(path, pattern, true).await?;
+                Ok(matches
+                    .into_iter()
+                    .map(|m| ProcessorSearchMatch {
+                        line: String::new(),
+                        binary: true,
+                        ..m
+                    })
+                    .collect())
+            }
         }
+    }
 
-        // Helper future that performs the actual search
-        let do_search = async {
-            if let Some(
-                crate::compression::CompressionType::Zip | crate::compression::CompressionType::Tar,
-            ) = crate::compression::CompressionType::from_extension(path)
-            {
-                let pat_str = if !self.config.case_sensitive {
-                    format!("(?i){}", pattern)
-                } else {
-                    pattern.to_string()
-                };
-                let regex = crate::processor::get_or_compile_regex(&pat_str)?;
-                let matches = crate::archive::search_archive(path, &regex)?;
+    /// `search_file` under the per-file timeout; `lossy` lines with invalid
+    /// UTF-8 are searched with replacement characters instead of skipped
+    async fn search_file_timed(
+        &self,
+        path: &Path,
+        pattern: &str,
+        lossy: bool,
+    ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
+        let Some(timeout_secs) = self.config.timeout_per_file else {
+            return self.search_file_untimed(path, pattern, lossy).await;
+        };
+        // If test env variable is set, simulate work taking time
+        let simulated_work = std::env::var("RFGREP_WORKER_SLEEP")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(std::time::Duration::from_secs);
 
-                // Archive matching uses processor::SearchMatch directly.
-                // Post-processing (invert match) is skipped as find_matches_streaming only returns positive matches.
-                let mut final_matches = matches;
+        // Scanning runs on a blocking thread so the timeout fires even while a
+        // slow regex is busy with one line; the abandoned scan then stops at
+        // its next cancellation check
+        let abandoned = Arc::new(AtomicBool::new(false));
+        let mut pipeline = self.clone();
+        pipeline.abandoned = Some(abandoned.clone());
+        let (path, pattern) = (path.to_path_buf(), pattern.to_string());
+        let timed = async move {
+            // Sleep inside the timed section to simulate long-running work
+            if let Some(delay) = simulated_work {
+                tokio::time::sleep(delay).await;
+            }
+            task::spawn_blocking(move || {
+                futures::executor::block_on(pipeline.search_file_untimed(&path, &pattern, lossy))
+            })
+            .await
+            .map_err(|e| RfgrepError::Other(format!("File search failed: {e}")))?
+        };
 
-                if let Some(max_matches) = self.config.max_matches {
-                    if final_matches.len() > max_matches {
-                        final_matches.truncate(max_matches);
-                    }
-                }
-                return RfgrepResult::Ok(final_matches);
+        // Enforce per-file timeout: on timeout, return no matches
+        match tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), timed).await {
+            Ok(res) => res,
+            Err(_elapsed) => {
+                abandoned.store(true, Ordering::Relaxed);
+                Ok(vec![])
             }
+        }
+    }
+
+    /// `search_file` without the per-file timeout
+    async fn search_file_untimed(
+        &self,
+        path: &Path,
+        pattern: &str,
+        lossy: bool,
+    ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
+        if let Some(preprocessor) = self.preprocessor_for(path) {
+            let output = preprocessor.run(path)?;
+            crate::processor::record_read(false);
+            let search_algo = self.create_search_algorithm(pattern)?;
+            let matches = self
+                .process_file_streaming(&output[..], search_algo.as_ref(), pattern, lossy)
+                .await?;
+            return self.finish_matches(matches, path);
+        }
 
-            let reader: Box<dyn Read + Send> = if let Some(compression) =
-                crate::compression::CompressionType::from_extension(path)
-            {
-                crate::compression::open_compressed_stream(path, compression)
-                    .map_err(RfgrepError::Io)?
+        if let Some(
+            crate::compression::CompressionType::Zip | crate::compression::CompressionType::Tar,
+        ) = crate::compression::CompressionType::from_extension(path)
+        {
+            let pat_str = if !self.config.case_sensitive {
+                format!("(?i){}", pattern)
             } else {
-                let file = File::open(path).map_err(RfgrepError::Io)?;
-                Box::new(file)
+                pattern.to_string()
             };
+            let regex = crate::processor::get_or_compile_regex(&pat_str)?;
+            let matches = crate::archive::search_archive_with_limits(
+                path,
+                &regex,
+                &self.config.archive_limits,
+            )?;
 
-            let reader = BufReader::with_capacity(self.config.buffer_size, reader);
+            // Archive matching uses processor::SearchMatch directly.
+            // Post-processing (invert match) is skipped as find_matches_streaming only returns positive matches.
+            let mut final_matches = matches;
+            if let Some(range) = self.config.line_range {
+                final_matches.retain(|m| range.contains(m.line_number));
+            }
+            if self.config.word_regexp {
+                final_matches.retain(|m| is_whole_word(&m.line, m.column_start, m.column_end));
+            }
 
-            // Create search algorithm instance
-            let search_algo = self.create_search_algorithm(pattern)?;
+            if let Some(limit) = self.per_file_limit() {
+                final_matches.truncate(limit);
+            }
+            return Ok(final_matches);
+        }
 
-            // Process file in chunks
-            let matches = self
-                .process_file_streaming(reader, search_algo.as_ref(), pattern, path)
-                .await?;
+        // Create search algorithm instance
+        let search_algo = self.create_search_algorithm(pattern)?;
 
-            // Apply post-processing
-            let mut final_matches = self.apply_post_processing(matches, path)?;
+        if let Some((mmap, _reservation)) = self.map_large_file(path) {
+            // Invalid UTF-8 is left to the streaming path, which skips bad lines
+            if let Ok(text) = std::str::from_utf8(&mmap) {
+                crate::processor::record_read(true);
+                let max_line_bytes = self.config.max_line_bytes;
+                let lines = crate::processor::lines_with_offsets(text).map(|(start, line)| {
+                    if line.len() > max_line_bytes {
+                        Err(std::io::Error::other(crate::processor::LineTooLong))
+                    } else {
+                        Ok((start, line))
+                    }
+                });
+                let matches = match self.scan_lines(lines, search_algo.as_ref(), pattern) {
+                    Err(RfgrepError::Io(e)) if crate::processor::is_line_too_long(&e) => {
+                        self.scan_chunks(&mmap[..], search_algo.as_ref(), pattern)?
+                    }
+                    result => result?,
+                };
+                return self.finish_matches(matches, path);
+            }
+        }
 
-            // Apply max_matches limit
-            if let Some(max_matches) = self.config.max_matches {
-                if final_matches.len() > max_matches {
-                    final_matches.truncate(max_matches);
-                }
+        crate::processor::record_read(false);
+        let reader = self.open_text(path)?;
+        let matches = match self
+            .process_file_streaming(reader, search_algo.as_ref(), pattern, lossy)
+            .await
+        {
+            // A line too long to hold in memory: start over, in windows
+            Err(RfgrepError::Io(e)) if crate::processor::is_line_too_long(&e) => {
+                self.scan_chunks(self.open_text(path)?, search_algo.as_ref(), pattern)?
             }
+            result => result?,
+        };
+
+        self.finish_matches(matches, path)
+    }
 
-            RfgrepResult::Ok(final_matches)
+    /// Open `path` for reading as UTF-8, decompressing and transcoding it
+    /// as needed
+    pub(crate) fn open_text(&self, path: &Path) -> RfgrepResult<Box<dyn BufRead + Send>> {
+        let reader: Box<dyn Read + Send> =
+            if let Some(compression) = crate::compression::CompressionType::from_extension(path) {
+                let stream = crate::compression::open_compressed_stream(path, compression)
+                    .map_err(RfgrepError::Io)?;
+                Box::new(crate::compression::SizeLimitedReader::new(
+                    stream,
+                    self.config.archive_limits.max_decompressed_bytes,
+                ))
+            } else {
+                let file = File::open(path).map_err(RfgrepError::Io)?;
+                Box::new(file)
+            };
+
+        let mut reader = BufReader::with_capacity(self.config.buffer_size, reader);
+        let head = reader.fill_buf().map_err(RfgrepError::Io)?;
+        Ok(match decoder_for(self.config.encoding, head) {
+            Some(decoder) => Box::new(BufReader::with_capacity(
+                self.config.buffer_size,
+                DecodingReader::new(reader, decoder),
+            )),
+            None => Box::new(reader),
+        })
+    }
+
+    /// Memory-map `path` if it's an uncompressed file of at least
+    /// `mmap_threshold` bytes that needs no transcoding and whose mapping fits
+    /// the memory cap; otherwise it's streamed
+    fn map_large_file(
+        &self,
+        path: &Path,
+    ) -> Option<(memmap2::Mmap, Option<crate::memory::MemoryReservation>)> {
+        if crate::compression::CompressionType::from_extension(path).is_some() {
+            return None;
+        }
+        let file = File::open(path).ok()?;
+        let len = file.metadata().ok()?.len();
+        if len < self.config.mmap_threshold {
+            return None;
+        }
+        let reservation = match &self.memory {
+            Some(memory) => Some(memory.try_reserve(len)?),
+            None => None,
         };
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.ok()?;
+        if decoder_for(self.config.encoding, &mmap[..mmap.len().min(4)]).is_some() {
+            return None;
+        }
+        Some((mmap, reservation))
+    }
 
-        if let Some(timeout_secs) = self.config.timeout_per_file {
-            // If test env variable is set, simulate work taking time
-            if let Ok(s) = std::env::var("RFGREP_WORKER_SLEEP") {
-                if let Ok(sec) = s.parse::<u64>() {
-                    // Sleep inside the timed section to simulate long-running work
-                    let sleep_fut = async {
-                        tokio::time::sleep(std::time::Duration::from_secs(sec)).await;
-                        do_search.await
-                    };
-                    return match tokio::time::timeout(
-                        std::time::Duration::from_secs(timeout_secs),
-                        sleep_fut,
-                    )
-                    .await
-                    {
-                        Ok(res) => res,
-                        Err(_elapsed) => Ok(vec![]),
-                    };
-                }
-            }
-            // Enforce per-file timeout: on timeout, return no matches
-            match tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), do_search)
-                .await
-            {
-                Ok(res) => res,
-                Err(_elapsed) => Ok(vec![]),
-            }
-        } else {
-            do_search.await
+    /// Convert a file's matches and apply the per-file limit
+    fn finish_matches(
+        &self,
+        matches: Vec<SearchMatch>,
+        path: &Path,
+    ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
+        let mut final_matches = self.apply_post_processing(matches, path)?;
+
+        // No file can contribute more than the per-file or overall limit
+        if let Some(limit) = self.per_file_limit() {
+            final_matches.truncate(limit);
         }
+
+        Ok(final_matches)
     }
 
     /// Search multiple files in parallel
@@ -181,24 +548,36 @@ impl StreamingSearchPipeline {
         pattern: &str,
         max_concurrent: usize,
     ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
-        let (tx, mut rx) = mpsc::channel::<RfgrepResult<Vec<ProcessorSearchMatch>>>(files.len());
+        let (tx, mut rx) =
+            mpsc::channel::<(PathBuf, RfgrepResult<Vec<ProcessorSearchMatch>>)>(files.len());
         let semaphore = Arc::new(tokio::sync::Semaphore::new(max_concurrent));
-        let config = Arc::new(self.config.clone());
         let pattern = Arc::new(pattern.to_string());
 
         // Spawn tasks for each file
         for file_path in files {
             let tx = tx.clone();
             let semaphore = semaphore.clone();
-            let config = config.clone();
             let pattern = pattern.clone();
+            // Clones share the progress counter and the memory budget
+            let pipeline = self.clone();
             let file_path = (*file_path).to_path_buf();
 
             task::spawn(async move {
                 let _permit = semaphore.acquire().await.unwrap();
-                let pipeline = StreamingSearchPipeline::new((*config).clone());
+                if pipeline.is_cancelled() {
+                    return;
+                }
+                let _reservation = match &pipeline.memory {
+                    Some(memory) => {
+                        Some(memory.reserve(pipeline.estimated_memory(&file_path)).await)
+                    }
+                    None => None,
+                };
                 let result = pipeline.search_file(&file_path, &pattern).await;
-                let _ = tx.send(result).await;
+                if let Some(progress) = &pipeline.progress {
+                    progress.file_done(&file_path);
+                }
+                let _ = tx.send((file_path, result)).await;
             });
         }
 
@@ -206,26 +585,93 @@ impl StreamingSearchPipeline {
 
         // Collect results
         let mut all_matches = Vec::new();
-        while let Some(result) = rx.recv().await {
+        while let Some((path, result)) = rx.recv().await {
             match result {
                 Ok(matches) => all_matches.extend(matches),
+                Err(e) if self.config.no_messages => {
+                    log::debug!("Error searching {}: {e}", path.display());
+                }
                 Err(e) => {
-                    eprintln!("Error in parallel search: {e}");
+                    eprintln!("Error searching {}: {e}", path.display());
                 }
             }
         }
 
-        // Sort results
-        all_matches.sort();
+        // Results arrive in completion order; put them back in file order
+        all_matches.sort_by(|a, b| {
+            (&a.path, a.line_number, a.column_start).cmp(&(&b.path, b.line_number, b.column_start))
+        });
         Ok(all_matches)
     }
 
+    /// Most matches a single file may report: the smaller of `max_count` and
+    /// `max_matches`
+    fn per_file_limit(&self) -> Option<usize> {
+        match (self.config.max_count, self.config.max_matches) {
+            (Some(count), Some(total)) => Some(count.min(total)),
+            (count, total) => count.or(total),
+        }
+    }
+
+    /// The `--pre` command to search `path` through, if it applies
+    fn preprocessor_for(&self, path: &Path) -> Option<&crate::preprocess::Preprocessor> {
+        self.config
+            .preprocessor
+            .as_ref()
+            .filter(|preprocessor| preprocessor.applies_to(path))
+    }
+
+    fn preprocesses(&self, path: &Path) -> bool {
+        self.preprocessor_for(path).is_some()
+    }
+
+    /// Whether `path` has to be transcoded before its bytes can be matched
+    fn needs_decoding(&self, path: &Path) -> bool {
+        match self.config.encoding {
+            Encoding::Utf8 => false,
+            Encoding::Auto => {
+                let mut head = [0u8; 3];
+                let read = File::open(path)
+                    .and_then(|mut file| file.read(&mut head))
+                    .unwrap_or(0);
+                encoding_rs::Encoding::for_bom(&head[..read]).is_some()
+            }
+            Encoding::Utf16le | Encoding::Utf16be | Encoding::Latin1 => true,
+        }
+    }
+
+    /// Whether this search should dispatch to the multi-pattern automaton
+    fn uses_multi_pattern(&self) -> bool {
+        self.config.patterns.len() > 1
+            && !matches!(
+                self.config.algorithm,
+                crate::search_algorithms::SearchAlgorithm::Regex
+            )
+    }
+
     fn create_search_algorithm(
         &self,
         pattern: &str,
     ) -> RfgrepResult<Box<dyn SearchAlgorithmTrait>> {
         use crate::search_algorithms::SearchAlgorithmFactory;
 
+        if self.uses_multi_pattern() {
+            return SearchAlgorithmFactory::create_multi_with_case_sensitivity(
+                &self.config.patterns,
+                self.config.case_sensitive,
+            );
+        }
+
+        if let SearchAlgorithm::Fuzzy = self.config.algorithm {
+            return Ok(Box::new(
+                crate::search_algorithms::FuzzySearch::with_options(
+                    pattern,
+                    self.config.max_distance,
+                    self.config.case_sensitive,
+                ),
+            ));
+        }
+
         // For non-regex algorithms, we need to handle case sensitivity differently
         let processed_pattern = match self.config.algorithm {
             crate::search_algorithms::SearchAlgorithm::Regex => {
@@ -242,28 +688,219 @@ impl StreamingSearchPipeline {
             }
         };
 
-        Ok(SearchAlgorithmFactory::create_with_case_sensitivity(
+        SearchAlgorithmFactory::create_with_case_sensitivity(
             self.config.algorithm.clone(),
             &processed_pattern,
             self.config.case_sensitive,
-        ))
+        )
     }
 
-    async fn process_file_streaming<R: Read + Send + 'static>(
+    async fn process_file_streaming<R: BufRead>(
         &self,
-        reader: BufReader<R>,
+        reader: R,
         search_algo: &dyn SearchAlgorithmTrait,
         pattern: &str,
-        _path: &Path,
+        lossy: bool,
     ) -> RfgrepResult<Vec<SearchMatch>> {
+        self.scan_lines(
+            crate::processor::OffsetLines::new(reader)
+                .lossy(lossy)
+                .max_line_len(self.config.max_line_bytes),
+            search_algo,
+            pattern,
+        )
+    }
+
+    /// Search `reader` in `chunk_size` windows rather than lines, for input
+    /// with lines too long to hold in memory
+    ///
+    /// Consecutive windows overlap by the longest pattern, so a literal match
+    /// straddling a window boundary is still found whole, and a match touching
+    /// the end of a window is left for the next one, which also sees what
+    /// follows it; regex matches are only guaranteed up to `chunk_size / 2`
+    /// bytes. A match's `line` is the part of its line inside the window,
+    /// marked as an excerpt when that isn't all of it, while its columns are
+    /// those of the whole line. No context is collected. Invalid UTF-8 is
+    /// searched as `?`. Inverted searches can't report a line they never hold,
+    /// so they find nothing here.
+    fn scan_chunks(
+        &self,
+        mut reader: impl Read,
+        search_algo: &dyn SearchAlgorithmTrait,
+        pattern: &str,
+    ) -> RfgrepResult<Vec<SearchMatch>> {
+        if self.config.invert_match {
+            log::warn!("--invert-match skips lines longer than the line limit");
+            return Ok(Vec::new());
+        }
+        let chunk_size = self.config.chunk_size.max(1);
+        let overlap = self.chunk_overlap(pattern);
+        let limit = self.per_file_limit();
         let mut matches = Vec::new();
-        let mut lines = reader.lines();
+        // The window, its offset in the input, the number of its first line,
+        // where that line starts in the input, the character just before the
+        // window, and how much of it the previous window already searched
+        let mut window: Vec<u8> = Vec::with_capacity(chunk_size + overlap);
+        let mut window_start = 0;
+        let mut line_number = 1;
+        let mut line_start = 0;
+        let mut char_before = None;
+        let mut searched = 0;
+
+        loop {
+            if self.is_cancelled() {
+                break;
+            }
+            let filled = window.len();
+            window.resize(filled + chunk_size, 0);
+            let mut read = 0;
+            while read < chunk_size {
+                match reader.read(&mut window[filled + read..]) {
+                    Ok(0) => break,
+                    Ok(n) => read += n,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(RfgrepError::Io(e)),
+                }
+            }
+            window.truncate(filled + read);
+            let at_end = read < chunk_size;
+
+            // A character cut off at the end of the window waits for the next one
+            let text_len = sanitize_utf8(&mut window, at_end);
+            let text = std::str::from_utf8(&window[..text_len]).unwrap_or_default();
+
+            let mut hits = search_algo.search_ranges_with_distance(text, pattern);
+            // Hits that ended in the part already searched were reported then,
+            // and those reaching the end of the window are left for the next
+            hits.retain(|&(_, end, _)| end >= searched && (at_end || end < text.len()));
+            for (start, end, distance) in hits {
+                if self.config.word_regexp {
+                    let before = text[..start].chars().next_back().or(char_before);
+                    let after = text[end..].chars().next();
+                    if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+                        continue;
+                    }
+                }
+                let (line_begin, begin_offset) = match text[..start].rfind('\n') {
+                    Some(i) => (i + 1, window_start + i + 1),
+                    None => (0, line_start),
+                };
+                let line_end = text[end..].find('\n').map_or(text.len(), |i| end + i);
+                let line = text[line_begin..line_end].trim_end_matches('\r');
+                // Columns count from where the line really starts, which may
+                // be several windows back
+                let excerpt_offset = window_start + line_begin - begin_offset;
+                let column_start = excerpt_offset + start - line_begin;
+                let column_end = excerpt_offset + (end - line_begin).min(line.len());
+                let excerpt = (excerpt_offset > 0 || line_end == text.len() && !at_end)
+                    .then_some(excerpt_offset);
+                let number = line_number + text[..start].matches('\n').count();
+                if self
+                    .config
+                    .line_range
+                    .is_some_and(|range| !range.contains(number))
+                {
+                    continue;
+                }
+                matches.push(SearchMatch {
+                    line_number: number,
+                    line: line.to_string(),
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    matched_text: text[start..end].to_string(),
+                    column_start,
+                    column_end,
+                    byte_offset: window_start + start,
+                    distance,
+                    excerpt,
+                });
+            }
+
+            if at_end || limit.is_some_and(|limit| matches.len() >= limit) {
+                break;
+            }
+
+            // Keep the tail so a match across the boundary is seen whole next time
+            let mut keep_from = text_len.saturating_sub(overlap);
+            while !text.is_char_boundary(keep_from) {
+                keep_from -= 1;
+            }
+            let dropped = &text[..keep_from];
+            line_number += dropped.matches('\n').count();
+            if let Some(i) = dropped.rfind('\n') {
+                line_start = window_start + i + 1;
+            }
+            char_before = dropped.chars().next_back().or(char_before);
+            window_start += keep_from;
+            window.drain(..keep_from);
+            searched = text_len - keep_from;
+        }
+
+        if let Some(limit) = limit {
+            matches.truncate(limit);
+        }
+        Ok(matches)
+    }
+
+    /// Bytes consecutive `scan_chunks` windows share: enough to hold any
+    /// literal match whole
+    fn chunk_overlap(&self, pattern: &str) -> usize {
+        let longest = self
+            .config
+            .patterns
+            .iter()
+            .map(String::len)
+            .chain([pattern.len()])
+            .max()
+            .unwrap_or(0);
+        match self.config.algorithm {
+            SearchAlgorithm::Regex => self.config.chunk_size / 2,
+            SearchAlgorithm::Fuzzy => longest + self.config.max_distance,
+            _ => longest,
+        }
+    }
+
+    /// Search `lines` one at a time, from a reader or from a mapped file; each
+    /// comes with the byte offset it starts at
+    fn scan_lines<L: AsRef<str>>(
+        &self,
+        lines: impl Iterator<Item = std::io::Result<(usize, L)>>,
+        search_algo: &dyn SearchAlgorithmTrait,
+        pattern: &str,
+    ) -> RfgrepResult<Vec<SearchMatch>> {
+        let StreamingConfig {
+            context_before,
+            context_after,
+            ..
+        } = self.config;
+        let mut matches: Vec<SearchMatch> = Vec::new();
         let mut line_number = 0;
         let mut context_buffer = Vec::new();
+        // Matches still collecting trailing context: (index into `matches`, lines wanted).
+        // Filling these as lines arrive keeps every line available for searching.
+        let mut pending_after: Vec<(usize, usize)> = Vec::new();
+        let limit = self.per_file_limit();
+
+        for line_result in lines {
+            // Once the limit is reached, only keep reading for trailing context
+            if limit.is_some_and(|limit| matches.len() >= limit) && pending_after.is_empty() {
+                break;
+            }
+            if line_number > 0 && line_number % CANCEL_CHECK_INTERVAL == 0 && self.is_cancelled() {
+                break;
+            }
 
-        while let Some(line_result) = lines.next() {
             line_number += 1;
-            let line = match line_result {
+            // Lines outside the range are neither searched nor used as context
+            if let Some(range) = self.config.line_range {
+                if range.is_past(line_number) {
+                    break;
+                }
+                if !range.contains(line_number) {
+                    continue;
+                }
+            }
+            let (line_start, line) = match line_result {
                 Ok(line) => line,
                 Err(e) => {
                     // Skip lines that can't be read as UTF-8 (likely binary content)
@@ -273,36 +910,74 @@ impl StreamingSearchPipeline {
                     return Err(RfgrepError::Io(e));
                 }
             };
+            let line = line.as_ref();
+
+            pending_after.retain_mut(|(index, remaining)| {
+                matches[*index]
+                    .context_after
+                    .push((line_number, line.to_string()));
+                *remaining -= 1;
+                *remaining > 0
+            });
 
             // Add to context buffer
-            context_buffer.push((line_number, line.clone()));
-            if context_buffer.len() > self.config.context_lines * 2 + 1 {
-                context_buffer.remove(0);
+            if context_before > 0 {
+                context_buffer.push((line_number, line.to_string()));
+                if context_buffer.len() > context_before + 1 {
+                    context_buffer.remove(0);
+                }
             }
 
             // Search in current line
-            let line_matches = search_algo.search(&line, pattern);
+            let mut line_matches = search_algo.search_ranges_with_distance(line, pattern);
+            if self.config.word_regexp {
+                line_matches.retain(|&(start, end, _)| is_whole_word(line, start, end));
+            }
+            let first_new = matches.len();
 
-            for match_pos in line_matches {
-                let context_before = self.get_context_before(&context_buffer, line_number);
-                let context_after =
-                    self.get_context_after(&context_buffer, line_number, &mut lines)?;
+            // With invert_match, report exactly the lines without any hit
+            if self.config.invert_match {
+                if line_matches.is_empty() {
+                    matches.push(SearchMatch {
+                        line_number,
+                        line: line.to_string(),
+                        context_before: self.get_context_before(&context_buffer, line_number),
+                        context_after: Vec::new(),
+                        matched_text: String::new(),
+                        column_start: 0,
+                        column_end: 0,
+                        byte_offset: line_start,
+                        distance: None,
+                        excerpt: None,
+                    });
+                }
+            } else {
+                for (match_start, match_end, distance) in line_matches {
+                    let matched_text = line
+                        .get(match_start..match_end)
+                        .unwrap_or_default()
+                        .to_string();
 
-                let matched_text = if match_pos + 1 < line.len() {
-                    line[match_pos..].chars().take(50).collect::<String>()
-                } else {
-                    line.clone()
-                };
+                    matches.push(SearchMatch {
+                        line_number,
+                        line: line.to_string(),
+                        context_before: self.get_context_before(&context_buffer, line_number),
+                        context_after: Vec::new(),
+                        matched_text,
+                        column_start: match_start,
+                        column_end: match_end,
+                        byte_offset: line_start + match_start,
+                        distance,
+                        excerpt: None,
+                    });
+                }
+            }
 
-                matches.push(SearchMatch {
-                    line_number,
-                    line: line.clone(),
-                    context_before,
-                    context_after,
-                    matched_text,
-                    column_start: match_pos,
-                    column_end: match_pos + 1,
-                });
+            if let Some(limit) = limit {
+                matches.truncate(limit.max(first_new));
+            }
+            if context_after > 0 {
+                pending_after.extend((first_new..matches.len()).map(|i| (i, context_after)));
             }
         }
 
@@ -314,7 +989,7 @@ impl StreamingSearchPipeline {
         context_buffer: &[(usize, String)],
         current_line: usize,
     ) -> Vec<(usize, String)> {
-        let start = current_line.saturating_sub(self.config.context_lines);
+        let start = current_line.saturating_sub(self.config.context_before);
         context_buffer
             .iter()
             .filter(|(line_num, _)| *line_num >= start && *line_num < current_line)
@@ -322,28 +997,6 @@ impl StreamingSearchPipeline {
             .collect()
     }
 
-    fn get_context_after<R: Read + Send + 'static>(
-        &self,
-        _context_buffer: &[(usize, String)],
-        current_line: usize,
-        lines: &mut std::io::Lines<BufReader<R>>,
-    ) -> RfgrepResult<Vec<(usize, String)>> {
-        let mut context_after = Vec::new();
-        let mut line_number = current_line;
-
-        for _ in 0..self.config.context_lines {
-            if let Some(line_result) = lines.next() {
-                line_number += 1;
-                let line = line_result.map_err(RfgrepError::Io)?;
-                context_after.push((line_number, line));
-            } else {
-                break;
-            }
-        }
-
-        Ok(context_after)
-    }
-
     fn apply_post_processing(
         &self,
         matches: Vec<SearchMatch>,
@@ -351,26 +1004,22 @@ impl StreamingSearchPipeline {
     ) -> RfgrepResult<Vec<ProcessorSearchMatch>> {
         let mut processor_matches = Vec::new();
 
+        // invert_match is already resolved while streaming, so every match is kept
         for search_match in matches {
-            // Apply invert_match logic
-            let should_include = if self.config.invert_match {
-                search_match.matched_text.is_empty()
-            } else {
-                !search_match.matched_text.is_empty()
-            };
-
-            if should_include {
-                processor_matches.push(ProcessorSearchMatch {
-                    path: path.to_path_buf(),
-                    line_number: search_match.line_number,
-                    line: search_match.line,
-                    context_before: search_match.context_before,
-                    context_after: search_match.context_after,
-                    matched_text: search_match.matched_text,
-                    column_start: search_match.column_start,
-                    column_end: search_match.column_end,
-                });
-            }
+            processor_matches.push(ProcessorSearchMatch {
+                path: path.to_path_buf(),
+                line_number: search_match.line_number,
+                line: search_match.line,
+                context_before: search_match.context_before,
+                context_after: search_match.context_after,
+                matched_text: search_match.matched_text,
+                column_start: search_match.column_start,
+                column_end: search_match.column_end,
+                byte_offset: search_match.byte_offset,
+                distance: search_match.distance,
+                binary: false,
+                excerpt: search_match.excerpt,
+            });
         }
 
         Ok(processor_matches)
@@ -427,10 +1076,12 @@ pub mod utils {
         algorithm: &SearchAlgorithm,
     ) -> (f64, String) {
         let base_time_per_mb = match algorithm {
-            SearchAlgorithm::BoyerMoore => 0.1, // 100ms per MB
-            SearchAlgorithm::Regex => 0.5,      // 500ms per MB
-            SearchAlgorithm::Simple => 0.2,     // 200ms per MB
-            SearchAlgorithm::Simd => 0.05,      // 50ms per MB (fastest)
+            SearchAlgorithm::BoyerMoore => 0.1,  // 100ms per MB
+            SearchAlgorithm::Regex => 0.5,       // 500ms per MB
+            SearchAlgorithm::Simple => 0.2,      // 200ms per MB
+            SearchAlgorithm::Simd => 0.05,       // 50ms per MB (fastest)
+            SearchAlgorithm::Fuzzy => 1.0,       // 1s per MB (edit-distance DP)
+            SearchAlgorithm::AhoCorasick => 0.1, // 100ms per MB
         };
 
         let total_size_mb = (file_count as f64 * avg_file_size as f64) / (1024.0 * 1024.0);
@@ -448,6 +1099,109 @@ pub mod utils {
     }
 }
 
+/// Overwrite invalid UTF-8 in `bytes` with `?`, keeping every offset, and
+/// return how much of it is text. An incomplete character at the very end is
+/// left out unless `at_end`.
+fn sanitize_utf8(bytes: &mut [u8], at_end: bool) -> usize {
+    let mut valid = 0;
+    loop {
+        match std::str::from_utf8(&bytes[valid..]) {
+            Ok(_) => return bytes.len(),
+            Err(e) => {
+                let bad_start = valid + e.valid_up_to();
+                match e.error_len() {
+                    Some(len) => {
+                        bytes[bad_start..bad_start + len].fill(b'?');
+                        valid = bad_start + len;
+                    }
+                    None if at_end => {
+                        bytes[bad_start..].fill(b'?');
+                        return bytes.len();
+                    }
+                    None => return bad_start,
+                }
+            }
+        }
+    }
+}
+
+/// The decoder for a file starting with `head`, or `None` when its bytes can be
+/// split into lines as UTF-8 directly
+fn decoder_for(encoding: Encoding, head: &[u8]) -> Option<encoding_rs::Decoder> {
+    match encoding {
+        Encoding::Utf8 => None,
+        Encoding::Utf16le => Some(encoding_rs::UTF_16LE.new_decoder_with_bom_removal()),
+        Encoding::Utf16be => Some(encoding_rs::UTF_16BE.new_decoder_with_bom_removal()),
+        // encoding_rs follows the WHATWG spec, where latin1 is windows-1252
+        Encoding::Latin1 => Some(encoding_rs::WINDOWS_1252.new_decoder_without_bom_handling()),
+        Encoding::Auto => encoding_rs::Encoding::for_bom(head)
+            .map(|(encoding, _)| encoding.new_decoder_with_bom_removal()),
+    }
+}
+
+/// Transcodes the bytes of another reader to UTF-8 as they are read
+struct DecodingReader<R> {
+    inner: R,
+    decoder: encoding_rs::Decoder,
+    input: Box<[u8]>,
+    input_start: usize,
+    input_end: usize,
+    output: Box<[u8]>,
+    output_start: usize,
+    output_end: usize,
+    eof: bool,
+    done: bool,
+}
+
+impl<R: Read> DecodingReader<R> {
+    const BUFFER_SIZE: usize = 8192;
+
+    fn new(inner: R, decoder: encoding_rs::Decoder) -> Self {
+        Self {
+            inner,
+            decoder,
+            input: vec![0; Self::BUFFER_SIZE].into_boxed_slice(),
+            input_start: 0,
+            input_end: 0,
+            output: vec![0; Self::BUFFER_SIZE].into_boxed_slice(),
+            output_start: 0,
+            output_end: 0,
+            eof: false,
+            done: false,
+        }
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.output_start == self.output_end {
+            if self.done {
+                return Ok(0);
+            }
+            if self.input_start == self.input_end && !self.eof {
+                self.input_start = 0;
+                self.input_end = self.inner.read(&mut self.input)?;
+                self.eof = self.input_end == 0;
+            }
+
+            let (result, read, written, _) = self.decoder.decode_to_utf8(
+                &self.input[self.input_start..self.input_end],
+                &mut self.output,
+                self.eof,
+            );
+            self.input_start += read;
+            self.output_start = 0;
+            self.output_end = written;
+            self.done = self.eof && result == encoding_rs::CoderResult::InputEmpty;
+        }
+
+        let n = buf.len().min(self.output_end - self.output_start);
+        buf[..n].copy_from_slice(&self.output[self.output_start..self.output_start + n]);
+        self.output_start += n;
+        Ok(n)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -466,4 +1220,533 @@ mod tests {
         // let matches = pipeline.process_file_streaming(reader, &search_algo, Path::new("test.txt")).await.unwrap();
         // assert!(!matches.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_multi_pattern_uses_aho_corasick() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(
+            &path,
+            "INFO start\nWARN low disk\nERROR failed\nINFO done\n",
+        )
+        .unwrap();
+
+        let config = StreamingConfig {
+            patterns: vec!["ERROR".to_string(), "WARN".to_string()],
+            context_before: 0,
+            context_after: 0,
+            ..Default::default()
+        };
+        let pipeline = StreamingSearchPipeline::new(config);
+
+        let matches = pipeline.search_file(&path, "ERROR").await.unwrap();
+        let lines: Vec<usize> = matches.iter().map(|m| m.line_number).collect();
+        assert_eq!(lines, vec![2, 3]);
+
+        assert!(pipeline
+            .search_file_fast_exit(&path, "ERROR")
+            .await
+            .unwrap());
+    }
+    #[tokio::test]
+    async fn test_fuzzy_reports_distance() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("code.rs");
+        std::fs::write(&path, "fn calculate_total()\nlet x = calculte_total();\n").unwrap();
+
+        let config = StreamingConfig {
+            algorithm: SearchAlgorithm::Fuzzy,
+            max_distance: 2,
+            context_before: 0,
+            context_after: 0,
+            ..Default::default()
+        };
+        let pipeline = StreamingSearchPipeline::new(config);
+
+        let matches = pipeline
+            .search_file(&path, "calculate_total")
+            .await
+            .unwrap();
+        let found: Vec<(usize, &str, Option<usize>)> = matches
+            .iter()
+            .map(|m| (m.line_number, m.matched_text.as_str(), m.distance))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, "calculate_total", Some(0)),
+                (2, "calculte_total", Some(1))
+            ]
+        );
+        assert!(pipeline
+            .search_file_fast_exit(&path, "calculate_total")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_literal_match_spans_whole_pattern() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spans.txt");
+        std::fs::write(
+            &path,
+            "alpha needle beta
+needle_needle
+",
+        )
+        .unwrap();
+
+        let pattern = "needle";
+        for algorithm in [
+            SearchAlgorithm::BoyerMoore,
+            SearchAlgorithm::Simd,
+            SearchAlgorithm::Simple,
+        ] {
+            let config = StreamingConfig {
+                algorithm: algorithm.clone(),
+                context_before: 0,
+                context_after: 0,
+                ..Default::default()
+            };
+            let pipeline = StreamingSearchPipeline::new(config);
+
+            let matches = pipeline.search_file(&path, pattern).await.unwrap();
+            assert_eq!(matches.len(), 3, "{algorithm:?}");
+            for m in &matches {
+                assert_eq!(
+                    m.column_end - m.column_start,
+                    pattern.len(),
+                    "{algorithm:?}"
+                );
+                assert_eq!(m.matched_text, pattern, "{algorithm:?}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlong_lines_are_searched_in_overlapping_windows() {
+        // One 100 KB line standing in for a line too large for memory, with
+        // hits straddling the boundaries of 4 KB windows (which end at
+        // multiples of 4096) and one cut inside a multibyte character
+        let mut content = vec![b'x'; 100_000];
+        let hits = [
+            (4093, "needle"),
+            (8190, "needle"),
+            (12287, "needle"),
+            (16382, "n\u{e9}\u{e9}dl\u{e9}"),
+            (50_000, "needle"),
+        ];
+        for (offset, text) in hits {
+            content[offset..offset + text.len()].copy_from_slice(text.as_bytes());
+        }
+        content.extend_from_slice(b"\nsecond needle line\n");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("one_line.txt");
+        std::fs::write(&path, &content).unwrap();
+
+        for (pattern, expected) in [
+            ("needle", vec![4093, 8190, 12287, 50_000, 100_008]),
+            ("n\u{e9}\u{e9}dl\u{e9}", vec![16382]),
+        ] {
+            for mmap_threshold in [0, u64::MAX] {
+                let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+                    context_before: 0,
+                    context_after: 0,
+                    chunk_size: 4096,
+                    max_line_bytes: 1024,
+                    mmap_threshold,
+                    ..Default::default()
+                });
+                let matches = pipeline.search_file(&path, pattern).await.unwrap();
+                let offsets: Vec<usize> = matches.iter().map(|m| m.byte_offset).collect();
+                assert_eq!(
+                    offsets, expected,
+                    "{pattern} mmap_threshold={mmap_threshold}"
+                );
+                for m in &matches {
+                    let (start, end) = m.line_span();
+                    assert_eq!(m.matched_text, pattern);
+                    assert_eq!(&m.line[start..end], pattern);
+                    if m.line_number == 1 {
+                        assert_eq!(m.column_start, m.byte_offset);
+                    }
+                }
+                if pattern == "needle" {
+                    let last = matches.last().unwrap();
+                    assert_eq!(last.line_number, 2);
+                    assert_eq!(
+                        (last.line.as_str(), last.column_start),
+                        ("second needle line", 7)
+                    );
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlong_line_matches_keep_their_real_columns() {
+        // 4 KB windows overlapping by the pattern's 6 bytes start every 4090
+        // bytes, so the second one starts at 4090 and ends at 8186
+        let mut content = vec![b' '; 40_000];
+        content[9_000..9_006].copy_from_slice(b"needle");
+        content[30_006..30_012].copy_from_slice(b"needle");
+        // Word characters just across window edges
+        content[4_089..4_096].copy_from_slice(b"aneedle");
+        content[8_180..8_187].copy_from_slice(b"needleb");
+        content.extend_from_slice(b"\n");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("one_line.txt");
+        std::fs::write(&path, &content).unwrap();
+
+        for (word_regexp, expected) in [
+            (false, vec![4_090, 8_180, 9_000, 30_006]),
+            (true, vec![9_000, 30_006]),
+        ] {
+            let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+                context_before: 0,
+                context_after: 0,
+                chunk_size: 4096,
+                max_line_bytes: 1024,
+                mmap_threshold: u64::MAX,
+                word_regexp,
+                ..Default::default()
+            });
+            let mut matches = pipeline.search_file(&path, "needle").await.unwrap();
+            // The order the default --sort path prints them in
+            matches.sort_by_key(|m| (m.line_number, m.column_start));
+            let columns: Vec<(usize, usize)> = matches
+                .iter()
+                .map(|m| (m.column_start, m.column_end))
+                .collect();
+            let expected: Vec<(usize, usize)> = expected
+                .into_iter()
+                .map(|start| (start, start + 6))
+                .collect();
+            assert_eq!(columns, expected, "word_regexp={word_regexp}");
+            for m in &matches {
+                assert!(m.excerpt.is_some());
+                let (start, end) = m.line_span();
+                assert_eq!(&m.line[start..end], "needle");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_utf16le_is_decoded_before_matching() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = "first line\nfind the needle here\n";
+        let utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let with_bom = dir.path().join("bom.txt");
+        std::fs::write(&with_bom, [&[0xff, 0xfe][..], &utf16].concat()).unwrap();
+        let without_bom = dir.path().join("plain.txt");
+        std::fs::write(&without_bom, &utf16).unwrap();
+
+        for (path, encoding) in [
+            (&with_bom, Encoding::Auto),
+            (&without_bom, Encoding::Utf16le),
+        ] {
+            let config = StreamingConfig {
+                encoding,
+                context_before: 0,
+                context_after: 0,
+                ..Default::default()
+            };
+            let pipeline = StreamingSearchPipeline::new(config);
+
+            let matches = pipeline.search_file(path, "needle").await.unwrap();
+            assert_eq!(matches.len(), 1, "{encoding:?}");
+            assert_eq!(matches[0].line_number, 2);
+            assert_eq!(matches[0].line, "find the needle here");
+            assert_eq!((matches[0].column_start, matches[0].column_end), (9, 15));
+            assert!(pipeline
+                .search_file_fast_exit(path, "needle")
+                .await
+                .unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compressed_files_are_searched_as_plain_text() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let text = "first line\nsecond has the needle\nthird\nneedle again\n";
+        let gz = dir.path().join("app.log.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(File::create(&gz).unwrap(), Default::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+        let zst = dir.path().join("app.log.zst");
+        std::fs::write(&zst, zstd::encode_all(text.as_bytes(), 0).unwrap()).unwrap();
+
+        for path in [&gz, &zst] {
+            let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+                context_before: 0,
+                context_after: 0,
+                ..Default::default()
+            });
+            let matches = pipeline.search_file(path, "needle").await.unwrap();
+            let found: Vec<_> = matches
+                .iter()
+                .map(|m| (m.line_number, m.line.as_str(), m.column_start))
+                .collect();
+            assert_eq!(
+                found,
+                vec![(2, "second has the needle", 15), (4, "needle again", 0)],
+                "{}",
+                path.display()
+            );
+            assert!(pipeline
+                .search_file_fast_exit(path, "needle")
+                .await
+                .unwrap());
+
+            // Decompressing past the configured size is an error, not a partial result
+            let limited = StreamingSearchPipeline::new(StreamingConfig {
+                archive_limits: crate::archive::ArchiveLimits {
+                    max_decompressed_bytes: 16,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            assert!(limited.search_file(path, "needle").await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fast_exit_ignores_case_and_decompresses() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let text = format!("{}\nthe Needle is here\n", "padding ".repeat(20));
+        let plain = dir.path().join("app.log");
+        std::fs::write(&plain, &text).unwrap();
+        let gz = dir.path().join("app.log.gz");
+        let mut encoder =
+            flate2::write::GzEncoder::new(File::create(&gz).unwrap(), Default::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        for path in [&plain, &gz] {
+            // Small buffers put "Needle" across a buffer boundary
+            let pipeline = |case_sensitive| {
+                StreamingSearchPipeline::new(StreamingConfig {
+                    case_sensitive,
+                    buffer_size: 7,
+                    ..Default::default()
+                })
+            };
+            let found = |case_sensitive, pattern| {
+                let pipeline = pipeline(case_sensitive);
+                let path = path.clone();
+                async move { pipeline.search_file_fast_exit(&path, pattern).await }
+            };
+            assert!(found(false, "NEEDLE").await.unwrap(), "{}", path.display());
+            assert!(found(true, "Needle").await.unwrap(), "{}", path.display());
+            assert!(!found(true, "NEEDLE").await.unwrap(), "{}", path.display());
+            assert!(
+                !found(false, "haystack").await.unwrap(),
+                "{}",
+                path.display()
+            );
+        }
+
+        // A file that decompresses past the limit is an error, not a miss
+        let limited = StreamingSearchPipeline::new(StreamingConfig {
+            archive_limits: crate::archive::ArchiveLimits {
+                max_decompressed_bytes: 16,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        assert!(limited
+            .search_file_fast_exit(&gz, "haystack")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_word_regexp_applies_to_every_algorithm() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        std::fs::write(&path, "the cat sat\ncategory\nbobcat\n").unwrap();
+
+        for algorithm in [
+            SearchAlgorithm::BoyerMoore,
+            SearchAlgorithm::Regex,
+            SearchAlgorithm::Simple,
+            SearchAlgorithm::Simd,
+            SearchAlgorithm::AhoCorasick,
+            SearchAlgorithm::Fuzzy,
+        ] {
+            let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+                algorithm: algorithm.clone(),
+                context_before: 0,
+                context_after: 0,
+                word_regexp: true,
+                ..Default::default()
+            });
+            let matches = pipeline.search_file(&path, "cat").await.unwrap();
+            let lines: Vec<usize> = matches.iter().map(|m| m.line_number).collect();
+            assert!(lines.contains(&1), "{algorithm:?}: {lines:?}");
+            assert!(
+                lines.iter().all(|&line| line == 1),
+                "{algorithm:?}: {lines:?}"
+            );
+            assert!(pipeline.search_file_fast_exit(&path, "cat").await.unwrap());
+            assert!(!pipeline
+                .search_file_fast_exit(&path, "categ")
+                .await
+                .unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mapped_and_streamed_files_match_the_same() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("large.txt");
+        let text: String = (0..5000)
+            .map(|i| match i % 7 {
+                0 => format!("line {i} has a needle and another needle\r\n"),
+                3 => format!("Line {i}: NEEDLE in caps\n"),
+                _ => format!("line {i} is plain\n"),
+            })
+            .collect();
+        std::fs::write(&path, text).unwrap();
+        let utf16 = dir.path().join("utf16.txt");
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(
+            "a needle\nplain\n"
+                .encode_utf16()
+                .flat_map(u16::to_le_bytes),
+        );
+        std::fs::write(&utf16, bytes).unwrap();
+
+        for (algorithm, case_sensitive, invert_match) in [
+            (SearchAlgorithm::BoyerMoore, true, false),
+            (SearchAlgorithm::Regex, false, false),
+            (SearchAlgorithm::Simd, true, true),
+        ] {
+            let search = |mmap_threshold| {
+                StreamingSearchPipeline::new(StreamingConfig {
+                    algorithm: algorithm.clone(),
+                    case_sensitive,
+                    invert_match,
+                    mmap_threshold,
+                    ..Default::default()
+                })
+            };
+            let mapped = search(0);
+            let streamed = search(u64::MAX);
+            for path in [&path, &utf16] {
+                let expected = streamed.search_file(path, "needle").await.unwrap();
+                assert!(!expected.is_empty(), "{algorithm:?} {}", path.display());
+                assert_eq!(
+                    mapped.search_file(path, "needle").await.unwrap(),
+                    expected,
+                    "{algorithm:?} {}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_interrupts_a_slow_regex() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        // One long line that this pattern takes many seconds to scan
+        let words = ["alpha", "beta", "gamma", "delta"];
+        let line: Vec<&str> = (0..20_000).map(|i| words[i * 7 % 4]).collect();
+        std::fs::write(&path, line.join(" ") + "\n").unwrap();
+
+        let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+            algorithm: SearchAlgorithm::Regex,
+            timeout_per_file: Some(1),
+            ..Default::default()
+        });
+        let start = std::time::Instant::now();
+        let matches = pipeline
+            .search_file(&path, r"(?:\w+\s+){100}Z")
+            .await
+            .unwrap();
+        assert!(matches.is_empty());
+        assert!(
+            start.elapsed() < std::time::Duration::from_secs(3),
+            "took {:?}",
+            start.elapsed()
+        );
+    }
+
+    #[test]
+    fn test_is_whole_word() {
+        assert!(is_whole_word("the cat sat", 4, 7));
+        assert!(is_whole_word("cat", 0, 3));
+        assert!(!is_whole_word("category", 0, 3));
+        assert!(!is_whole_word("bob_cat", 4, 7));
+        assert!(is_whole_word("é cat-é", 3, 6));
+        assert!(!is_whole_word("écat", 2, 5));
+    }
+
+    #[tokio::test]
+    async fn test_line_range_also_bounds_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lines.txt");
+        std::fs::write(&path, "a\nb\nneedle\nc\nd\n").unwrap();
+
+        let pipeline = StreamingSearchPipeline::new(StreamingConfig {
+            context_before: 2,
+            context_after: 2,
+            line_range: Some(LineRange {
+                start: 2,
+                end: Some(4),
+            }),
+            ..Default::default()
+        });
+        let matches = pipeline.search_file(&path, "needle").await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].context_before, vec![(2, "b".to_string())]);
+        assert_eq!(matches[0].context_after, vec![(4, "c".to_string())]);
+        assert!(!pipeline.search_file_fast_exit(&path, "a").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_memory_cap_bounds_in_flight_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let line = "filler text that never matches the pattern\n".repeat(24_000);
+        let paths: Vec<PathBuf> = (0..6)
+            .map(|i| {
+                let path = dir.path().join(format!("big{i}.log"));
+                std::fs::write(&path, format!("{line}needle {i}\n{line}")).unwrap();
+                path
+            })
+            .collect();
+        let files: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+
+        let cap = 256 * 1024;
+        let config = StreamingConfig {
+            context_before: 0,
+            context_after: 0,
+            max_memory_bytes: Some(cap),
+            ..Default::default()
+        };
+        let pipeline = StreamingSearchPipeline::new(config);
+
+        let matches = pipeline
+            .search_files_parallel(&files, "needle", 8)
+            .await
+            .unwrap();
+        assert_eq!(matches.len(), paths.len());
+        // Files larger than the cap are streamed instead of read whole
+        for path in &files {
+            assert!(pipeline
+                .search_file_fast_exit(path, "needle")
+                .await
+                .unwrap());
+        }
+        let peak = pipeline.peak_memory_bytes().unwrap();
+        assert!(peak > 0 && peak <= cap, "peak {peak} over cap {cap}");
+    }
 }
@@ -20,8 +20,75 @@ use ratatui::{
     },
     Frame, Terminal,
 };
-use std::io::{self, Stdout};
+use std::io::{self, BufRead, BufReader, Stdout};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Delay after the last keystroke before a live search runs
+const LIVE_SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+/// How often the event loop wakes to check on a running search
+const SEARCH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long the event loop waits for input when nothing is pending
+const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Number of search patterns kept in the history
+const MAX_HISTORY: usize = 100;
+/// Lines shown on each side of the match when context has to be read from disk
+const PREVIEW_CONTEXT_LINES: usize = 5;
+/// Most context lines `+` shows on each side of the matches in the table
+pub const MAX_CONTEXT_LINES: usize = 10;
+
+/// Context lines around a match: (line number, text) before and after it
+type PreviewContext = (Vec<(usize, String)>, Vec<(usize, String)>);
+
+/// Deadline-based debounce timer: each `touch` pushes the deadline back, and
+/// `fire` reports once when it has passed.
+#[derive(Debug, Clone)]
+pub struct Debouncer {
+    delay: Duration,
+    deadline: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            deadline: None,
+        }
+    }
+
+    /// Record activity at `now`, restarting the timer
+    pub fn touch(&mut self, now: Instant) {
+        self.deadline = Some(now + self.delay);
+    }
+
+    /// Drop any pending deadline
+    pub fn cancel(&mut self) {
+        self.deadline = None;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    /// Time left until the deadline, if one is pending
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(now))
+    }
+
+    /// Returns true exactly once after the deadline has passed
+    pub fn fire(&mut self, now: Instant) -> bool {
+        match self.deadline {
+            Some(deadline) if now >= deadline => {
+                self.deadline = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
 
 /// TUI application state
 #[derive(Debug, Clone)]
@@ -36,12 +103,19 @@ pub struct TuiState {
     pub case_sensitive: bool,
     pub context_lines: usize,
     pub show_help: bool,
+    pub show_preview: bool,
     pub status_message: String,
     pub search_in_progress: bool,
     pub scroll_offset: usize,
     pub input_mode: InputMode,
     pub input_buffer: String,
     pub input_cursor: usize,
+    /// Executed search patterns, oldest first
+    pub history: Vec<String>,
+    /// Position in `history` while recalling with Up/Down
+    pub history_index: Option<usize>,
+    /// What was typed before recalling started, restored by moving past the newest entry
+    pub history_draft: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -71,16 +145,105 @@ impl Default for TuiState {
             case_sensitive: false,
             context_lines: 0,
             show_help: false,
+            show_preview: false,
             status_message: "Ready".to_string(),
             search_in_progress: false,
             scroll_offset: 0,
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             input_cursor: 0,
+            history: Vec::new(),
+            history_index: None,
+            history_draft: String::new(),
         }
     }
 }
 
+impl TuiState {
+    /// Remember an executed pattern, skipping consecutive duplicates
+    pub fn push_history(&mut self, pattern: &str) {
+        self.history_index = None;
+        if pattern.is_empty() || pattern.contains('\n') {
+            return;
+        }
+        if self.history.last().map(String::as_str) != Some(pattern) {
+            self.history.push(pattern.to_string());
+        }
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+        }
+    }
+
+    /// Step back to an older pattern; returns whether the input changed
+    pub fn recall_previous(&mut self) -> bool {
+        let index = match self.history_index {
+            None if self.history.is_empty() => return false,
+            None => {
+                self.history_draft = self.input_buffer.clone();
+                self.history.len() - 1
+            }
+            Some(0) => return false,
+            Some(i) => i - 1,
+        };
+        self.history_index = Some(index);
+        self.set_input(self.history[index].clone());
+        true
+    }
+
+    /// Step forward to a newer pattern, ending at what was typed before
+    /// recalling; returns whether the input changed
+    pub fn recall_next(&mut self) -> bool {
+        let Some(i) = self.history_index else {
+            return false;
+        };
+        if i + 1 < self.history.len() {
+            self.history_index = Some(i + 1);
+            self.set_input(self.history[i + 1].clone());
+        } else {
+            self.history_index = None;
+            let draft = std::mem::take(&mut self.history_draft);
+            self.set_input(draft);
+        }
+        true
+    }
+
+    /// Show `delta` more (or fewer) context lines around each match, within
+    /// `0..=MAX_CONTEXT_LINES`; returns whether the amount changed
+    pub fn adjust_context(&mut self, delta: isize) -> bool {
+        let lines = self
+            .context_lines
+            .saturating_add_signed(delta)
+            .min(MAX_CONTEXT_LINES);
+        self.status_message = format!("Context lines: {lines}");
+        if lines == self.context_lines {
+            return false;
+        }
+        self.context_lines = lines;
+        true
+    }
+
+    /// In Regex mode, check that `pattern` compiles; on failure the compiler
+    /// error goes to the status bar and the search should be skipped
+    pub fn validate_pattern(&mut self, pattern: &str) -> bool {
+        if self.search_mode != SearchMode::Regex {
+            return true;
+        }
+        match regex::Regex::new(pattern) {
+            Ok(_) => true,
+            Err(e) => {
+                self.status_message = format!("Invalid regex: {}", regex_error_summary(&e));
+                false
+            }
+        }
+    }
+
+    fn set_input(&mut self, input: String) {
+        self.input_buffer = input;
+        self.input_cursor = self.input_buffer.len();
+    }
+}
+
 /// TUI application
 pub struct TuiApp {
     pub state: TuiState,
@@ -90,11 +253,23 @@ pub struct TuiApp {
     table_state: TableState,
     scrollbar_state: ScrollbarState,
     should_quit: bool,
+    search_debounce: Debouncer,
+    search_task: Option<JoinHandle<Vec<SearchMatch>>>,
+    /// Context read from disk for the last previewed match, keyed by path and line
+    preview_cache: Option<((PathBuf, usize), PreviewContext)>,
+    /// Matches whose `context_before`/`context_after` hold `context_lines`
+    /// lines read from disk; only matches that have been on screen are read
+    context_loaded: std::collections::HashSet<usize>,
+    /// Kept open for the session; on some platforms the copied text is lost
+    /// when the clipboard handle is dropped
+    clipboard: Option<arboard::Clipboard>,
+    /// Where search history is persisted between sessions
+    history_path: Option<PathBuf>,
 }
 
 impl TuiApp {
     pub async fn new() -> RfgrepResult<Self> {
-        let plugin_manager = Arc::new(EnhancedPluginManager::new());
+        let plugin_manager = Arc::new(EnhancedPluginManager::load_saved());
         let registry = PluginRegistry::new(plugin_manager.clone());
 
         registry.load_plugins().await?;
@@ -105,14 +280,29 @@ impl TuiApp {
         let mut table_state = TableState::default();
         table_state.select(Some(0));
 
+        let history_path = dirs::config_dir().map(|dir| dir.join("rfgrep").join("tui_history"));
+        let state = TuiState {
+            history: history_path
+                .as_deref()
+                .map(load_history)
+                .unwrap_or_default(),
+            ..TuiState::default()
+        };
+
         Ok(Self {
-            state: TuiState::default(),
+            state,
             plugin_manager,
             streaming_pipeline: None,
             list_state,
             table_state,
             scrollbar_state: ScrollbarState::default(),
             should_quit: false,
+            search_debounce: Debouncer::new(LIVE_SEARCH_DEBOUNCE),
+            search_task: None,
+            preview_cache: None,
+            context_loaded: std::collections::HashSet::new(),
+            clipboard: None,
+            history_path,
         })
     }
 
@@ -121,17 +311,83 @@ impl TuiApp {
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     ) -> RfgrepResult<()> {
         loop {
+            self.poll_search_task().await;
             terminal.draw(|f| self.ui(f))?;
 
-            if let Event::Key(key) = event::read()? {
-                if self.handle_key_event(key).await? {
-                    break;
+            let timeout = self.search_debounce.remaining(Instant::now()).unwrap_or(
+                if self.search_task.is_some() {
+                    SEARCH_POLL_INTERVAL
+                } else {
+                    IDLE_POLL_INTERVAL
+                },
+            );
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()? {
+                    if self.handle_key_event(key).await? {
+                        break;
+                    }
                 }
             }
+
+            if self.search_debounce.fire(Instant::now()) {
+                self.start_live_search();
+            }
         }
+        self.cancel_search_task();
         Ok(())
     }
 
+    /// Run the pattern being typed on a background task, replacing any search
+    /// that is still in flight
+    fn start_live_search(&mut self) {
+        self.cancel_search_task();
+        let pattern = self.state.input_buffer.clone();
+        self.state.pattern = pattern.clone();
+        if pattern.is_empty() {
+            self.set_matches(Vec::new());
+            self.state.status_message = "Ready".to_string();
+            return;
+        }
+        if !self.state.validate_pattern(&pattern) {
+            return;
+        }
+
+        self.state.search_in_progress = true;
+        self.state.status_message = "searching…".to_string();
+        let plugin_manager = self.plugin_manager.clone();
+        self.search_task = Some(tokio::spawn(async move {
+            collect_matches(&plugin_manager, &pattern).await
+        }));
+    }
+
+    /// Apply the results of the background search once it has finished
+    async fn poll_search_task(&mut self) {
+        if !self.search_task.as_ref().is_some_and(|t| t.is_finished()) {
+            return;
+        }
+        let Some(task) = self.search_task.take() else {
+            return;
+        };
+        self.state.search_in_progress = false;
+        match task.await {
+            Ok(matches) => {
+                self.set_matches(matches);
+                self.state.status_message = "Search completed".to_string();
+            }
+            Err(e) => {
+                self.state.status_message = format!("Search failed: {}", e);
+            }
+        }
+    }
+
+    /// Abort the in-flight background search, if any
+    fn cancel_search_task(&mut self) {
+        if let Some(task) = self.search_task.take() {
+            task.abort();
+            self.state.search_in_progress = false;
+        }
+    }
+
     fn ui(&mut self, f: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -153,7 +409,15 @@ impl TuiApp {
 
     fn render_header(&self, f: &mut Frame, area: Rect) {
         let header_text = if self.state.input_mode == InputMode::Search {
-            format!("rfgrep TUI - Search: {}_", self.state.input_buffer)
+            format!(
+                "rfgrep TUI - Search: {}_{}",
+                self.state.input_buffer,
+                if self.state.search_in_progress {
+                    "  (searching…)"
+                } else {
+                    ""
+                }
+            )
         } else if self.state.search_in_progress {
             format!("rfgrep TUI - Searching for: '{}'...", self.state.pattern)
         } else {
@@ -175,6 +439,19 @@ impl TuiApp {
     fn render_main_content(&mut self, f: &mut Frame, area: Rect) {
         if self.state.matches.is_empty() {
             self.render_empty_state(f, area);
+        } else if self.state.show_preview {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(20),
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(40),
+                ])
+                .split(area);
+
+            self.render_file_list(f, chunks[0]);
+            self.render_matches_table(f, chunks[1]);
+            self.render_preview(f, chunks[2]);
         } else {
             let chunks = Layout::default()
                 .direction(Direction::Horizontal)
@@ -186,6 +463,68 @@ impl TuiApp {
         }
     }
 
+    fn render_preview(&mut self, f: &mut Frame, area: Rect) {
+        let Some(current) = self
+            .state
+            .matches
+            .get(self.state.current_match_index)
+            .cloned()
+        else {
+            return;
+        };
+
+        let (before, after) = self.preview_context(&current);
+        let preview = Paragraph::new(Text::from(format_preview(&current, &before, &after))).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Preview: {}", current.path.display())),
+        );
+
+        f.render_widget(preview, area);
+    }
+
+    /// Read the context lines of match `index` from its file, unless they're
+    /// already there at the current size
+    fn load_context(&mut self, index: usize) {
+        if !self.context_loaded.insert(index) {
+            return;
+        }
+        let lines = self.state.context_lines;
+        let m = &mut self.state.matches[index];
+        (m.context_before, m.context_after) = read_context(&m.path, m.line_number, lines);
+    }
+
+    /// Show more or fewer context lines in the matches table; context is
+    /// read again, at the new size, as matches come into view
+    fn adjust_context(&mut self, delta: isize) {
+        if !self.state.adjust_context(delta) {
+            return;
+        }
+        for index in self.context_loaded.drain() {
+            let m = &mut self.state.matches[index];
+            m.context_before = Vec::new();
+            m.context_after = Vec::new();
+        }
+    }
+
+    /// Context around a match, falling back to reading the file when the
+    /// search didn't capture any
+    fn preview_context(&mut self, m: &SearchMatch) -> PreviewContext {
+        if !m.context_before.is_empty() || !m.context_after.is_empty() {
+            return (m.context_before.clone(), m.context_after.clone());
+        }
+
+        let key = (m.path.clone(), m.line_number);
+        if let Some((cached_key, context)) = &self.preview_cache {
+            if *cached_key == key {
+                return context.clone();
+            }
+        }
+        let context = read_context(&m.path, m.line_number, PREVIEW_CONTEXT_LINES);
+        self.preview_cache = Some((key, context.clone()));
+        context
+    }
+
     fn render_empty_state(&self, f: &mut Frame, area: Rect) {
         let empty_text = if self.state.pattern.is_empty() {
             "Enter a search pattern to begin..."
@@ -231,35 +570,42 @@ impl TuiApp {
     }
 
     fn render_matches_table(&mut self, f: &mut Frame, area: Rect) {
-        let matches = &self.state.matches;
+        let height = (area.height as usize).saturating_sub(2);
         let start_idx = self.state.scroll_offset;
-        let end_idx = (start_idx + (area.height as usize).saturating_sub(2)).min(matches.len());
-
-        let rows: Vec<Row> = matches[start_idx..end_idx]
-            .iter()
-            .enumerate()
-            .map(|(i, m)| {
-                let line_num = format!("{:<4}", m.line_number);
-                let content = if m.line.len() > 80 {
-                    format!("{}...", &m.line[..77])
-                } else {
-                    m.line.clone()
-                };
-
-                let style = if start_idx + i == self.state.current_match_index {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default()
-                };
+        let show_context = self.state.context_lines > 0;
+        let context_style = Style::default().fg(Color::DarkGray);
+
+        // Each match takes a row, plus one per context line around it
+        let mut rows: Vec<Row> = Vec::new();
+        let mut index = start_idx;
+        while rows.len() < height && index < self.state.matches.len() {
+            if show_context {
+                self.load_context(index);
+            }
+            let m = &self.state.matches[index];
+            let style = if index == self.state.current_match_index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
 
-                Row::new(vec![
-                    Cell::from(Span::styled(line_num, style)),
-                    Cell::from(Span::styled(content, style)),
-                ])
-            })
-            .collect();
+            if show_context {
+                for (number, line) in &m.context_before {
+                    rows.push(table_row(*number, line, context_style));
+                }
+            }
+            rows.push(table_row(m.line_number, &m.line, style));
+            if show_context {
+                for (number, line) in &m.context_after {
+                    rows.push(table_row(*number, line, context_style));
+                }
+            }
+            index += 1;
+        }
+        rows.truncate(height);
+        let matches = &self.state.matches;
 
         let table = Table::new(rows, &[Constraint::Length(6), Constraint::Min(0)])
             .block(Block::default().borders(Borders::ALL).title("Matches"))
@@ -267,7 +613,7 @@ impl TuiApp {
 
         f.render_stateful_widget(table, area, &mut self.table_state);
 
-        if matches.len() > (area.height as usize).saturating_sub(2) {
+        if matches.len() > height {
             let scrollbar = Scrollbar::default()
                 .orientation(ScrollbarOrientation::VerticalRight)
                 .begin_symbol(Some("↑"))
@@ -286,7 +632,7 @@ impl TuiApp {
 
     fn render_status_bar(&self, f: &mut Frame, area: Rect) {
         let status_text = if self.state.search_in_progress {
-            "Searching...".to_string()
+            "searching…".to_string()
         } else {
             format!(
                 "Matches: {} | Files: {} | Mode: {:?} | Algorithm: {:?} | Case: {}",
@@ -324,12 +670,16 @@ impl TuiApp {
             "  n           - Next match",
             "  N           - Previous match",
             "  Enter       - Open file in editor",
+            "  p           - Toggle preview pane",
+            "  y           - Copy matched line",
+            "  Y           - Copy path:line",
             "",
             "Search Input Mode:",
-            "  Type pattern - Enter search pattern",
+            "  Type pattern - Search live as you type",
             "  Enter       - Execute search",
             "  Esc         - Cancel search",
             "  ←/→         - Move cursor",
+            "  ↑/↓         - Recall previous searches",
             "  Home/End    - Jump to start/end",
             "",
             "Settings:",
@@ -337,6 +687,7 @@ impl TuiApp {
             "  m           - Change search mode",
             "  a           - Change algorithm",
             "  r           - Refresh search",
+            "  +/-         - Show more/less context per match",
             "",
             "Other:",
             "  h           - Toggle this help",
@@ -405,12 +756,31 @@ impl TuiApp {
             KeyCode::Char('m') => {
                 self.cycle_search_mode();
             }
+            KeyCode::Char('y') => {
+                self.yank_current_match(false);
+            }
+            KeyCode::Char('Y') => {
+                self.yank_current_match(true);
+            }
+            KeyCode::Char('p') => {
+                self.state.show_preview = !self.state.show_preview;
+                self.state.status_message = format!(
+                    "Preview: {}",
+                    if self.state.show_preview { "ON" } else { "OFF" }
+                );
+            }
             KeyCode::Char('a') => {
                 self.cycle_algorithm();
             }
             KeyCode::Char('r') => {
                 self.refresh_search().await?;
             }
+            KeyCode::Char('+') => {
+                self.adjust_context(1);
+            }
+            KeyCode::Char('-') => {
+                self.adjust_context(-1);
+            }
             KeyCode::Char('/') => {
                 self.enter_search_input_mode();
             }
@@ -493,6 +863,28 @@ impl TuiApp {
         }
     }
 
+    /// Copy the selected match's line, or its `path:line`, to the clipboard
+    fn yank_current_match(&mut self, location: bool) {
+        let Some(current) = self.state.matches.get(self.state.current_match_index) else {
+            self.state.status_message = "Nothing to copy".to_string();
+            return;
+        };
+        let text = yank_text(current, location);
+
+        let copied = match self.clipboard.as_mut() {
+            Some(clipboard) => clipboard.set_text(text.clone()),
+            None => arboard::Clipboard::new().and_then(|mut clipboard| {
+                clipboard.set_text(text.clone())?;
+                self.clipboard = Some(clipboard);
+                Ok(())
+            }),
+        };
+        self.state.status_message = match copied {
+            Ok(()) => format!("Copied: {}", text),
+            Err(e) => format!("Clipboard unavailable: {}", e),
+        };
+    }
+
     fn cycle_search_mode(&mut self) {
         self.state.search_mode = match self.state.search_mode {
             SearchMode::Text => SearchMode::Word,
@@ -507,7 +899,9 @@ impl TuiApp {
             SearchAlgorithm::BoyerMoore => SearchAlgorithm::Regex,
             SearchAlgorithm::Regex => SearchAlgorithm::Simple,
             SearchAlgorithm::Simple => SearchAlgorithm::Simd,
-            SearchAlgorithm::Simd => SearchAlgorithm::BoyerMoore,
+            SearchAlgorithm::Simd | SearchAlgorithm::AhoCorasick | SearchAlgorithm::Fuzzy => {
+                SearchAlgorithm::BoyerMoore
+            }
         };
         self.state.status_message = format!("Algorithm: {:?}", self.state.algorithm);
     }
@@ -553,28 +947,16 @@ impl TuiApp {
             self.state.status_message = "No pattern to search".to_string();
             return Ok(());
         }
+        let pattern = self.state.pattern.clone();
+        if !self.state.validate_pattern(&pattern) {
+            return Ok(());
+        }
 
+        self.cancel_search_task();
         self.state.search_in_progress = true;
         self.state.status_message = "Searching...".to_string();
 
-        use crate::walker::walk_dir;
-        use std::path::Path;
-
-        let pattern = self.state.pattern.clone();
-        let mut all_matches: Vec<SearchMatch> = Vec::new();
-        let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-
-        let entries: Vec<_> = walk_dir(Path::new(&cwd), true, false).collect();
-        for entry in entries {
-            let path = entry.path();
-            if path.is_file() {
-                let res = self.plugin_manager.search_file(path, &pattern).await;
-                if let Ok(mut matches) = res {
-                    all_matches.append(&mut matches);
-                }
-            }
-        }
-
+        let all_matches = collect_matches(&self.plugin_manager, &pattern).await;
         self.set_matches(all_matches);
 
         self.state.search_in_progress = false;
@@ -589,6 +971,7 @@ impl TuiApp {
 
     pub fn set_matches(&mut self, matches: Vec<SearchMatch>) {
         self.state.matches = matches;
+        self.context_loaded.clear();
         self.state.current_match_index = 0;
         self.state.current_file_index = 0;
         self.state.scroll_offset = 0;
@@ -606,6 +989,7 @@ impl TuiApp {
         self.state.input_mode = InputMode::Search;
         self.state.input_buffer = self.state.pattern.clone();
         self.state.input_cursor = self.state.input_buffer.len();
+        self.state.history_index = None;
         self.state.status_message =
             "Enter search pattern (Enter to search, Esc to cancel)".to_string();
     }
@@ -624,7 +1008,9 @@ impl TuiApp {
         match key.code {
             KeyCode::Enter => {
                 // Apply the search
+                self.search_debounce.cancel();
                 self.state.pattern = self.state.input_buffer.clone();
+                self.record_history();
                 self.state.input_mode = InputMode::Normal;
                 self.state.input_buffer.clear();
                 self.state.input_cursor = 0;
@@ -634,6 +1020,8 @@ impl TuiApp {
             }
             KeyCode::Esc => {
                 // Cancel search input
+                self.search_debounce.cancel();
+                self.cancel_search_task();
                 self.state.input_mode = InputMode::Normal;
                 self.state.input_buffer.clear();
                 self.state.input_cursor = 0;
@@ -644,12 +1032,14 @@ impl TuiApp {
                 if self.state.input_cursor > 0 {
                     self.state.input_cursor -= 1;
                     self.state.input_buffer.remove(self.state.input_cursor);
+                    self.input_changed();
                 }
                 Ok(false)
             }
             KeyCode::Delete => {
                 if self.state.input_cursor < self.state.input_buffer.len() {
                     self.state.input_buffer.remove(self.state.input_cursor);
+                    self.input_changed();
                 }
                 Ok(false)
             }
@@ -665,6 +1055,18 @@ impl TuiApp {
                 }
                 Ok(false)
             }
+            KeyCode::Up => {
+                if self.state.input_mode == InputMode::Search && self.state.recall_previous() {
+                    self.input_changed();
+                }
+                Ok(false)
+            }
+            KeyCode::Down => {
+                if self.state.input_mode == InputMode::Search && self.state.recall_next() {
+                    self.input_changed();
+                }
+                Ok(false)
+            }
             KeyCode::Home => {
                 self.state.input_cursor = 0;
                 Ok(false)
@@ -676,12 +1078,33 @@ impl TuiApp {
             KeyCode::Char(c) => {
                 self.state.input_buffer.insert(self.state.input_cursor, c);
                 self.state.input_cursor += 1;
+                self.input_changed();
                 Ok(false)
             }
             _ => Ok(false),
         }
     }
 
+    /// Add the executed pattern to the history and persist it
+    fn record_history(&mut self) {
+        let pattern = self.state.pattern.clone();
+        self.state.push_history(&pattern);
+        if let Some(path) = &self.history_path {
+            if let Err(e) = save_history(path, &self.state.history) {
+                log::debug!("Could not save search history to {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Restart the live-search timer after an edit; a search still running
+    /// for the previous text is stale and gets cancelled
+    fn input_changed(&mut self) {
+        if self.state.input_mode == InputMode::Search {
+            self.cancel_search_task();
+            self.search_debounce.touch(Instant::now());
+        }
+    }
+
     /// Handle command input mode (for future use)
     async fn handle_command_input(&mut self, key: KeyEvent) -> RfgrepResult<bool> {
         match key.code {
@@ -708,6 +1131,156 @@ impl TuiApp {
     }
 }
 
+/// Search every file under the current directory with the loaded plugins
+async fn collect_matches(
+    plugin_manager: &EnhancedPluginManager,
+    pattern: &str,
+) -> Vec<SearchMatch> {
+    use crate::walker::walk_dir;
+    use std::path::Path;
+
+    let mut all_matches: Vec<SearchMatch> = Vec::new();
+    let cwd = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+
+    let paths: Vec<_> = walk_dir(Path::new(&cwd), true, false)
+        .map(|entry| entry.into_path())
+        .collect();
+    for path in paths {
+        if path.is_file() {
+            if let Ok(mut matches) = plugin_manager.search_file(&path, pattern).await {
+                all_matches.append(&mut matches);
+            }
+        }
+    }
+    all_matches
+}
+
+/// The regex crate's errors span several lines with a caret diagram; the
+/// status bar only has room for the final description
+fn regex_error_summary(error: &regex::Error) -> String {
+    let message = error.to_string();
+    message
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.trim().trim_start_matches("error: ").to_string())
+        .unwrap_or(message)
+}
+
+/// Read persisted search history, one pattern per line
+fn load_history(path: &Path) -> Vec<String> {
+    let mut state = TuiState::default();
+    if let Ok(contents) = std::fs::read_to_string(path) {
+        for line in contents.lines() {
+            state.push_history(line);
+        }
+    }
+    state.history
+}
+
+fn save_history(path: &Path, history: &[String]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut contents = history.join("\n");
+    contents.push('\n');
+    std::fs::write(path, contents)
+}
+
+/// Text copied by `y` (the matched line) or `Y` (`path:line`)
+fn yank_text(m: &SearchMatch, location: bool) -> String {
+    if location {
+        format!("{}:{}", m.path.display(), m.line_number)
+    } else {
+        m.line.trim_end_matches(['\n', '\r']).to_string()
+    }
+}
+
+/// A matches table row: the line number, then the line cut to 80 characters
+fn table_row(line_number: usize, line: &str, style: Style) -> Row<'static> {
+    let content = if line.chars().count() > 80 {
+        format!("{}...", line.chars().take(77).collect::<String>())
+    } else {
+        line.to_string()
+    };
+    Row::new(vec![
+        Cell::from(Span::styled(format!("{line_number:<4}"), style)),
+        Cell::from(Span::styled(content, style)),
+    ])
+}
+
+/// Read up to `radius` lines on each side of the 1-based `line_number`
+fn read_context(path: &Path, line_number: usize, radius: usize) -> PreviewContext {
+    let Ok(file) = std::fs::File::open(path) else {
+        return (Vec::new(), Vec::new());
+    };
+    let first = line_number.saturating_sub(radius).max(1);
+    let last = line_number + radius;
+
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    for (number, line) in BufReader::new(file)
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .skip(first - 1)
+        .take_while(|(number, _)| *number <= last)
+    {
+        let Ok(line) = line else { break };
+        if number < line_number {
+            before.push((number, line));
+        } else if number > line_number {
+            after.push((number, line));
+        }
+    }
+    (before, after)
+}
+
+/// Lay out the preview block: numbered context lines with the match line
+/// marked and its matched text highlighted
+fn format_preview(
+    m: &SearchMatch,
+    before: &[(usize, String)],
+    after: &[(usize, String)],
+) -> Vec<Line<'static>> {
+    let gutter = |number: usize, marker: char| format!("{:>5} {} ", number, marker);
+    let context_line = |(number, text): &(usize, String)| {
+        Line::from(vec![
+            Span::styled(gutter(*number, '|'), Style::default().fg(Color::DarkGray)),
+            Span::raw(text.clone()),
+        ])
+    };
+
+    let line = &m.line;
+    let mut start = m.column_start.min(line.len());
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = m.column_end.clamp(start, line.len());
+    while !line.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut lines: Vec<Line<'static>> = before.iter().map(context_line).collect();
+    lines.push(Line::from(vec![
+        Span::styled(
+            gutter(m.line_number, '>'),
+            Style::default().fg(Color::Yellow),
+        ),
+        Span::raw(line[..start].to_string()),
+        Span::styled(
+            line[start..end].to_string(),
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(line[end..].to_string()),
+    ]));
+    lines.extend(after.iter().map(context_line));
+    lines
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -761,3 +1334,252 @@ pub fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Rf
     terminal.show_cursor()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debouncer_fires_once_after_the_last_touch() {
+        let start = Instant::now();
+        let mut debounce = Debouncer::new(Duration::from_millis(150));
+        assert!(!debounce.fire(start + Duration::from_secs(1)));
+
+        debounce.touch(start);
+        assert!(!debounce.fire(start + Duration::from_millis(100)));
+        // A second keystroke pushes the deadline back
+        debounce.touch(start + Duration::from_millis(100));
+        assert!(!debounce.fire(start + Duration::from_millis(200)));
+        assert_eq!(
+            debounce.remaining(start + Duration::from_millis(200)),
+            Some(Duration::from_millis(50))
+        );
+
+        assert!(debounce.fire(start + Duration::from_millis(250)));
+        assert!(!debounce.is_pending());
+        assert!(!debounce.fire(start + Duration::from_millis(300)));
+    }
+
+    fn sample_match(line: &str, column_start: usize, column_end: usize) -> SearchMatch {
+        SearchMatch {
+            path: PathBuf::from("src/lib.rs"),
+            line_number: 3,
+            line: line.to_string(),
+            matched_text: line[column_start..column_end].to_string(),
+            column_start,
+            column_end,
+            ..Default::default()
+        }
+    }
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn preview_marks_the_match_between_its_context() {
+        let m = sample_match("let needle = 1;", 4, 10);
+        let before = vec![(1, "fn main() {".to_string()), (2, String::new())];
+        let after = vec![(4, "}".to_string())];
+
+        let lines = format_preview(&m, &before, &after);
+        let text: Vec<String> = lines.iter().map(line_text).collect();
+        assert_eq!(
+            text,
+            vec![
+                "    1 | fn main() {",
+                "    2 | ",
+                "    3 > let needle = 1;",
+                "    4 | }",
+            ]
+        );
+
+        let highlighted: Vec<&str> = lines[2]
+            .spans
+            .iter()
+            .filter(|s| s.style.bg == Some(Color::Yellow))
+            .map(|s| s.content.as_ref())
+            .collect();
+        assert_eq!(highlighted, vec!["needle"]);
+    }
+
+    #[test]
+    fn preview_clamps_columns_to_the_line() {
+        let m = SearchMatch {
+            column_end: 99,
+            ..sample_match("héllo", 1, 3)
+        };
+        let lines = format_preview(&m, &[], &[]);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(line_text(&lines[0]), "    3 > héllo");
+    }
+
+    #[test]
+    fn yank_copies_the_line_or_its_location() {
+        let m = sample_match("let needle = 1;\r\n", 4, 10);
+        assert_eq!(yank_text(&m, false), "let needle = 1;");
+        assert_eq!(yank_text(&m, true), "src/lib.rs:3");
+    }
+
+    #[test]
+    fn read_context_returns_lines_around_the_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        std::fs::write(&path, "one\ntwo\nthree\nfour\nfive\n").unwrap();
+
+        let (before, after) = read_context(&path, 2, 2);
+        assert_eq!(before, vec![(1, "one".to_string())]);
+        assert_eq!(
+            after,
+            vec![(3, "three".to_string()), (4, "four".to_string())]
+        );
+    }
+
+    fn state_with_history(history: &[&str]) -> TuiState {
+        TuiState {
+            history: history.iter().map(|p| p.to_string()).collect(),
+            ..TuiState::default()
+        }
+    }
+
+    #[test]
+    fn recall_walks_history_and_restores_the_draft() {
+        let mut state = state_with_history(&["alpha", "beta"]);
+        state.input_buffer = "gam".to_string();
+
+        assert!(state.recall_previous());
+        assert_eq!(state.input_buffer, "beta");
+        assert_eq!(state.input_cursor, 4);
+        assert!(state.recall_previous());
+        assert_eq!(state.input_buffer, "alpha");
+        // Already at the oldest entry
+        assert!(!state.recall_previous());
+        assert_eq!(state.input_buffer, "alpha");
+
+        assert!(state.recall_next());
+        assert_eq!(state.input_buffer, "beta");
+        assert!(state.recall_next());
+        assert_eq!(state.input_buffer, "gam");
+        assert_eq!(state.input_cursor, 3);
+        assert!(!state.recall_next());
+    }
+
+    #[test]
+    fn recall_with_empty_history_leaves_input_alone() {
+        let mut state = state_with_history(&[]);
+        state.input_buffer = "typed".to_string();
+        assert!(!state.recall_previous());
+        assert!(!state.recall_next());
+        assert_eq!(state.input_buffer, "typed");
+    }
+
+    #[test]
+    fn history_skips_consecutive_duplicates_and_is_capped() {
+        let mut state = state_with_history(&[]);
+        state.push_history("a");
+        state.push_history("a");
+        state.push_history("b");
+        state.push_history("a");
+        state.push_history("");
+        assert_eq!(state.history, vec!["a", "b", "a"]);
+
+        for i in 0..MAX_HISTORY + 10 {
+            state.push_history(&i.to_string());
+        }
+        assert_eq!(state.history.len(), MAX_HISTORY);
+        assert_eq!(state.history[0], "10");
+    }
+
+    #[test]
+    fn history_round_trips_through_its_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rfgrep").join("tui_history");
+        assert!(load_history(&path).is_empty());
+
+        let history = vec!["foo".to_string(), "ba r".to_string()];
+        save_history(&path, &history).unwrap();
+        assert_eq!(load_history(&path), history);
+    }
+
+    #[test]
+    fn invalid_regex_reports_the_compiler_error() {
+        let mut state = TuiState {
+            search_mode: SearchMode::Regex,
+            ..TuiState::default()
+        };
+        assert!(!state.validate_pattern("foo(bar"));
+        assert_eq!(state.status_message, "Invalid regex: unclosed group");
+
+        assert!(state.validate_pattern("foo(bar)"));
+        state.search_mode = SearchMode::Text;
+        assert!(state.validate_pattern("foo(bar"));
+    }
+
+    #[tokio::test]
+    async fn invalid_regex_keeps_previous_results() {
+        let mut app = TuiApp::new().await.unwrap();
+        app.set_matches(vec![sample_match("let needle = 1;", 4, 10)]);
+        app.state.search_mode = SearchMode::Regex;
+        app.set_pattern("needle[".to_string());
+
+        app.refresh_search().await.unwrap();
+        assert!(app.state.status_message.starts_with("Invalid regex:"));
+        assert!(!app.state.search_in_progress);
+        assert_eq!(app.state.matches.len(), 1);
+    }
+
+    #[test]
+    fn context_adjustment_is_clamped() {
+        let mut state = TuiState::default();
+        assert!(!state.adjust_context(-1));
+        assert_eq!(state.context_lines, 0);
+        assert!(state.adjust_context(1));
+        assert_eq!(state.status_message, "Context lines: 1");
+
+        assert!(state.adjust_context(100));
+        assert_eq!(state.context_lines, MAX_CONTEXT_LINES);
+        assert!(!state.adjust_context(1));
+        assert!(state.adjust_context(-1));
+        assert_eq!(state.context_lines, MAX_CONTEXT_LINES - 1);
+    }
+
+    #[tokio::test]
+    async fn context_is_read_lazily_at_the_current_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        std::fs::write(&path, "one\ntwo\nthree\nfour\nfive\n").unwrap();
+
+        let mut app = TuiApp::new().await.unwrap();
+        app.set_matches(vec![SearchMatch {
+            path: path.clone(),
+            line_number: 3,
+            line: "three".to_string(),
+            ..Default::default()
+        }]);
+        app.adjust_context(1);
+        assert!(app.state.matches[0].context_before.is_empty());
+
+        app.load_context(0);
+        assert_eq!(
+            app.state.matches[0].context_before,
+            vec![(2, "two".to_string())]
+        );
+        app.adjust_context(1);
+        assert!(app.state.matches[0].context_after.is_empty());
+        app.load_context(0);
+        assert_eq!(
+            app.state.matches[0].context_after,
+            vec![(4, "four".to_string()), (5, "five".to_string())]
+        );
+    }
+
+    #[test]
+    fn debouncer_cancel_drops_the_pending_deadline() {
+        let start = Instant::now();
+        let mut debounce = Debouncer::new(Duration::from_millis(150));
+        debounce.touch(start);
+        debounce.cancel();
+        assert_eq!(debounce.remaining(start), None);
+        assert!(!debounce.fire(start + Duration::from_secs(1)));
+    }
+}
@@ -1,5 +1,7 @@
 use ignore::{DirEntry, WalkBuilder};
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone)]
 pub struct WalkerOptions {
@@ -13,6 +15,10 @@ pub struct WalkerOptions {
     pub max_depth: Option<usize>,
     pub follow_links: bool,
     pub overrides: Vec<String>,
+    /// Directory names that are pruned instead of descended into
+    pub ignore_directories: Vec<String>,
+    /// Apply `.rfgrepignore` files (gitignore syntax) whatever the git settings
+    pub respect_rfgrepignore: bool,
 }
 
 impl Default for WalkerOptions {
@@ -28,10 +34,73 @@ impl Default for WalkerOptions {
             max_depth: None,
             follow_links: false,
             overrides: Vec::new(),
+            ignore_directories: Vec::new(),
+            respect_rfgrepignore: true,
         }
     }
 }
 
+/// A file found by the walker, with the metadata it was stat-ed for once
+///
+/// Filtering, size limits and sorting all read `metadata` rather than
+/// calling `Path::metadata` again, so a large tree costs one stat per file.
+/// Binary detection reads the file's head, so it's likewise done at most once.
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub metadata: std::fs::Metadata,
+    binary: std::sync::OnceLock<bool>,
+}
+
+impl FileEntry {
+    /// Stat the file at `path`
+    pub fn from_path(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let metadata = std::fs::metadata(&path)?;
+        Ok(Self {
+            path,
+            metadata,
+            binary: std::sync::OnceLock::new(),
+        })
+    }
+
+    /// The entry's metadata, or `None` if it can't be read
+    ///
+    /// The walker reuses what it read while descending where it can. A
+    /// symlink is stat-ed through, so it describes the file it points to.
+    pub fn from_dir_entry(entry: &DirEntry) -> Option<Self> {
+        let metadata = if entry.path_is_symlink() {
+            std::fs::metadata(entry.path()).ok()?
+        } else {
+            entry.metadata().ok()?
+        };
+        Some(Self {
+            path: entry.path().to_path_buf(),
+            metadata,
+            binary: std::sync::OnceLock::new(),
+        })
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.metadata.is_file()
+    }
+
+    /// Whether [`processor::is_binary`](crate::processor::is_binary) classifies
+    /// the file as binary, remembered after the first call
+    pub fn is_binary(&self) -> bool {
+        *self
+            .binary
+            .get_or_init(|| crate::processor::is_binary(&self.path))
+    }
+}
+
+/// Regular files under `path`, each stat-ed once
+pub fn walk_files(path: &Path, options: WalkerOptions) -> impl Iterator<Item = FileEntry> {
+    walk_dir_with_options(path, options)
+        .filter_map(|entry| FileEntry::from_dir_entry(&entry))
+        .filter(FileEntry::is_file)
+}
+
 pub fn walk_dir(path: &Path, recursive: bool, show_hidden: bool) -> impl Iterator<Item = DirEntry> {
     let options = WalkerOptions {
         recursive,
@@ -44,6 +113,8 @@ pub fn walk_dir(path: &Path, recursive: bool, show_hidden: bool) -> impl Iterato
         max_depth: if recursive { None } else { Some(1) },
         follow_links: false,
         overrides: Vec::new(),
+        ignore_directories: Vec::new(),
+        respect_rfgrepignore: !show_hidden,
     };
     walk_dir_with_options(path, options)
 }
@@ -61,9 +132,45 @@ pub fn walk_dir_with_options(
         .git_ignore(options.respect_gitignore)
         .git_exclude(options.respect_git_exclude)
         .ignore(options.respect_gitignore) // .ignore files usually serve same purpose
-        .parents(options.respect_gitignore) // look for .gitignore in parent dirs
+        // look for ignore files in parent dirs, so a project root's apply to subdirectories
+        .parents(options.respect_gitignore || options.respect_rfgrepignore)
         .max_depth(options.max_depth)
         .follow_links(options.follow_links);
+    if options.respect_rfgrepignore {
+        builder.add_custom_ignore_filename(".rfgrepignore");
+    }
+
+    if options.follow_links || !options.ignore_directories.is_empty() {
+        let ignore_directories: HashSet<std::ffi::OsString> = options
+            .ignore_directories
+            .iter()
+            .map(std::ffi::OsString::from)
+            .collect();
+        let follow_links = options.follow_links;
+        // Followed links can reach the same directory or file along several
+        // paths (or loop back on themselves), so every entry is keyed by its
+        // canonical path and anything already seen is pruned
+        let visited: Arc<Mutex<HashSet<PathBuf>>> = Arc::default();
+        builder.filter_entry(move |entry| {
+            // The search root itself is never pruned, even if its name is ignored
+            let ignored_dir = entry.depth() > 0
+                && entry.file_type().is_some_and(|ft| ft.is_dir())
+                && ignore_directories.contains(entry.file_name());
+            if ignored_dir {
+                return false;
+            }
+            if !follow_links {
+                return true;
+            }
+            match entry.path().canonicalize() {
+                Ok(canonical) => visited
+                    .lock()
+                    .map(|mut visited| visited.insert(canonical))
+                    .unwrap_or(true),
+                Err(_) => true,
+            }
+        });
+    }
 
     // Explicitly handle .git directory searching if requested, otherwise default ignore logic usually skips it
     // But 'ignore' crate skips .git by default if hidden() is true (default).
@@ -80,3 +187,40 @@ pub fn walk_dir_with_options(
 
     builder.build().filter_map(Result::ok)
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn test_follow_links_survives_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a");
+        let b = dir.path().join("b");
+        fs::create_dir(&a).unwrap();
+        fs::create_dir(&b).unwrap();
+        fs::write(a.join("x.txt"), "x").unwrap();
+        fs::write(b.join("y.txt"), "y").unwrap();
+        // a/to_b -> b and b/to_a -> a form a cycle
+        symlink(&b, a.join("to_b")).unwrap();
+        symlink(&a, b.join("to_a")).unwrap();
+
+        let options = WalkerOptions {
+            follow_links: true,
+            ..Default::default()
+        };
+        let mut files: Vec<PathBuf> = walk_dir_with_options(dir.path(), options)
+            .filter(|entry| entry.path().is_file())
+            .map(|entry| entry.path().canonicalize().unwrap())
+            .collect();
+        files.sort();
+
+        let expected = vec![
+            a.join("x.txt").canonicalize().unwrap(),
+            b.join("y.txt").canonicalize().unwrap(),
+        ];
+        assert_eq!(files, expected);
+    }
+}
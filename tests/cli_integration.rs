@@ -1,7 +1,7 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn target_debug() -> PathBuf {
     let mut p = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"));
@@ -135,7 +135,8 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--")
         .arg("bench_data")
         .assert()
-        .success();
+        // Nothing matches, so this exits 1 like grep
+        .code(1);
     assert!(fs::metadata(log_search).is_ok());
     let _ = fs::remove_file(log_search);
 
@@ -160,7 +161,7 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--path-flag")
         .arg("bench_data")
         .assert()
-        .success();
+        .code(1);
 
     // 12: Global Option --path with list
     Command::new(&bin)
@@ -179,7 +180,7 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--")
         .arg("bench_data")
         .assert()
-        .success();
+        .code(1);
 
     // 14: Search Command --mode word
     Command::new(&bin)
@@ -200,7 +201,7 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--")
         .arg("bench_data")
         .assert()
-        .success();
+        .code(1);
 
     // 16: List Command --skip-binary
     Command::new(&bin)
@@ -213,3 +214,2313 @@ fn run_readme_examples() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn text_output_contains_only_header_and_matches() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("notes.txt");
+    fs::write(&file, "hello world\nnothing here\nworld again\n")?;
+
+    let output = Command::new(target_debug())
+        .arg("search")
+        .arg("world")
+        .arg("--output-format")
+        .arg("text")
+        .arg("--")
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout)?;
+    let file_prefix = format!("{}:", file.display());
+    let mut match_lines = 0;
+    for line in stdout.lines().filter(|l| !l.is_empty()) {
+        if line.starts_with(&file_prefix) {
+            match_lines += 1;
+        } else {
+            assert!(
+                line.starts_with("Query: ")
+                    || line.starts_with("Path: ")
+                    || line.starts_with("Total matches: ")
+                    || line.contains(&file.display().to_string()),
+                "unexpected line in text output: {line:?}"
+            );
+        }
+    }
+    assert_eq!(match_lines, 2, "stdout was:\n{stdout}");
+
+    Ok(())
+}
+
+#[test]
+fn multiple_patterns_match_any() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("log.txt");
+    fs::write(
+        &file,
+        "foo here\nbar there\nfoobar both\na(b literal\nnone\n",
+    )?;
+    let prefix = format!("{}:", file.display());
+
+    let match_locations = |args: &[&str]| -> Vec<String> {
+        let output = Command::new(target_debug())
+            .arg("search")
+            .args(args)
+            .arg("--")
+            .arg(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{args:?} failed");
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .filter_map(|l| l.strip_prefix(&prefix))
+            .map(|l| l.split(": ").next().unwrap().to_string())
+            .collect()
+    };
+
+    // Positional pattern and -e values are merged
+    assert_eq!(
+        match_locations(&["foo", "-e", "bar"]),
+        vec!["1:1", "2:1", "3:1", "3:4"]
+    );
+    // Text mode treats every pattern literally, even ones that aren't valid regex
+    assert_eq!(
+        match_locations(&["-e", "a(b", "-e", "none"]),
+        vec!["4:1", "5:1"]
+    );
+    // Overlapping hits on one line are reported once
+    assert_eq!(match_locations(&["-e", "foob", "-e", "oba"]), vec!["3:1"]);
+    // Regex mode joins the patterns into one alternation
+    assert_eq!(
+        match_locations(&["-e", "fo+ ", "-e", "^ba[r]", "--mode", "regex"]),
+        vec!["1:1", "2:1"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fixed_strings_matches_literally() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("dots.txt");
+    fs::write(&file, "axb\na.b\n")?;
+    let prefix = format!("{}:", file.display());
+
+    // -F wins over --mode regex
+    for extra in [
+        &[][..],
+        &["--mode", "regex"][..],
+        &["--algorithm", "regex"][..],
+    ] {
+        let output = Command::new(target_debug())
+            .arg("search")
+            .arg("-F")
+            .arg("a.b")
+            .args(extra)
+            .arg("--")
+            .arg(dir.path())
+            .output()?;
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout)?;
+        let lines: Vec<&str> = stdout
+            .lines()
+            .filter_map(|l| l.strip_prefix(&prefix))
+            .collect();
+        assert_eq!(lines, vec!["2:1: a.b"], "args {extra:?}");
+    }
+
+    // Piped input is searched with a regex, so the pattern must be escaped there too
+    Command::new(target_debug())
+        .arg("search")
+        .arg("-F")
+        .arg("a.b")
+        .write_stdin("axb\n")
+        .assert()
+        .stdout(predicate::str::contains("axb").not());
+
+    Ok(())
+}
+
+#[test]
+fn null_separated_output() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let spaced = dir.path().join("with space.txt");
+    let plain = dir.path().join("plain.txt");
+    fs::write(&spaced, "needle\n")?;
+    fs::write(&plain, "needle\nother\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "-l", "--null", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let expected = format!("{}\0{}\0", plain.display(), spaced.display());
+    assert_eq!(String::from_utf8(output.stdout)?, expected);
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "-0", "--"])
+        .arg(&plain)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    // A single file isn't named in its records
+    assert_eq!(stdout, "1:1: needle\0");
+    assert!(!stdout.ends_with('\n'));
+
+    Ok(())
+}
+
+#[test]
+fn only_matching_reports_each_occurrence() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("ids.txt");
+    fs::write(
+        &file,
+        "id=12 and id=345
+nothing here
+",
+    )?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "id=[0-9]+", "--mode", "regex", "-o", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(stdout, "1:1:id=12\n1:11:id=345\n");
+
+    Ok(())
+}
+
+#[test]
+fn vimgrep_repeats_the_line_for_each_match() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("ids.txt");
+    fs::write(&file, "id=12 and id=345\nnothing here\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "id=[0-9]+", "--mode", "regex", "--vimgrep", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let path = file.display();
+    assert_eq!(
+        stdout,
+        format!("{path}:1:1:id=12 and id=345\n{path}:1:11:id=12 and id=345\n")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn simulate_reports_each_pattern_as_json() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a.txt"), "foo bar\nbaz foo\n")?;
+
+    let output = Command::new(target_debug())
+        .arg(dir.path())
+        .args(["simulate", "--format", "json", "-e", "foo", "-e", "ba[rz]"])
+        .output()?;
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let scenarios = report["scenarios"].as_array().unwrap();
+    assert_eq!(scenarios.len(), 2);
+    for (scenario, pattern) in scenarios.iter().zip(["foo", "ba[rz]"]) {
+        assert_eq!(scenario["pattern"], pattern);
+        assert!(scenario["millis"].is_u64(), "{scenario}");
+        assert_eq!(scenario["matches"], 2);
+        assert_eq!(scenario["files"], 1);
+    }
+    assert_eq!(report["environment"]["parallel"], false);
+    assert_eq!(report["environment"]["threads"], 1);
+    assert!(report["environment"]["simd_backend"].is_string());
+    assert!(dir.path().join("results/simulations.json").exists());
+
+    // With --parallel, --threads 0 picks the same count as leaving it out
+    let threads = |extra: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .arg(dir.path())
+            .args(extra)
+            .args(["simulate", "--parallel", "--format", "json", "-e", "foo"])
+            .output()?;
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(report["environment"]["parallel"], true);
+        Ok(report["environment"]["threads"].clone())
+    };
+    let default_threads = threads(&[])?;
+    assert!(default_threads.as_u64().unwrap() >= 1);
+    assert_eq!(threads(&["--threads", "0"])?, default_threads);
+    assert_eq!(threads(&["--threads", "3"])?, 3);
+    let output = Command::new(target_debug())
+        .args(["--threads", "5000", "search", "foo", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)?.contains("maximum of 1024"));
+
+    Ok(())
+}
+
+#[test]
+fn json_file_type_names_each_language() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("main.rs"), "// needle\n")?;
+    fs::write(dir.path().join("tool.py"), "# needle\n")?;
+    fs::write(dir.path().join("notes.unknown"), "needle\n")?;
+
+    let languages =
+        |args: &[&str]| -> Result<Vec<(String, serde_json::Value)>, Box<dyn std::error::Error>> {
+            let output = Command::new(target_debug())
+                .args(["search", "needle"])
+                .args(args)
+                .arg("--")
+                .arg(dir.path())
+                .output()?;
+            assert!(output.status.success());
+            let stdout = String::from_utf8(output.stdout)?;
+            let records: Vec<serde_json::Value> = if args.contains(&"--ndjson") {
+                stdout
+                    .lines()
+                    .map(serde_json::from_str)
+                    .collect::<Result<_, _>>()?
+            } else {
+                let json: serde_json::Value = serde_json::from_str(&stdout)?;
+                json["matches"].as_array().unwrap().clone()
+            };
+            let mut languages: Vec<_> = records
+                .iter()
+                .map(|m| {
+                    let path = std::path::Path::new(m["path"].as_str().unwrap());
+                    let name = path.file_name().unwrap().to_string_lossy().to_string();
+                    (name, m.get("language").cloned().unwrap_or_default())
+                })
+                .collect();
+            languages.sort_by(|a, b| a.0.cmp(&b.0));
+            Ok(languages)
+        };
+
+    let expected = vec![
+        ("main.rs".to_string(), serde_json::json!("rust")),
+        ("notes.unknown".to_string(), serde_json::Value::Null),
+        ("tool.py".to_string(), serde_json::json!("python")),
+    ];
+    let json = [
+        "--output-format",
+        "json",
+        "--search-all-files",
+        "--json-file-type",
+    ];
+    assert_eq!(languages(&json)?, expected);
+    assert_eq!(
+        languages(&["--ndjson", "--search-all-files", "--json-file-type"])?,
+        expected
+    );
+    // Left out unless asked for
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--output-format", "json", "--"])
+        .arg(dir.path().join("main.rs"))
+        .output()?;
+    assert!(!String::from_utf8(output.stdout)?.contains("language"));
+
+    Ok(())
+}
+
+#[test]
+fn rfgrepignore_skips_matching_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join(".rfgrepignore"), "*.log\n")?;
+    fs::create_dir(dir.path().join("sub"))?;
+    fs::write(dir.path().join("app.log"), "needle\n")?;
+    fs::write(dir.path().join("sub/debug.log"), "needle\n")?;
+    fs::write(dir.path().join("notes.txt"), "needle\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "-l", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("notes.txt"), "{stdout}");
+    assert!(!stdout.contains(".log"), "{stdout}");
+
+    // Searching everything ignores it, like the gitignore rules
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "-l", "--search-all-files", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(String::from_utf8(output.stdout)?.contains("app.log"));
+
+    Ok(())
+}
+
+#[test]
+fn hidden_files_are_skipped_without_hidden() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join(".env"), "SECRET=needle\n")?;
+    fs::create_dir(dir.path().join(".config"))?;
+    fs::write(dir.path().join(".config/settings.txt"), "needle\n")?;
+    fs::write(dir.path().join("notes.txt"), "needle\n")?;
+    fs::write(dir.path().join(".rfgrepignore"), "*.log\n")?;
+    fs::write(dir.path().join(".debug.log"), "needle\n")?;
+
+    let run = |args: &[&str]| -> Result<String, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .args(args)
+            .arg("--")
+            .arg(dir.path())
+            .output()?;
+        assert!(output.status.success(), "{args:?}");
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    for args in [
+        &["search", "needle", "-l"][..],
+        &["list", "--simple", "--recursive"],
+    ] {
+        let shown = run(args)?;
+        assert!(shown.contains("notes.txt"), "{args:?}: {shown}");
+        assert!(!shown.contains(".env"), "{args:?}: {shown}");
+        assert!(!shown.contains("settings.txt"), "{args:?}: {shown}");
+
+        // --hidden only adds dotfiles; ignore files still apply
+        let args = [args, &["--hidden"]].concat();
+        let shown = run(&args)?;
+        assert!(shown.contains(".env"), "{args:?}: {shown}");
+        assert!(shown.contains("settings.txt"), "{args:?}: {shown}");
+        assert!(!shown.contains(".debug.log"), "{args:?}: {shown}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn extensions_ignore_case_unless_asked() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    for name in ["a.RS", "b.Rs", "c.rs", "d.txt"] {
+        fs::write(dir.path().join(name), "needle\n")?;
+    }
+
+    let run = |args: &[&str]| -> Result<String, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .args(args)
+            .arg("--")
+            .arg(dir.path())
+            .output()?;
+        assert!(output.status.success(), "{args:?}");
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    for args in [
+        &["search", "needle", "-l", "--extensions", "rs"][..],
+        &["list", "--simple", "--extensions", "rs"],
+    ] {
+        let shown = run(args)?;
+        for name in ["a.RS", "b.Rs", "c.rs"] {
+            assert!(shown.contains(name), "{args:?}: {shown}");
+        }
+        assert!(!shown.contains("d.txt"), "{args:?}: {shown}");
+
+        let args = [args, &["--case-sensitive-extensions"]].concat();
+        let shown = run(&args)?;
+        assert!(shown.contains("c.rs"), "{args:?}: {shown}");
+        assert!(!shown.contains("a.RS"), "{args:?}: {shown}");
+        assert!(!shown.contains("b.Rs"), "{args:?}: {shown}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn multiline_matches_across_lines() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("lib.rs");
+    fs::write(&file, "// header\nfn foo(\n    bar: u32,\n) {}\n")?;
+
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            r"fn foo\(\s*\n\s*bar",
+            "--mode",
+            "regex",
+            "-U",
+            "--output-format",
+            "json",
+            "--",
+        ])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let matches = json["matches"].as_array().ok_or("no matches array")?;
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["line_number"], 2);
+    assert_eq!(matches[0]["matched_text"], "fn foo(\n    bar");
+
+    // Without -U the pattern can never see the newline
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            r"fn foo\(\s*\n\s*bar",
+            "--mode",
+            "regex",
+            "-c",
+            "--",
+        ])
+        .arg(&file)
+        .output()?;
+    assert_eq!(String::from_utf8(output.stdout)?.trim(), "0");
+
+    Ok(())
+}
+
+#[test]
+fn serial_and_parallel_searches_agree_on_order() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    // Two hits per line whose text sorts opposite to their columns
+    for i in 0..10 {
+        fs::write(
+            dir.path().join(format!("f{i}.txt")),
+            "zzz aaa\nplain\naaa zzz\n",
+        )?;
+    }
+    let search = || {
+        Command::new(target_debug())
+            .args(["search", "-e", "zzz", "-e", "aaa", "--"])
+            .arg(dir.path())
+            .output()
+    };
+
+    // Ten files are searched one after another...
+    let serial = String::from_utf8(search()?.stdout)?;
+    // ...while an eleventh pushes the search onto the parallel path
+    fs::write(dir.path().join("z_last.txt"), "aaa\n")?;
+    let parallel = String::from_utf8(search()?.stdout)?;
+
+    let records = |out: &str| -> Vec<String> {
+        out.lines()
+            .filter(|l| l.contains(".txt:") && !l.contains("z_last.txt"))
+            .map(str::to_string)
+            .collect()
+    };
+    let serial = records(&serial);
+    assert_eq!(serial.len(), 40);
+    assert_eq!(serial, records(&parallel));
+
+    // Within a line, matches come in column order
+    let first = dir.path().join("f0.txt");
+    let prefix = format!("{}:", first.display());
+    let columns: Vec<&str> = serial
+        .iter()
+        .filter_map(|l| l.strip_prefix(&prefix))
+        .collect();
+    assert_eq!(
+        columns,
+        [
+            "1:1: zzz aaa",
+            "1:5: zzz aaa",
+            "3:1: aaa zzz",
+            "3:5: aaa zzz"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn replace_previews_and_rewrites_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let source = dir.path().join("lib.rs");
+    let binary = dir.path().join("data.rs");
+    let original = "let old_name = 1;\r\nfoo(old_name);\r\nunrelated\r\n";
+    fs::write(&source, original)?;
+    let binary_content = [&b"\x89PNG\r\n\x1a\n"[..], b"old_name", &[0u8; 64]].concat();
+    fs::write(&binary, &binary_content)?;
+
+    // Without --in-place only a diff is printed
+    Command::new(target_debug())
+        .args([
+            "replace",
+            "old_name",
+            "new_name",
+            "--extensions",
+            "rs",
+            "--",
+        ])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-let old_name = 1;"))
+        .stdout(predicate::str::contains("+let new_name = 1;"));
+    assert_eq!(fs::read_to_string(&source)?, original);
+
+    // --dry-run wins over --in-place
+    Command::new(target_debug())
+        .args([
+            "replace",
+            "old_name",
+            "new_name",
+            "--in-place",
+            "--dry-run",
+            "--",
+        ])
+        .arg(dir.path())
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&source)?, original);
+
+    // Capture groups expand in regex mode; CRLF endings and binaries are untouched
+    Command::new(target_debug())
+        .args(["replace", r"(\w+)_name", "${1}_id", "--mode", "regex"])
+        .args(["--in-place", "--backup", "--"])
+        .arg(dir.path())
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&source)?,
+        "let old_id = 1;\r\nfoo(old_id);\r\nunrelated\r\n"
+    );
+    assert_eq!(fs::read_to_string(dir.path().join("lib.rs.bak"))?, original);
+    assert_eq!(fs::read(&binary)?, binary_content);
+
+    Ok(())
+}
+
+#[test]
+fn replace_dry_run_prints_a_colored_diff() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("notes.txt");
+    let original = (1..=20)
+        .map(|n| {
+            if n == 10 {
+                "call foo()\n".to_string()
+            } else {
+                format!("line {n}\n")
+            }
+        })
+        .collect::<String>();
+    fs::write(&file, &original)?;
+
+    let output = Command::new(target_debug())
+        .args(["replace", "foo", "bar", "--in-place", "--dry-run"])
+        .args(["--color", "never", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let diff = String::from_utf8(output.stdout)?;
+    assert!(diff.contains("\n-call foo()\n+call bar()\n"), "{diff}");
+    // Only the changed hunk, with three lines of context
+    assert!(diff.contains("@@ -7,7 +7,7 @@"), "{diff}");
+    assert!(!diff.contains("line 1\n"), "{diff}");
+    assert_eq!(fs::read_to_string(&file)?, original);
+
+    Command::new(target_debug())
+        .args([
+            "replace",
+            "foo",
+            "bar",
+            "--dry-run",
+            "--color",
+            "always",
+            "--",
+        ])
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[31m-call foo()\x1b[0m\n"))
+        .stdout(predicate::str::contains("\x1b[32m+call bar()\x1b[0m\n"));
+    assert_eq!(fs::read_to_string(&file)?, original);
+
+    Ok(())
+}
+
+#[test]
+fn binary_modes_skip_search_or_report_binary_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    // Both would be dropped by their type alone: .bin is never searched by
+    // default and extensionless files are sniffed as binary
+    let contents = [
+        &b"heade"[..],
+        &[0; 16],
+        b"\x01\nthe needle is here\n\xff\xfe needle again\n",
+    ]
+    .concat();
+    let bin = dir.path().join("data.bin");
+    let noext = dir.path().join("noext");
+    fs::write(&bin, &contents)?;
+    fs::write(&noext, &contents)?;
+    let search = |args: &[&str]| {
+        Command::new(target_debug())
+            .args(["search", "needle"])
+            .args(args)
+            .arg("--")
+            .arg(dir.path())
+            .output()
+    };
+
+    // Skipped by default, and with an explicit --binary-mode skip
+    for args in [&[][..], &["--binary-mode", "skip"]] {
+        let output = search(args)?;
+        assert_eq!(output.status.code(), Some(1));
+        assert!(!String::from_utf8_lossy(&output.stdout).contains("needle"));
+    }
+
+    // -a searches every line, invalid UTF-8 included
+    let output = search(&["-a"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    for file in [&bin, &noext] {
+        let prefix = file.display().to_string();
+        assert!(
+            stdout.contains(&format!("{prefix}:2:5: the needle is here")),
+            "{stdout}"
+        );
+        assert!(
+            stdout.contains(&format!("{prefix}:3:8: \u{fffd}\u{fffd} needle again")),
+            "{stdout}"
+        );
+    }
+    assert_eq!(
+        search(&["--binary-mode", "text"])?.stdout,
+        search(&["-a"])?.stdout
+    );
+
+    // Binary mode reports each file once instead of its lines
+    let output = search(&["--binary-mode", "binary"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    for file in [&bin, &noext] {
+        let prefix = file.display().to_string();
+        assert_eq!(
+            stdout
+                .matches(&format!("Binary file {prefix} matches\n"))
+                .count(),
+            1,
+            "{stdout}"
+        );
+    }
+    assert!(!stdout.contains("needle is here"), "{stdout}");
+
+    Ok(())
+}
+
+#[test]
+fn case_sensitive_by_default_and_last_case_flag_wins() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("case.txt");
+    fs::write(&file, "FOO upper\nfoo lower\n")?;
+
+    let matched_lines = |args: &[&str]| -> Vec<String> {
+        let output = Command::new(target_debug())
+            .arg("search")
+            .args(args)
+            .arg("--")
+            .arg(&file)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .filter_map(|l| l.split(':').next().map(str::to_string))
+            .filter(|n| n.parse::<usize>().is_ok())
+            .collect()
+    };
+
+    assert_eq!(matched_lines(&["foo"]), vec!["2"]);
+    assert_eq!(matched_lines(&["foo", "-i"]), vec!["1", "2"]);
+    assert_eq!(matched_lines(&["foo", "--ignore-case"]), vec!["1", "2"]);
+    assert_eq!(matched_lines(&["foo", "-s"]), vec!["2"]);
+    assert_eq!(matched_lines(&["foo", "-i", "-s"]), vec!["2"]);
+    assert_eq!(matched_lines(&["foo", "-s", "-i"]), vec!["1", "2"]);
+    assert_eq!(matched_lines(&["foo", "-i", "-S"]), vec!["1", "2"]);
+    assert_eq!(matched_lines(&["Foo", "-S"]), Vec::<String>::new());
+
+    // Piped input follows the same rules
+    let output = Command::new(target_debug())
+        .args(["search", "foo", "-i", "--count-total"])
+        .write_stdin("FOO\nfoo\nbar\n")
+        .output()?;
+    assert_eq!(String::from_utf8(output.stdout)?.trim(), "2");
+
+    Ok(())
+}
+
+#[test]
+fn word_regexp_agrees_across_algorithms_and_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    // Like grep -w, a pattern starting with `-` only needs a non-word
+    // character before it, which `\b` would get wrong
+    let input = "x -foo y\na-foo\n-foox\n";
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("words.txt");
+    fs::write(&file, input)?;
+
+    let search = |extra: &[&str], stdin: bool| -> Result<String, Box<dyn std::error::Error>> {
+        let mut command = Command::new(target_debug());
+        command
+            .args([
+                "search",
+                "-w",
+                "--pattern=-foo",
+                "--vimgrep",
+                "--label",
+                "words.txt",
+            ])
+            .args(extra);
+        let output = if stdin {
+            command.write_stdin(input).output()?
+        } else {
+            command.arg("--").arg(&file).output()?
+        };
+        assert!(output.status.success(), "{extra:?} stdin={stdin}");
+        let stdout = String::from_utf8(output.stdout)?;
+        // Drop the path, which differs between files and stdin
+        Ok(stdout.replace(&file.display().to_string(), "words.txt"))
+    };
+
+    let expected = "words.txt:1:3:x -foo y\n";
+    assert_eq!(search(&["--algorithm", "boyer-moore"], false)?, expected);
+    assert_eq!(search(&["--algorithm", "regex"], false)?, expected);
+    assert_eq!(search(&["--mode", "regex"], false)?, expected);
+    assert_eq!(search(&[], true)?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn smart_case_follows_pattern_case() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("case.txt");
+    fs::write(&file, "FOO upper\nfoo lower\n")?;
+    let prefix = format!("{}:", file.display());
+
+    let matched_lines = |args: &[&str]| -> Vec<String> {
+        let output = Command::new(target_debug())
+            .args(["search", "--smart-case"])
+            .args(args)
+            .arg("--")
+            .arg(dir.path())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .filter_map(|l| l.strip_prefix(&prefix))
+            .map(|l| l.split(':').next().unwrap().to_string())
+            .collect()
+    };
+
+    // All-lowercase pattern: case-insensitive
+    assert_eq!(matched_lines(&["foo"]), vec!["1", "2"]);
+    assert_eq!(
+        matched_lines(&["foo", "--algorithm", "regex"]),
+        vec!["1", "2"]
+    );
+    // Any uppercase: case-sensitive
+    assert_eq!(matched_lines(&["Foo"]), Vec::<String>::new());
+    assert_eq!(matched_lines(&["FOO"]), vec!["1"]);
+    // Regex escapes don't count as uppercase
+    assert_eq!(
+        matched_lines(&[r"foo\W", "--mode", "regex"]),
+        vec!["1", "2"]
+    );
+    // A later --case-sensitive wins
+    assert_eq!(matched_lines(&["foo", "--case-sensitive"]), vec!["2"]);
+    // -l uses the same rules
+    let output = Command::new(target_debug())
+        .args(["search", "-S", "foo", "-l", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(String::from_utf8(output.stdout)?.contains("case.txt"));
+
+    Ok(())
+}
+
+#[test]
+fn invert_match_emits_non_matching_lines() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("lines.txt");
+    fs::write(&file, "keep\nremove\nkeep\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "remove", "--invert-match", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let prefix = format!("{}:", file.display());
+    let stdout = String::from_utf8(output.stdout)?;
+    let records: Vec<&str> = stdout
+        .lines()
+        .filter_map(|l| l.strip_prefix(&prefix))
+        .collect();
+    assert_eq!(records, vec!["1:1: keep", "3:1: keep"]);
+
+    Ok(())
+}
+
+#[test]
+fn context_lines_do_not_swallow_adjacent_matches() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join("adjacent.txt"),
+        "hit one\nhit two\nplain\nhit three\nplain\nplain\n",
+    )?;
+
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            "hit",
+            "--context-lines",
+            "2",
+            "--output-format",
+            "json",
+            "--",
+        ])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let matches = json["matches"].as_array().unwrap();
+    let line_numbers: Vec<u64> = matches
+        .iter()
+        .map(|m| m["line_number"].as_u64().unwrap())
+        .collect();
+    assert_eq!(line_numbers, vec![1, 2, 4]);
+
+    let context_numbers = |m: &serde_json::Value, key: &str| -> Vec<u64> {
+        m[key]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["line_number"].as_u64().unwrap())
+            .collect()
+    };
+    assert_eq!(context_numbers(&matches[0], "context_after"), vec![2, 3]);
+    assert_eq!(context_numbers(&matches[1], "context_before"), vec![1]);
+    assert_eq!(context_numbers(&matches[1], "context_after"), vec![3, 4]);
+    assert_eq!(context_numbers(&matches[2], "context_before"), vec![2, 3]);
+    assert_eq!(context_numbers(&matches[2], "context_after"), vec![5, 6]);
+
+    Ok(())
+}
+
+#[test]
+fn max_count_limits_matches_per_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    for name in ["a.txt", "b.txt"] {
+        fs::write(dir.path().join(name), "hit\nhit\nhit\nhit\nhit\n")?;
+    }
+
+    let output = Command::new(target_debug())
+        .args(["search", "hit", "-m", "2", "--output-format", "json", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let matches = json["matches"].as_array().unwrap();
+    assert_eq!(matches.len(), 4);
+    for name in ["a.txt", "b.txt"] {
+        let per_file = matches
+            .iter()
+            .filter(|m| m["path"].as_str().unwrap().ends_with(name))
+            .count();
+        assert_eq!(per_file, 2, "{name} should report two matches");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn ignore_directories_from_config_are_pruned() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    // Keep any user config out of the way so the defaults apply
+    let home = tempfile::tempdir()?;
+    fs::create_dir(dir.path().join("node_modules"))?;
+    fs::write(dir.path().join("node_modules/foo.js"), "needle\n")?;
+    fs::write(dir.path().join("main.js"), "needle\n")?;
+
+    let search = |extra: &[&str]| {
+        Command::new(target_debug())
+            .env("HOME", home.path())
+            .env("XDG_CONFIG_HOME", home.path())
+            .args(["search", "needle", "--recursive", "-l"])
+            .args(extra)
+            .arg("--")
+            .arg(dir.path())
+            .output()
+    };
+
+    let output = search(&[])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("main.js"));
+    assert!(
+        !stdout.contains("foo.js"),
+        "node_modules was searched: {stdout}"
+    );
+
+    let output = search(&["--no-ignore-dirs"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("main.js"));
+    assert!(
+        stdout.contains("foo.js"),
+        "node_modules was skipped: {stdout}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn progress_does_not_contaminate_stdout() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    // Enough files to take the parallel path
+    for i in 0..20 {
+        fs::write(dir.path().join(format!("f{i}.txt")), "needle\n")?;
+    }
+
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            "needle",
+            "--progress",
+            "--output-format",
+            "json",
+            "--",
+        ])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["matches"].as_array().unwrap().len(), 20);
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--progress", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let records = stdout
+        .lines()
+        .filter(|l| l.ends_with(":1:1: needle"))
+        .count();
+    assert_eq!(records, 20);
+    assert!(
+        !stdout.contains("files"),
+        "progress leaked into stdout: {stdout}"
+    );
+    assert!(!stdout.contains('\r'));
+
+    Ok(())
+}
+
+#[test]
+fn exit_codes_follow_grep() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a.txt"), "needle\n")?;
+
+    let search = |args: &[&str]| {
+        Command::new(target_debug())
+            .arg("search")
+            .args(args)
+            .arg("--")
+            .arg(dir.path())
+            .output()
+    };
+
+    assert_eq!(search(&["needle"])?.status.code(), Some(0));
+    assert_eq!(search(&["haystack"])?.status.code(), Some(1));
+    // An invalid regex is an error, not an empty result
+    assert_eq!(
+        search(&["(unclosed", "--mode", "regex"])?.status.code(),
+        Some(2)
+    );
+    // So is a path that doesn't exist
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--"])
+        .arg(dir.path().join("does-not-exist"))
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)?.contains("does-not-exist"));
+
+    Ok(())
+}
+
+#[test]
+fn quiet_prints_nothing_and_reports_through_exit_code() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a.txt"), "needle\nneedle\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "-q", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    let output = Command::new(target_debug())
+        .args(["search", "haystack", "-q", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn heading_groups_matches_under_each_path() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    fs::write(&a, "needle one\nneedle two\n")?;
+    fs::write(&b, "plain\nneedle three\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--heading", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+
+    let headings: Vec<&str> = stdout
+        .lines()
+        .filter(|line| *line == a.display().to_string() || *line == b.display().to_string())
+        .collect();
+    assert_eq!(headings.len(), 2, "stdout was:\n{stdout}");
+    for path in [&a, &b] {
+        let prefix = format!("{}:", path.display());
+        assert!(
+            !stdout.lines().any(|line| line.starts_with(&prefix)),
+            "per-line path prefix in:\n{stdout}"
+        );
+    }
+    assert!(stdout.contains("  1: needle one\n  2: needle two\n\n"));
+    assert!(stdout.contains("  2: needle three\n"));
+
+    Ok(())
+}
+
+#[test]
+fn files_without_match_lists_only_unmatched_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join("a.rs"),
+        "// SPDX-License-Identifier: MIT\nfn a() {}\n",
+    )?;
+    fs::write(dir.path().join("b.rs"), "fn b() {}\n")?;
+    fs::write(
+        dir.path().join("c.rs"),
+        "// SPDX-License-Identifier: MIT\nfn c() {}\n",
+    )?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "SPDX-License-Identifier", "-L", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(stdout, format!("{}\n", dir.path().join("b.rs").display()));
+
+    Ok(())
+}
+
+#[test]
+fn count_reports_lines_and_count_matches_reports_occurrences(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("twice.txt");
+    fs::write(&file, "needle and needle\nnothing here\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "-c", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, "1\n");
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--count-matches", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!("{}:2\n", file.display())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn count_is_broken_down_per_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    fs::write(&a, "needle\nneedle needle\n")?;
+    fs::write(&b, "plain\nneedle\n")?;
+    fs::write(dir.path().join("c.txt"), "plain\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "-c", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!("{}:2\n{}:1\n", a.display(), b.display())
+    );
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--count-total", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, "3\n");
+
+    Ok(())
+}
+
+#[test]
+fn count_names_files_like_matches_do() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    fs::write(&a, "needle needle\nneedle\n")?;
+    fs::write(&b, "needle\n")?;
+
+    let count = |args: &[&str], path: &Path| -> Result<String, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .args(["search", "needle"])
+            .args(args)
+            .arg("--")
+            .arg(path)
+            .output()?;
+        assert!(output.status.success(), "{args:?}");
+        Ok(String::from_utf8(output.stdout)?)
+    };
+    let (a_name, b_name) = (a.display(), b.display());
+
+    // A single file is counted without its name unless -H asks for it
+    assert_eq!(count(&["-c"], &a)?, "2\n");
+    assert_eq!(count(&["--count-matches"], &a)?, "3\n");
+    assert_eq!(count(&["-c", "-H"], &a)?, format!("{a_name}:2\n"));
+    assert_eq!(
+        count(&["--count-matches", "-H"], &a)?,
+        format!("{a_name}:3\n")
+    );
+    // A directory's files are named unless -h leaves them out
+    assert_eq!(
+        count(&["-c"], dir.path())?,
+        format!("{a_name}:2\n{b_name}:1\n")
+    );
+    assert_eq!(count(&["-c", "-h"], dir.path())?, "2\n1\n");
+    assert_eq!(count(&["--count-matches", "-h"], dir.path())?, "3\n1\n");
+
+    Ok(())
+}
+
+#[test]
+fn line_range_limits_matches_to_the_window() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("generated.txt");
+    let contents: String = (1..=10).map(|i| format!("needle {i}\n")).collect();
+    fs::write(&file, contents)?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--line-range", "3:5", "--ndjson", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let lines: Vec<u64> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line).unwrap()["line_number"]
+                .as_u64()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(lines, vec![3, 4, 5]);
+
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            "needle",
+            "--line-range",
+            "9:",
+            "--count-total",
+            "--",
+        ])
+        .arg(&file)
+        .output()?;
+    assert_eq!(String::from_utf8(output.stdout)?, "2\n");
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--line-range", "5:3", "--"])
+        .arg(&file)
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn passthru_prints_every_line_of_each_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let a = dir.path().join("a.txt");
+    let b = dir.path().join("b.txt");
+    fs::write(&a, "one\nneedle two\n")?;
+    fs::write(&b, "three\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--passthru", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!(
+            "{a}-1-one\n{a}:2:needle two\n{b}-1-three\n",
+            a = a.display(),
+            b = b.display()
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn passthru_decodes_like_the_search() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("latin1.txt");
+    fs::write(&file, b"caf\xe9 needle\nna\xefve\nlast\n")?;
+
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            "needle",
+            "--passthru",
+            "--encoding",
+            "latin1",
+            "--",
+        ])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "1:caf\u{e9} needle\n2-na\u{ef}ve\n3-last\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn patterns_are_read_from_a_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let patterns = dir.path().join("patterns.txt");
+    fs::write(&patterns, "# generated list\nalpha\n\nbeta\r\ngamma\n")?;
+    let file = dir.path().join("input.log");
+    fs::write(
+        &file,
+        "alpha 1\nnothing\nbeta 2\n# generated list\ngamma 3\n",
+    )?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "-f"])
+        .arg(&patterns)
+        .args(["--ndjson", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let lines: Vec<u64> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line).unwrap()["line_number"]
+                .as_u64()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(lines, vec![1, 3, 5]);
+
+    let missing = Command::new(target_debug())
+        .args(["search", "-f"])
+        .arg(dir.path().join("missing.txt"))
+        .arg("--")
+        .arg(&file)
+        .output()?;
+    assert_eq!(missing.status.code(), Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn copy_puts_plain_results_on_the_clipboard() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("notes.txt");
+    fs::write(&file, "plain\nneedle here\n")?;
+    let clipboard = tempfile::NamedTempFile::new()?;
+
+    let output = Command::new(target_debug())
+        .env("RFGREP_CLIPBOARD_FILE", clipboard.path())
+        .args(["search", "needle", "--copy", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let copied = fs::read_to_string(clipboard.path())?;
+    assert!(copied.contains("\n2:1: needle here"), "copied:\n{copied}");
+    assert!(!copied.contains('\x1b'));
+    assert!(String::from_utf8(output.stderr)?.contains("Copied results to clipboard"));
+
+    let output = Command::new(target_debug())
+        .env("RFGREP_CLIPBOARD_FILE", clipboard.path())
+        .args(["list", "--copy", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(clipboard.path())?,
+        format!("{}\n", file.display())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn verbose_logs_debug_records_to_stderr_or_log_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("text.txt"), "needle\n")?;
+
+    let output = Command::new(target_debug())
+        .env_remove("RUST_LOG")
+        .args(["--verbose", "search", "needle", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("[DEBUG] [rfgrep::processor]"),
+        "stderr was:\n{stderr}"
+    );
+
+    // Without --verbose only warnings are logged
+    let output = Command::new(target_debug())
+        .env_remove("RUST_LOG")
+        .args(["search", "needle", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(!String::from_utf8(output.stderr)?.contains("[DEBUG]"));
+
+    // --log sends the records to the file and keeps stderr clean
+    let log = dir.path().join("logs").join("rfgrep.log");
+    let output = Command::new(target_debug())
+        .env_remove("RUST_LOG")
+        .args(["--verbose", "--log"])
+        .arg(&log)
+        .args(["search", "needle", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(!String::from_utf8(output.stderr)?.contains("[DEBUG]"));
+    assert!(fs::read_to_string(&log)?.contains("[rfgrep::processor]"));
+
+    Ok(())
+}
+
+#[test]
+fn stats_summarize_the_search_on_stderr() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(dir.path().join(name), "needle\nplain\n")?;
+    }
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--stats", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("Files scanned: 3\n"),
+        "stderr was:\n{stderr}"
+    );
+    assert!(
+        stderr.contains("Bytes scanned: 39 "),
+        "stderr was:\n{stderr}"
+    );
+    assert!(stderr.contains("Matches: 3\n"), "stderr was:\n{stderr}");
+    assert!(
+        stderr.contains("Read: 0 memory-mapped, 3 streamed\n"),
+        "stderr was:\n{stderr}"
+    );
+    assert!(stderr.contains("  search: "), "stderr was:\n{stderr}");
+    assert!(!String::from_utf8(output.stdout)?.contains("Files scanned"));
+
+    // With JSON results the summary is a JSON object too
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            "needle",
+            "--metrics",
+            "--output-format",
+            "json",
+            "--",
+        ])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    let stats: serde_json::Value = serde_json::from_str(stderr.trim_end().lines().last().unwrap())?;
+    assert_eq!(stats["files_scanned"], 3);
+    assert_eq!(stats["files_streamed"], 3);
+    assert!(stats["regex_cache"]["hits"].is_u64());
+    assert!(stats["phases_seconds"]["walk"].is_f64());
+
+    // -q keeps the summary out too
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--stats", "-q", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.stderr.is_empty());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn pre_command_output_is_searched() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("notes.txt");
+    fs::write(&file, "hello world\nnothing here\n")?;
+
+    let search = |extra: &[&str]| -> Result<std::process::Output, std::io::Error> {
+        Command::new(target_debug())
+            .args(["search", "HELLO", "--no-heading"])
+            .args(extra)
+            .arg("--")
+            .arg(dir.path())
+            .output()
+    };
+
+    let output = search(&["--pre", "tr a-z A-Z"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(
+        stdout.contains(&format!("{}:1:1: HELLO WORLD", file.display())),
+        "{stdout}"
+    );
+
+    // Without it, or for files --pre-glob leaves out, the file is searched as is
+    assert_eq!(search(&[])?.status.code(), Some(1));
+    let output = search(&["--pre", "tr a-z A-Z", "--pre-glob", "*.pdf"])?;
+    assert_eq!(output.status.code(), Some(1));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn ctrl_c_stops_the_search_early() -> Result<(), Box<dyn std::error::Error>> {
+    use std::process::{Command as StdCommand, Stdio};
+    use std::time::{Duration, Instant};
+
+    let dir = tempfile::tempdir()?;
+    for i in 0..20 {
+        fs::write(dir.path().join(format!("{i:02}.txt")), "needle\n")?;
+    }
+
+    // One worker that takes 2s per file: a full run would take 40s
+    let start = Instant::now();
+    let child = StdCommand::new(target_debug())
+        .env("RFGREP_WORKER_SLEEP", "2")
+        .args([
+            "search",
+            "needle",
+            "--threads",
+            "1",
+            "--timeout-per-file",
+            "10",
+        ])
+        .arg("--")
+        .arg(dir.path())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    std::thread::sleep(Duration::from_secs(1));
+    let status = StdCommand::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()?;
+    assert!(status.success());
+
+    let output = child.wait_with_output()?;
+    assert!(
+        start.elapsed() < Duration::from_secs(15),
+        "took {:?}",
+        start.elapsed()
+    );
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("Shutdown requested"),
+        "stderr was:\n{stderr}"
+    );
+    // The file in progress finishes and its match is still reported
+    let stdout = String::from_utf8(output.stdout)?;
+    let found = stdout
+        .lines()
+        .filter(|l| l.ends_with(".txt:1:1: needle"))
+        .count();
+    assert!((1..20).contains(&found), "stdout was:\n{stdout}");
+
+    Ok(())
+}
+
+#[test]
+fn type_filter_uses_config_type_definitions() -> Result<(), Box<dyn std::error::Error>> {
+    let config_home = tempfile::tempdir()?;
+    fs::create_dir_all(config_home.path().join("rfgrep"))?;
+    fs::write(
+        config_home.path().join("rfgrep/config.toml"),
+        "[type_definitions]\nrust = [\"rs\", \"rlib\"]\n",
+    )?;
+    let dir = tempfile::tempdir()?;
+    for name in ["main.rs", "lib.rlib", "script.py", "notes.txt"] {
+        fs::write(dir.path().join(name), "needle\n")?;
+    }
+
+    let search = |args: &[&str]| {
+        Command::new(target_debug())
+            .env("XDG_CONFIG_HOME", config_home.path())
+            .arg("search")
+            .args(args)
+            .arg("--")
+            .arg(dir.path())
+            .output()
+    };
+
+    let output = search(&["needle", "--type", "rust"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("main.rs"), "stdout was:\n{stdout}");
+    assert!(stdout.contains("lib.rlib"), "stdout was:\n{stdout}");
+    assert!(!stdout.contains("script.py"), "stdout was:\n{stdout}");
+    assert!(!stdout.contains("notes.txt"), "stdout was:\n{stdout}");
+
+    let output = search(&["needle", "-T", "rust"])?;
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("script.py"), "stdout was:\n{stdout}");
+    assert!(!stdout.contains("main.rs"), "stdout was:\n{stdout}");
+
+    let output = search(&["needle", "--type", "cobol"])?;
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)?.contains("Unknown file type 'cobol'"));
+
+    let output = search(&["--type-list"])?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(
+        stdout.contains("rust: *.rs, *.rlib\n"),
+        "stdout was:\n{stdout}"
+    );
+    assert!(stdout.contains("python: *.py"), "stdout was:\n{stdout}");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinked_files_are_searched_once() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("real.txt"), "needle\n")?;
+    std::os::unix::fs::symlink(dir.path().join("real.txt"), dir.path().join("link.txt"))?;
+    fs::create_dir(dir.path().join("sub"))?;
+    fs::write(dir.path().join("sub/other.txt"), "needle\n")?;
+    std::os::unix::fs::symlink(dir.path().join("sub"), dir.path().join("sub-link"))?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--follow-symlinks", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(
+        stdout
+            .lines()
+            .filter(|l| l.ends_with(":1:1: needle"))
+            .count(),
+        2,
+        "stdout was:\n{stdout}"
+    );
+    assert!(stdout.contains("Total matches: 2"), "stdout was:\n{stdout}");
+
+    Ok(())
+}
+
+#[test]
+fn format_template_renders_capture_groups() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("settings.ini");
+    fs::write(&file, "width=80\n# comment\nheight=24 depth=3\n")?;
+
+    // Text mode is switched to regex so the groups exist
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            r"(?P<key>\w+)=(\d+)",
+            "--format",
+            "$2 <- ${key}",
+            "--",
+        ])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "80 <- width\n24 <- height\n3 <- depth\n"
+    );
+
+    // Searching a directory names the file of each record
+    let output = Command::new(target_debug())
+        .args(["search", r"width=(\d+)", "--format", "$1", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!("{}:80\n", file.display())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn ndjson_reports_byte_offsets() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("notes.txt");
+    let content = "intro\r\nfirst needle\n\nplain\r\nneedle and needle\n";
+    fs::write(&file, content)?;
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--ndjson", "--"])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let offsets: Vec<usize> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line).unwrap()["byte_offset"]
+                .as_u64()
+                .unwrap() as usize
+        })
+        .collect();
+    let expected: Vec<usize> = content.match_indices("needle").map(|(i, _)| i).collect();
+    assert_eq!(offsets, expected);
+    assert_eq!(offsets[0], content.find("needle").unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn github_actions_output_is_only_annotations() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("notes.txt");
+    fs::write(&file, "first needle\nplain\nneedle again\n")?;
+
+    let output = Command::new(target_debug())
+        .args([
+            "search",
+            "needle",
+            "--output-format",
+            "github-actions",
+            "--",
+        ])
+        .arg(&file)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert_eq!(stdout.lines().count(), 2, "{stdout:?}");
+    assert!(
+        stdout.lines().all(|line| line.starts_with("::warning ")),
+        "{stdout:?}"
+    );
+    assert!(stdout.ends_with("needle\n"), "{stdout:?}");
+
+    Ok(())
+}
+
+#[test]
+fn json_summary_ends_ndjson_output() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a.txt"), "needle needle\nplain\nneedle\n")?;
+    fs::write(dir.path().join("b.txt"), "one needle\n")?;
+    fs::write(dir.path().join("c.txt"), "nothing\n")?;
+
+    let parse = |output: std::process::Output| -> Vec<serde_json::Value> {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    };
+
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--ndjson", "--json-summary", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let records = parse(output);
+    let (summary, matches) = records.split_last().unwrap();
+    assert_eq!(matches.len(), 4);
+    assert!(matches.iter().all(|m| m["type"] == "match"));
+    assert_eq!(summary["type"], "summary");
+    assert_eq!(summary["matched_lines"], 3);
+    assert_eq!(summary["files"], 2);
+    assert!(summary["elapsed_ms"].is_u64());
+
+    // Stdin gets one too, even when nothing matched
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--ndjson", "--json-summary"])
+        .write_stdin("nothing here\n")
+        .output()?;
+    let records = parse(output);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["type"], "summary");
+    assert_eq!(records[0]["matched_lines"], 0);
+
+    // It only applies to NDJSON
+    Command::new(target_debug())
+        .args(["search", "needle", "--json-summary", "--"])
+        .arg(dir.path())
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn list_output_format_json_describes_each_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a.txt"), "hello")?;
+    fs::write(dir.path().join("b.rs"), "fn main() {}\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["list", "--output-format", "json", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let files = listing.as_array().expect("a JSON array");
+    assert_eq!(files.len(), 2);
+    assert!(files[0]["path"].as_str().unwrap().ends_with("a.txt"));
+    assert_eq!(files[0]["size"], 5);
+    assert_eq!(files[1]["extension"], "rs");
+    assert_eq!(files[1]["size"], 13);
+
+    // --long adds details and --stats the totals
+    let output = Command::new(target_debug())
+        .args(["list", "--output-format", "json", "--long", "--stats", "--"])
+        .arg(dir.path())
+        .output()?;
+    let listing: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(listing["files"][0]["is_binary"], false);
+    assert!(listing["files"][0]["modified"].is_u64());
+    assert_eq!(listing["stats"]["total_files"], 2);
+    assert_eq!(listing["stats"]["total_size"], 18);
+
+    Ok(())
+}
+
+#[test]
+fn list_duplicates_groups_identical_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("one.txt"), "same content\n")?;
+    fs::write(dir.path().join("two.txt"), "same content\n")?;
+    // Same size, different bytes
+    fs::write(dir.path().join("three.txt"), "diff content\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["list", "--duplicates", "--output-format", "json", "--"])
+        .arg(dir.path())
+        .output()?;
+    assert!(output.status.success());
+    let groups: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let groups = groups.as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    let paths: Vec<&str> = groups[0]["paths"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|path| path.as_str().unwrap())
+        .collect();
+    assert_eq!(paths.len(), 2);
+    assert!(paths[0].ends_with("one.txt"));
+    assert!(paths[1].ends_with("two.txt"));
+    assert_eq!(groups[0]["size"], 13);
+
+    let output = Command::new(target_debug())
+        .args(["list", "--duplicates", "--"])
+        .arg(dir.path())
+        .output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.starts_with("2 identical files (13 bytes each):\n"));
+    assert!(!stdout.contains("three.txt"));
+
+    Ok(())
+}
+
+#[test]
+fn before_and_after_context_are_independent() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("log.txt");
+    fs::write(&file, "l1\nl2\nl3\nhit\nl5\nl6\nl7\nl8\n")?;
+
+    let context = |args: &[&str]| -> Result<(Vec<u64>, Vec<u64>), Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .args(["search", "hit", "--ndjson"])
+            .args(args)
+            .arg("--")
+            .arg(&file)
+            .output()?;
+        let record: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let numbers = |key: &str| {
+            record[key]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|line| line["line_number"].as_u64().unwrap())
+                .collect()
+        };
+        Ok((numbers("context_before"), numbers("context_after")))
+    };
+
+    assert_eq!(context(&["-B", "1", "-A", "2"])?, (vec![3], vec![5, 6]));
+    assert_eq!(context(&["-C", "1"])?, (vec![3], vec![5]));
+    // -A and -B override -C on their side only
+    assert_eq!(context(&["-C", "2", "-A", "0"])?, (vec![2, 3], vec![]));
+
+    Ok(())
+}
+
+#[test]
+fn file_names_are_shown_for_directories_only() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("notes.txt");
+    fs::write(&file, "plain\nneedle here\n")?;
+
+    let search = |target: &Path, flags: &[&str]| -> Result<String, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .args(["search", "needle", "--no-heading"])
+            .args(flags)
+            .arg("--")
+            .arg(target)
+            .output()?;
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout)?;
+        Ok(stdout
+            .lines()
+            .find(|line| line.ends_with(": needle here"))
+            .unwrap_or_default()
+            .to_string())
+    };
+    let named = format!("{}:2:1: needle here", file.display());
+
+    assert_eq!(search(&file, &[])?, "2:1: needle here");
+    assert_eq!(search(dir.path(), &[])?, named);
+    assert_eq!(search(&file, &["-H"])?, named);
+    assert_eq!(search(dir.path(), &["-h"])?, "2:1: needle here");
+    // The last of -H and -h wins
+    assert_eq!(search(&file, &["-h", "--with-filename"])?, named);
+
+    Ok(())
+}
+
+#[test]
+fn match_color_comes_from_the_config() -> Result<(), Box<dyn std::error::Error>> {
+    let config_home = tempfile::tempdir()?;
+    fs::create_dir_all(config_home.path().join("rfgrep"))?;
+    fs::write(
+        config_home.path().join("rfgrep/config.toml"),
+        "[output.colors]\nmatch = \"#ff0000\"\nline_number = \"green\"\n",
+    )?;
+    let dir = tempfile::tempdir()?;
+    let file = dir.path().join("notes.txt");
+    fs::write(&file, "a needle here\n")?;
+
+    let search = |color: &str| -> Result<String, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .env("XDG_CONFIG_HOME", config_home.path())
+            .args(["--color", color, "search", "needle", "--"])
+            .arg(&file)
+            .output()?;
+        assert!(output.status.success());
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    let never = search("never")?;
+    assert!(never.contains("1:3: a needle here"), "{never:?}");
+    assert!(!never.contains('\x1b'), "{never:?}");
+
+    let always = search("always")?;
+    assert!(
+        always.contains("\x1b[38;2;255;0;0mneedle\x1b[0m"),
+        "{always:?}"
+    );
+    assert!(always.contains("\x1b[32m1\x1b[0m:"), "{always:?}");
+
+    Ok(())
+}
+
+#[test]
+fn color_always_survives_a_pipe_for_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    let config_home = tempfile::tempdir()?;
+    fs::create_dir_all(config_home.path().join("rfgrep"))?;
+    fs::write(
+        config_home.path().join("rfgrep/config.toml"),
+        "[output.colors]\nmatch = \"#ff0000\"\n",
+    )?;
+
+    let search = |color: &str, extra: &[&str]| -> Result<String, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .env("XDG_CONFIG_HOME", config_home.path())
+            .args(["--color", color, "search", "needle"])
+            .args(extra)
+            .write_stdin("a needle here\nplain\n")
+            .output()?;
+        assert!(output.status.success());
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    for extra in [&[][..], &["--passthru"]] {
+        let always = search("always", extra)?;
+        assert!(
+            always.contains("\x1b[38;2;255;0;0mneedle\x1b[0m"),
+            "{extra:?}: {always:?}"
+        );
+    }
+    let never = search("never", &[])?;
+    assert!(never.contains("a needle here"), "{never:?}");
+    assert!(!never.contains('\x1b'), "{never:?}");
+
+    Ok(())
+}
+
+#[test]
+fn interactive_filter_narrows_results_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join("a.txt"),
+        "error: disk full\nerror: timeout\n",
+    )?;
+    fs::write(dir.path().join("b.txt"), "error: timeout again\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["interactive", "error", "--"])
+        .arg(dir.path())
+        .write_stdin("f timeout\nf again\nc\nq\n")
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let summaries: Vec<&str> = stdout
+        .lines()
+        .filter(|l| l.contains("matches for"))
+        .collect();
+    assert_eq!(
+        summaries,
+        [
+            "3 matches for 'error'",
+            "2 of 3 matches for 'error' (filters: timeout)",
+            "1 of 3 matches for 'error' (filters: timeout, again)",
+            "3 matches for 'error'",
+        ],
+        "stdout was:\n{stdout}"
+    );
+    assert!(stdout.contains("b.txt:1:1: error: timeout again\n"));
+
+    Ok(())
+}
+
+#[test]
+fn interactive_save_writes_the_shown_results() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join("a.txt"),
+        "error: disk full\nerror: timeout\n",
+    )?;
+    let saved = dir.path().join("saved.json");
+
+    // A new search replaces the results and drops the filters
+    Command::new(target_debug())
+        .args(["interactive", "error", "--output-format", "json", "--"])
+        .arg(dir.path())
+        .write_stdin(format!("f disk\nn timeout\ns {}\nq\n", saved.display()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 matches for 'timeout'"))
+        .stdout(predicate::str::contains("Saved 1 matches to"));
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&saved)?)?;
+    let matches = json["matches"].as_array().expect("matches array");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["line"], "error: timeout");
+    assert_eq!(matches[0]["line_number"], 2);
+
+    Ok(())
+}
+
+#[test]
+fn list_tree_groups_files_by_directory() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let root = dir.path();
+    fs::create_dir_all(root.join("src/app"))?;
+    fs::create_dir_all(root.join("deep/er/still"))?;
+    fs::write(root.join("README.md"), "readme\n")?;
+    fs::write(root.join("main.rs"), "fn main() {}\n")?;
+    fs::write(root.join("src/lib.rs"), "\n")?;
+    fs::write(root.join("src/app/mod.rs"), "\n")?;
+    fs::write(root.join("src/app/notes.txt"), "filtered out\n")?;
+    fs::write(root.join("deep/er/still/a.rs"), "\n")?;
+
+    let output = Command::new(target_debug())
+        .args(["list", "--tree", "--recursive", "--extensions", "rs", "--"])
+        .arg(root)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let expected = format!(
+        "{}\n\
+         ├── deep/er/still/\n\
+         │   └── a.rs\n\
+         ├── main.rs\n\
+         └── src/\n\
+         \x20   ├── app/\n\
+         \x20   │   └── mod.rs\n\
+         \x20   └── lib.rs\n\
+         Summary: 4 files found\n",
+        root.display()
+    );
+    assert_eq!(stdout, expected);
+
+    Ok(())
+}
+
+#[test]
+fn modification_time_filters_limit_search_and_list() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::{Duration, SystemTime};
+
+    let dir = tempfile::tempdir()?;
+    let day = Duration::from_secs(24 * 60 * 60);
+    for (name, age) in [("today.txt", 0), ("lastweek.txt", 7), ("lastyear.txt", 365)] {
+        let path = dir.path().join(name);
+        fs::write(&path, "needle\n")?;
+        fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(SystemTime::now() - age * day)?;
+    }
+
+    let listed = |args: &[&str]| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .args(args)
+            .arg(dir.path())
+            .output()?;
+        let mut names: Vec<String> = String::from_utf8(output.stdout)?
+            .lines()
+            .filter_map(|line| Path::new(line).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        Ok(names)
+    };
+
+    assert_eq!(
+        listed(&["list", "--simple", "--newer-than", "2d", "--"])?,
+        ["today.txt"]
+    );
+    assert_eq!(
+        listed(&[
+            "list",
+            "--simple",
+            "--newer-than",
+            "30d",
+            "--older-than",
+            "1d",
+            "--"
+        ])?,
+        ["lastweek.txt"]
+    );
+    assert_eq!(
+        listed(&["search", "needle", "-l", "--older-than", "2d", "--"])?,
+        ["lastweek.txt", "lastyear.txt"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sort_files_gives_the_same_output_every_run() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    // Created in reverse so directory order is unlikely to be path order
+    for name in ["e.txt", "d/c.txt", "d/a.txt", "b.txt", "a.txt"] {
+        let path = dir.path().join(name);
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(path, "needle\n")?;
+    }
+
+    let run = || {
+        Command::new(target_debug())
+            .args([
+                "search",
+                "needle",
+                "--sort",
+                "none",
+                "--sort-files",
+                "-r",
+                "--",
+            ])
+            .arg(dir.path())
+            .output()
+    };
+    let first = run()?;
+    let second = run()?;
+    assert!(first.status.success());
+    assert_eq!(first.stdout, second.stdout);
+
+    let found: Vec<String> = String::from_utf8(first.stdout)?
+        .lines()
+        .filter_map(|line| line.strip_suffix(":1:1: needle"))
+        .map(|path| {
+            Path::new(path)
+                .strip_prefix(dir.path())
+                .unwrap()
+                .display()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(found, ["a.txt", "b.txt", "d/a.txt", "d/c.txt", "e.txt"]);
+
+    Ok(())
+}
+
+#[test]
+fn list_binary_only_and_text_only_split_the_files() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("notes.txt"), "plain text\n")?;
+    let mut blob = b"\x7fBLOB".to_vec();
+    blob.extend(std::iter::repeat_n(0u8, 64));
+    blob.extend(b"\x01\x02\x03 data");
+    fs::write(dir.path().join("blob.dat"), blob)?;
+
+    let list = |flag: &str| -> Result<String, Box<dyn std::error::Error>> {
+        let output = Command::new(target_debug())
+            .args(["list", "--simple", flag, "--"])
+            .arg(dir.path())
+            .output()?;
+        assert!(output.status.success());
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    let binary = list("--binary-only")?;
+    assert!(binary.contains("blob.dat"), "{binary}");
+    assert!(!binary.contains("notes.txt"), "{binary}");
+
+    let text = list("--text-only")?;
+    assert!(text.contains("notes.txt"), "{text}");
+    assert!(!text.contains("blob.dat"), "{text}");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_prints_lines_as_they_are_appended() -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir()?;
+    let log = dir.path().join("app.log");
+    fs::write(&log, "ERROR old\ninfo old\n")?;
+    let append = |text: &str| -> std::io::Result<()> {
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&log)?
+            .write_all(text.as_bytes())
+    };
+
+    let mut child = std::process::Command::new(target_debug())
+        .args(["watch", "ERROR", "--poll-interval", "20", "--after", "2"])
+        .arg(&log)
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let (sender, lines) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let next = || lines.recv_timeout(Duration::from_secs(10));
+
+    // `--after 2` looks back at the lines already there
+    let old = next();
+    append("info new\nERROR first")?;
+    std::thread::sleep(Duration::from_millis(100));
+    append(" half\nERROR second\n")?;
+    let first = next();
+    let second = next();
+
+    // A rotated log is followed from the start of the new file
+    fs::rename(&log, dir.path().join("app.log.1"))?;
+    fs::write(&log, "ERROR rotated\n")?;
+    let rotated = next();
+
+    child.kill()?;
+    child.wait()?;
+    let path = log.display();
+    assert_eq!(old?, format!("{path}:1:1: ERROR old"));
+    assert_eq!(first?, format!("{path}:4:1: ERROR first half"));
+    assert_eq!(second?, format!("{path}:5:1: ERROR second"));
+    assert_eq!(rotated?, format!("{path}:1:1: ERROR rotated"));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn no_messages_hides_per_file_errors() -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Few files are searched one by one, many in parallel
+    for plain_files in [1, 12] {
+        let dir = tempfile::tempdir()?;
+        for i in 0..plain_files {
+            fs::write(dir.path().join(format!("{i}.txt")), "a needle\n")?;
+        }
+        // Root can read anything, so a corrupt archive stands in when the
+        // unreadable file isn't
+        fs::write(dir.path().join("broken.gz"), "not gzip, needle\n")?;
+        let locked = dir.path().join("locked.txt");
+        fs::write(&locked, "needle\n")?;
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000))?;
+
+        let search = |extra: &[&str]| -> Result<std::process::Output, std::io::Error> {
+            Command::new(target_debug())
+                .args(["search", "needle", "--no-heading"])
+                .args(extra)
+                .arg("--")
+                .arg(dir.path())
+                .output()
+        };
+
+        let noisy = search(&[])?;
+        assert!(noisy.status.success());
+        let stderr = String::from_utf8(noisy.stderr)?;
+        assert!(stderr.contains("broken.gz"), "{plain_files}: {stderr}");
+
+        let quiet = search(&["--no-messages"])?;
+        assert!(quiet.status.success());
+        assert!(quiet.stderr.is_empty(), "{plain_files}: {:?}", quiet.stderr);
+        assert_eq!(quiet.stdout, noisy.stdout);
+    }
+    Ok(())
+}
@@ -142,13 +142,15 @@ fn test_file_io_performance() {
 
     let config = StreamingConfig {
         algorithm: SearchAlgorithm::BoyerMoore,
-        context_lines: 0,
+        context_before: 0,
+        context_after: 0,
         case_sensitive: true,
         invert_match: false,
         max_matches: None,
         timeout_per_file: None,
         chunk_size: 8192,
         buffer_size: 65536,
+        ..Default::default()
     };
 
     let pipeline = StreamingSearchPipeline::new(config);
@@ -182,13 +184,15 @@ fn test_parallel_processing_performance() {
 
     let config = StreamingConfig {
         algorithm: SearchAlgorithm::BoyerMoore,
-        context_lines: 0,
+        context_before: 0,
+        context_after: 0,
         case_sensitive: true,
         invert_match: false,
         max_matches: None,
         timeout_per_file: None,
         chunk_size: 8192,
         buffer_size: 65536,
+        ..Default::default()
     };
 
     let pipeline = StreamingSearchPipeline::new(config);
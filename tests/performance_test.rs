@@ -7,7 +7,7 @@ fn test_simd_search_performance() {
 
     let simd_search = SimdSearch::new(pattern);
     let boyer_moore = BoyerMoore::new(pattern);
-    let simple_search = SimpleSearch::new(pattern);
+    let simple_search = SimpleSearch::new_case_sensitive(pattern);
 
     let simd_matches = simd_search.search(text, pattern);
     let bm_matches = boyer_moore.search(text, pattern);
@@ -52,10 +52,10 @@ fn test_search_with_context() {
 fn test_algorithm_factory() {
     let pattern = "test";
 
-    let simd = SearchAlgorithmFactory::create(SearchAlgorithm::Simd, pattern);
-    let boyer_moore = SearchAlgorithmFactory::create(SearchAlgorithm::BoyerMoore, pattern);
-    let regex = SearchAlgorithmFactory::create(SearchAlgorithm::Regex, pattern);
-    let simple = SearchAlgorithmFactory::create(SearchAlgorithm::Simple, pattern);
+    let simd = SearchAlgorithmFactory::create(SearchAlgorithm::Simd, pattern).unwrap();
+    let boyer_moore = SearchAlgorithmFactory::create(SearchAlgorithm::BoyerMoore, pattern).unwrap();
+    let regex = SearchAlgorithmFactory::create(SearchAlgorithm::Regex, pattern).unwrap();
+    let simple = SearchAlgorithmFactory::create(SearchAlgorithm::Simple, pattern).unwrap();
 
     let text = "test text test";
 
@@ -1,5 +1,7 @@
 use proptest::prelude::*;
-use rfgrep::search_algorithms::{BoyerMoore, SimdSearch, SimpleSearch};
+use rfgrep::search_algorithms::{
+    BoyerMoore, FuzzySearch, SearchAlgorithm, SearchAlgorithmFactory, SimdSearch, SimpleSearch,
+};
 
 // Property: Search should always find the pattern if it exists
 proptest! {
@@ -244,3 +246,88 @@ proptest! {
         prop_assert!(results.is_empty(), "Should not find pattern longer than text");
     }
 }
+
+// Property: Boyer-Moore agrees with the naive search, including on
+// repetitive inputs that exercise the good-suffix shifts
+proptest! {
+    #[test]
+    fn test_boyer_moore_matches_simple(pattern in "[ab]{1,6}", text in "[ab]{0,200}") {
+        let bm = BoyerMoore::new(&pattern);
+        let simple = SimpleSearch::new_case_sensitive(&pattern);
+        prop_assert_eq!(bm.search(&text, &pattern), simple.search(&text, &pattern));
+    }
+}
+
+// Property: every exact algorithm reports the same non-overlapping matches,
+// even for runs of a repeated character where overlaps are possible
+proptest! {
+    #[test]
+    fn test_repeated_char_matches_agree(repeat in 1usize..5, text in "[ab]{0,100}") {
+        let pattern = "a".repeat(repeat);
+        let expected = SimpleSearch::new_case_sensitive(&pattern).search(&text, &pattern);
+        for algorithm in [
+            SearchAlgorithm::Simd,
+            SearchAlgorithm::BoyerMoore,
+            SearchAlgorithm::Regex,
+            SearchAlgorithm::AhoCorasick,
+        ] {
+            let searcher = SearchAlgorithmFactory::create(algorithm.clone(), &pattern).unwrap();
+            prop_assert_eq!(
+                searcher.search(&text, &pattern),
+                expected.clone(),
+                "{:?} disagrees for {:?} in {:?}",
+                algorithm,
+                pattern,
+                text
+            );
+        }
+        // Non-overlapping: a run of n 'a's holds n / repeat matches
+        let run_total: usize = text
+            .split('b')
+            .map(|run| run.len() / repeat)
+            .sum();
+        prop_assert_eq!(expected.len(), run_total);
+    }
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (diag + usize::from(ca != cb))
+                .min(row[j] + 1)
+                .min(above + 1);
+            diag = above;
+        }
+    }
+    row[b.len()]
+}
+
+// Property: every fuzzy match is a slice within the threshold, the reported
+// distance is the real edit distance, and matches never overlap
+proptest! {
+    #[test]
+    fn test_fuzzy_matches_are_within_distance(
+        pattern in "[abc]{3,6}",
+        text in "[abcd]{0,80}",
+        max_distance in 0usize..3,
+    ) {
+        let fuzzy = FuzzySearch::with_options(&pattern, max_distance, true);
+        let pattern_chars: Vec<char> = pattern.chars().collect();
+        let mut last_end = 0;
+        for m in fuzzy.find_matches(&text) {
+            let slice: Vec<char> = text[m.start..m.end].chars().collect();
+            prop_assert!(m.start >= last_end);
+            prop_assert!(m.distance <= max_distance);
+            prop_assert_eq!(levenshtein(&slice, &pattern_chars), m.distance);
+            last_end = m.end;
+        }
+        // Exact occurrences are always found
+        if max_distance < pattern.len() && text.contains(&pattern) {
+            prop_assert!(!fuzzy.find_matches(&text).is_empty());
+        }
+    }
+}
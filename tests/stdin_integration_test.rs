@@ -23,6 +23,40 @@ fn test_stdin_basic_search() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_stdin_label_replaces_stdin_name() -> Result<(), Box<dyn std::error::Error>> {
+    Command::new(target_debug())
+        .args(["search", "error", "--label", "foo.log"])
+        .write_stdin("ok\nan error here\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("foo.log:2:4: an error here"))
+        .stdout(predicate::str::contains("<stdin>").not());
+
+    Command::new(target_debug())
+        .args(["search", "error", "--label", "foo.log", "-l"])
+        .write_stdin("an error\n")
+        .assert()
+        .success()
+        .stdout("foo.log\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_stdin_line_numbers_are_one_based() -> Result<(), Box<dyn std::error::Error>> {
+    Command::new(target_debug())
+        .arg("search")
+        .arg("match")
+        .write_stdin("first match\nsecond\nthird\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<stdin>:1:"))
+        .stdout(predicate::str::contains("<stdin>:0:").not());
+
+    Ok(())
+}
+
 #[test]
 fn test_stdin_count_mode() -> Result<(), Box<dyn std::error::Error>> {
     Command::new(target_debug())
@@ -37,6 +71,74 @@ fn test_stdin_count_mode() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn test_stdin_count_matches_counts_occurrences() -> Result<(), Box<dyn std::error::Error>> {
+    Command::new(target_debug())
+        .arg("search")
+        .arg("test")
+        .arg("--count-matches")
+        .write_stdin("test test\nother\n")
+        .assert()
+        .success()
+        .stdout("2\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_stdin_line_range() -> Result<(), Box<dyn std::error::Error>> {
+    Command::new(target_debug())
+        .arg("search")
+        .arg("x")
+        .arg("--line-range")
+        .arg("2:3")
+        .arg("-c")
+        .write_stdin("x\nx\nx\nx\n")
+        .assert()
+        .success()
+        .stdout("2\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_stdin_passthru_echoes_every_line() -> Result<(), Box<dyn std::error::Error>> {
+    Command::new(target_debug())
+        .arg("search")
+        .arg("beta")
+        .arg("--passthru")
+        .write_stdin("alpha\nbeta\ngamma\n")
+        .assert()
+        .success()
+        .stdout("1-alpha\n2:beta\n3-gamma\n");
+
+    Command::new(target_debug())
+        .arg("search")
+        .arg("beta")
+        .arg("--passthru")
+        .arg("--invert-match")
+        .write_stdin("beta\n")
+        .assert()
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn test_stdin_format_template() -> Result<(), Box<dyn std::error::Error>> {
+    Command::new(target_debug())
+        .arg("search")
+        .arg(r"(?P<key>\w+)=(\d+)")
+        .arg("--format")
+        .arg("${key} -> $2")
+        .write_stdin("a=1 bb=22\nno pairs\nccc=333\n")
+        .assert()
+        .success()
+        .stdout("a -> 1\nbb -> 22\nccc -> 333\n");
+
+    Ok(())
+}
+
 #[test]
 fn test_stdin_files_with_matches() -> Result<(), Box<dyn std::error::Error>> {
     Command::new(target_debug())
@@ -87,7 +189,7 @@ fn test_stdin_empty_input() -> Result<(), Box<dyn std::error::Error>> {
         .arg("pattern")
         .write_stdin("")
         .assert()
-        .success();
+        .code(1);
 
     Ok(())
 }
@@ -99,7 +201,7 @@ fn test_stdin_no_matches() -> Result<(), Box<dyn std::error::Error>> {
         .arg("notfound")
         .write_stdin("some text without the pattern\n")
         .assert()
-        .success();
+        .code(1);
     // When stdout is piped (as in tests), quiet mode is enabled
     // so no output is expected when there are no matches
 
@@ -311,3 +413,48 @@ fn test_invert_match() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_files_from_stdin_searches_listed_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let first = temp_dir.path().join("first.rs");
+    let second = temp_dir.path().join("second.rs");
+    let unlisted = temp_dir.path().join("unlisted.rs");
+    for file in [&first, &second, &unlisted] {
+        fs::write(file, "let needle = 1;\n")?;
+    }
+
+    // The piped list is read as paths, never searched as content
+    let output = Command::new(target_debug())
+        .args(["search", "needle", "--files-from", "-"])
+        .write_stdin(format!(
+            "{}\n{}\nneedle.rs\n",
+            first.display(),
+            second.display()
+        ))
+        .output()?;
+    // Listed paths that don't exist are reported and skipped, and like grep
+    // the search fails once the rest are searched
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(
+        stdout.contains(&format!("{}:1:5:", first.display())),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!("{}:1:5:", second.display())),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("unlisted.rs"), "{stdout}");
+    assert!(!stdout.contains("<stdin>"), "{stdout}");
+    assert!(String::from_utf8(output.stderr)?.contains("needle.rs"));
+
+    Command::new(target_debug())
+        .args(["search", "needle", "--files-from0", "-", "-l"])
+        .write_stdin(format!("{}\0", second.display()))
+        .assert()
+        .success()
+        .stdout(format!("{}\n", second.display()));
+
+    Ok(())
+}
@@ -12,7 +12,30 @@ fn worker_timeout_enforced() {
         .arg("1")
         .arg("--")
         .arg("bench_data/file1.txt");
+    // The timed-out file reports nothing, so the search exits 1
     cmd.assert()
-        .success()
+        .code(1)
         .stdout(predicate::str::contains("No matches found"));
 }
+
+#[allow(deprecated)]
+#[test]
+fn worker_timeout_with_files_with_matches() {
+    let dir = tempfile::tempdir().unwrap();
+    for i in 0..8 {
+        std::fs::write(dir.path().join(format!("{i}.txt")), "pattern\n").unwrap();
+    }
+    let mut cmd = Command::cargo_bin("rfgrep").unwrap();
+    cmd.arg("search")
+        .arg("pattern")
+        .arg("-l")
+        .arg("--algorithm")
+        .arg("regex")
+        .arg("--timeout-per-file")
+        .arg("5")
+        .arg("--")
+        .arg(dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("7.txt"));
+}